@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::RtError;
+use crate::config;
+
+/// Named shell commands saved with `--bookmark`, stored as `name = "command"` lines.
+#[derive(Debug, Clone)]
+pub struct BookmarkStore {
+    path: PathBuf,
+}
+
+impl BookmarkStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Opens `bookmarks.toml` in the config directory, next to `config.toml`.
+    pub fn open_default() -> Result<Self, RtError> {
+        let dir = config::default_config_dir().ok_or_else(|| {
+            RtError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "could not determine config directory",
+            ))
+        })?;
+        Ok(Self::new(dir.join("bookmarks.toml")))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads all bookmarks, sorted by name; a missing file means none are saved.
+    pub fn load(&self) -> Result<BTreeMap<String, String>, RtError> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(err) => return Err(RtError::Io(err)),
+        };
+
+        toml::from_str(&content).map_err(|err| RtError::Config {
+            path: self.path.clone(),
+            message: err.message().to_string(),
+        })
+    }
+
+    /// Saves `command` under `name`, replacing any bookmark with the same name.
+    pub fn save(&self, name: &str, command: &str) -> Result<(), RtError> {
+        let mut bookmarks = self.load()?;
+        bookmarks.insert(name.to_string(), command.to_string());
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(RtError::Io)?;
+        }
+        let content = toml::to_string(&bookmarks)
+            .map_err(|err| RtError::Io(std::io::Error::other(err.to_string())))?;
+        std::fs::write(&self.path, content).map_err(RtError::Io)
+    }
+}
+
+/// Returns whether `name` can be saved and later run as `rt @name`.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('@')
+        && !name.chars().any(|ch| ch.is_whitespace() || ch.is_control())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn save_then_load_round_trips_bookmarks() {
+        let dir = tempdir().unwrap();
+        let store = BookmarkStore::new(dir.path().join("rt").join("bookmarks.toml"));
+
+        store
+            .save("deploy-prod", "just deploy prod --force")
+            .unwrap();
+        store.save("lint", "cargo clippy -- -D warnings").unwrap();
+        store.save("deploy-prod", "just deploy prod").unwrap();
+
+        let bookmarks = store.load().unwrap();
+        let entries: Vec<(&str, &str)> = bookmarks
+            .iter()
+            .map(|(name, command)| (name.as_str(), command.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("deploy-prod", "just deploy prod"),
+                ("lint", "cargo clippy -- -D warnings"),
+            ]
+        );
+    }
+
+    #[test]
+    fn load_returns_empty_when_file_is_missing() {
+        let dir = tempdir().unwrap();
+        let store = BookmarkStore::new(dir.path().join("bookmarks.toml"));
+        assert!(store.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn is_valid_name_rejects_blank_and_spaced_names() {
+        assert!(is_valid_name("deploy-prod"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("deploy prod"));
+        assert!(!is_valid_name("@deploy"));
+    }
+}
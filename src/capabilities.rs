@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+use serde::Deserialize;
+
+use crate::detect::{Runner, runner_name};
+use crate::exec::{WhichResolver, base_command};
+
+/// A runner tool's `major.minor.patch` version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Finds the first `X.Y` or `X.Y.Z` token in `--version` output, e.g. `just 1.40.0`.
+    pub fn parse_from_output(output: &str) -> Option<Self> {
+        output.split_whitespace().find_map(|token| {
+            let token = token.trim_start_matches('v');
+            let mut parts = token
+                .split(['.', '-', '+'])
+                .map(|part| part.parse::<u32>().ok());
+            let major = parts.next()??;
+            let minor = parts.next()??;
+            let patch = parts.next().flatten().unwrap_or(0);
+            Some(Self::new(major, minor, patch))
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A version constraint such as `>=1.14` or `>=3.20, <4`; every comparator must hold.
+///
+/// Missing minor or patch numbers count as zero, and a bare version means `=`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct VersionRequirement {
+    source: String,
+    comparators: Vec<(Comparison, Version)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Exact,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl VersionRequirement {
+    pub fn matches(&self, version: Version) -> bool {
+        self.comparators
+            .iter()
+            .all(|(comparison, bound)| match comparison {
+                Comparison::Exact => version == *bound,
+                Comparison::Greater => version > *bound,
+                Comparison::GreaterOrEqual => version >= *bound,
+                Comparison::Less => version < *bound,
+                Comparison::LessOrEqual => version <= *bound,
+            })
+    }
+}
+
+impl std::str::FromStr for VersionRequirement {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let comparators = value
+            .split(',')
+            .map(|part| {
+                let part = part.trim();
+                let (comparison, version) = [
+                    (">=", Comparison::GreaterOrEqual),
+                    ("<=", Comparison::LessOrEqual),
+                    (">", Comparison::Greater),
+                    ("<", Comparison::Less),
+                    ("=", Comparison::Exact),
+                ]
+                .into_iter()
+                .find_map(|(prefix, comparison)| {
+                    part.strip_prefix(prefix)
+                        .map(|version| (comparison, version))
+                })
+                .unwrap_or((Comparison::Exact, part));
+                parse_requirement_version(version.trim())
+                    .map(|version| (comparison, version))
+                    .ok_or_else(|| format!("invalid version requirement `{value}`"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            source: value.trim().to_string(),
+            comparators,
+        })
+    }
+}
+
+impl TryFrom<String> for VersionRequirement {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for VersionRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn parse_requirement_version(value: &str) -> Option<Version> {
+    let mut parts = value.trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    parts
+        .next()
+        .is_none()
+        .then(|| Version::new(major, minor, patch))
+}
+
+/// A configured version requirement checked against the installed tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequirementCheck {
+    pub runner: Runner,
+    pub requirement: VersionRequirement,
+    /// `None` when the tool is missing or its version couldn't be read.
+    pub found: Option<Version>,
+}
+
+impl RequirementCheck {
+    pub fn is_satisfied(&self) -> bool {
+        self.found
+            .is_some_and(|version| self.requirement.matches(version))
+    }
+}
+
+/// Checks each runner that has a requirement (keyed by runner name) against the probed version.
+pub fn check_requirements(
+    runners: &[Runner],
+    requirements: &BTreeMap<String, VersionRequirement>,
+    probe: &dyn Fn(Runner) -> Option<Version>,
+) -> Vec<RequirementCheck> {
+    runners
+        .iter()
+        .filter_map(|runner| {
+            let requirement = requirements.get(runner_name(*runner))?;
+            Some(RequirementCheck {
+                runner: *runner,
+                requirement: requirement.clone(),
+                found: probe(*runner),
+            })
+        })
+        .collect()
+}
+
+/// What rt knows about an installed runner tool.
+///
+/// Version-gated features belong here as flags derived from `version`, once a feature consults
+/// them; none does yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunnerCapabilities {
+    /// `None` when the tool is missing or its version couldn't be read.
+    pub version: Option<Version>,
+}
+
+/// Returns the runner's capabilities, probing `--version` at most once per process.
+pub fn capabilities(runner: Runner) -> RunnerCapabilities {
+    static CACHE: OnceLock<Mutex<HashMap<Runner, RunnerCapabilities>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(cached) = cache
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&runner).copied())
+    {
+        return cached;
+    }
+
+    let probed = RunnerCapabilities {
+        version: probe_version(runner),
+    };
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(runner, probed);
+    }
+    probed
+}
+
+fn probe_version(runner: Runner) -> Option<Version> {
+    // Procfile entries and package scripts have no single tool to ask.
+    if matches!(runner, Runner::Procfile | Runner::PackageJson) {
+        return None;
+    }
+    let output = base_command(runner, &WhichResolver)
+        .ok()?
+        .arg("--version")
+        .output()
+        .ok()?;
+    Version::parse_from_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_from_output_reads_common_version_lines() {
+        assert_eq!(
+            Version::parse_from_output("just 1.40.0\n"),
+            Some(Version::new(1, 40, 0))
+        );
+        assert_eq!(
+            Version::parse_from_output("Task version: v3.38.0 (h1:abc)"),
+            Some(Version::new(3, 38, 0))
+        );
+        assert_eq!(
+            Version::parse_from_output("GNU Make 4.3\nBuilt for x86_64"),
+            Some(Version::new(4, 3, 0))
+        );
+        assert_eq!(
+            Version::parse_from_output("2025.1.6-beta linux-x64"),
+            Some(Version::new(2025, 1, 6))
+        );
+        assert_eq!(Version::parse_from_output("unknown"), None);
+    }
+
+    #[test]
+    fn version_requirement_parses_comparators() {
+        let requirement: VersionRequirement = ">=1.14".parse().unwrap();
+        assert!(requirement.matches(Version::new(1, 14, 0)));
+        assert!(requirement.matches(Version::new(2, 0, 0)));
+        assert!(!requirement.matches(Version::new(1, 13, 9)));
+
+        let range: VersionRequirement = ">=3.20, <4".parse().unwrap();
+        assert!(range.matches(Version::new(3, 38, 0)));
+        assert!(!range.matches(Version::new(4, 0, 0)));
+
+        let exact: VersionRequirement = "1.40.1".parse().unwrap();
+        assert!(exact.matches(Version::new(1, 40, 1)));
+        assert!(!exact.matches(Version::new(1, 40, 2)));
+        assert_eq!(exact.to_string(), "1.40.1");
+
+        assert!(">=".parse::<VersionRequirement>().is_err());
+        assert!(">=1.x".parse::<VersionRequirement>().is_err());
+        assert!("1.2.3.4".parse::<VersionRequirement>().is_err());
+    }
+
+    #[test]
+    fn check_requirements_reports_satisfied_and_unsatisfied_runners() {
+        let requirements = BTreeMap::from([
+            ("just".to_string(), ">=1.14".parse().unwrap()),
+            ("task".to_string(), ">=3.30".parse().unwrap()),
+            ("mask".to_string(), ">=0.11".parse().unwrap()),
+            ("mise".to_string(), ">=2024.1".parse().unwrap()),
+        ]);
+        let probe = |runner: Runner| match runner {
+            Runner::Justfile => Some(Version::new(1, 40, 0)),
+            Runner::Taskfile => Some(Version::new(3, 20, 0)),
+            _ => None,
+        };
+        let checks = check_requirements(
+            &[
+                Runner::Justfile,
+                Runner::Taskfile,
+                Runner::Maskfile,
+                Runner::Makefile,
+            ],
+            &requirements,
+            &probe,
+        );
+
+        let summary: Vec<(Runner, Option<Version>, bool)> = checks
+            .iter()
+            .map(|check| (check.runner, check.found, check.is_satisfied()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Runner::Justfile, Some(Version::new(1, 40, 0)), true),
+                (Runner::Taskfile, Some(Version::new(3, 20, 0)), false),
+                (Runner::Maskfile, None, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,551 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::RtError;
+use crate::capabilities::VersionRequirement;
+use crate::detect::Runner;
+
+/// User configuration read from `config.toml` and the project's `.rt.toml`; every section is
+/// optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Behave as if `--args` were always passed (`--no-args` still wins).
+    pub prompt_args: bool,
+    /// Runner used when `--runner` isn't passed, as if it were; `.rt.toml` only.
+    pub runner: Option<Runner>,
+    /// Task run by a bare `rt` instead of opening the picker; `.rt.toml` only.
+    pub default_task: Option<String>,
+    /// Short names for tasks, e.g. `{ t = "test" }` makes `rt t` run `test`.
+    pub aliases: BTreeMap<String, String>,
+    pub confirm: ConfirmConfig,
+    pub ui: UiConfig,
+    pub timeout: TimeoutConfig,
+    pub mise: MiseConfig,
+    pub package_json: PackageJsonConfig,
+    pub history: HistoryConfig,
+    pub runners: RunnersConfig,
+    /// Tool version requirements by runner name, checked by `--runner-env-check`.
+    pub require: BTreeMap<String, VersionRequirement>,
+}
+
+/// Which runners detection finds, and which one wins when several are present.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct RunnersConfig {
+    /// Runners preferred over the rest, most preferred first; unlisted ones keep the default order.
+    pub priority: Vec<Runner>,
+    /// Runners never detected, as if their files weren't there; `--runner` still picks them.
+    pub exclude: Vec<Runner>,
+}
+
+/// What rt records about each run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Store a hash of each command's arguments instead of the plaintext; this obscures commands
+    /// but doesn't protect them (see `history::command_hash`).
+    pub hash_commands: bool,
+    /// Skip recording a run whose command and directory match the newest record.
+    pub dedupe: bool,
+}
+
+/// How rt cooperates with mise-managed tool versions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct MiseConfig {
+    /// Run other runners through `mise exec --` when the project has a `mise.toml`.
+    pub activate: bool,
+}
+
+/// How rt treats `package.json` projects.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct PackageJsonConfig {
+    /// List and run the scripts of workspace packages as `<package>:<script>`.
+    pub workspaces: bool,
+}
+
+/// Time limits, in seconds, after which a running task is killed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    /// Limit for tasks without their own entry; no limit when unset.
+    pub default: Option<u64>,
+    /// Per-task limits that override `default`, e.g. `{ "integration-test" = 300 }`.
+    pub tasks: BTreeMap<String, u64>,
+}
+
+impl TimeoutConfig {
+    /// Returns the time limit for `task`: its own entry first, then the default.
+    pub fn for_task(&self, task: &str) -> Option<Duration> {
+        self.tasks
+            .get(task)
+            .copied()
+            .or(self.default)
+            .map(Duration::from_secs)
+    }
+}
+
+/// Picker settings applied to every interactive list.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Options shown per page; scales with the terminal height when unset.
+    pub page_size: Option<usize>,
+    /// Navigate with `j`/`k` instead of typing to filter.
+    pub vim_keys: bool,
+    /// Show task names only, without descriptions (`--compact`).
+    pub compact: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+    /// Glob patterns (`*`, `?`) of task names that need confirmation with `--confirm-destructive`.
+    pub patterns: Vec<String>,
+    /// Substrings of task names that need confirmation even without the flag, from
+    /// `RT_CONFIRM_PATTERNS`, matched literally.
+    #[serde(skip)]
+    pub always: Vec<String>,
+}
+
+const DEFAULT_CONFIRM_PATTERNS: [&str; 5] = ["deploy*", "release*", "*prod*", "clean", "reset"];
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        Self {
+            patterns: DEFAULT_CONFIRM_PATTERNS
+                .iter()
+                .map(|pattern| (*pattern).to_string())
+                .collect(),
+            always: Vec::new(),
+        }
+    }
+}
+
+impl ConfirmConfig {
+    /// Returns whether running `task` should ask for confirmation first: `always` substrings ask
+    /// regardless, and `patterns` only with `--confirm-destructive`.
+    pub fn requires_confirmation(&self, task: &str, confirm_destructive: bool) -> bool {
+        let always = self.always.iter().any(|part| task.contains(part.as_str()));
+        let matches = || {
+            self.patterns
+                .iter()
+                .any(|pattern| glob_match(pattern, task))
+        };
+        always || (confirm_destructive && matches())
+    }
+}
+
+/// Splits `RT_CONFIRM_PATTERNS` into its comma-separated substrings.
+fn split_substrings(patterns: &str) -> Vec<String> {
+    patterns
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Project config file, looked up in the working directory and its parents.
+const PROJECT_CONFIG_FILE: &str = ".rt.toml";
+
+/// Keys that only make sense for one project, so the user config can't set them for all.
+const PROJECT_ONLY_KEYS: [&str; 2] = ["runner", "default_task"];
+
+/// Loads the user config with the nearest `.rt.toml` at or above `cwd` laid over it, returning
+/// defaults when neither file exists.
+///
+/// `RT_CONFIRM_PATTERNS` fills `confirm.always`, which has no config file key.
+pub fn load(cwd: &Path) -> Result<Config, RtError> {
+    let mut config = load_layers(default_config_path().as_deref(), cwd)?;
+    if let Ok(patterns) = std::env::var("RT_CONFIRM_PATTERNS") {
+        config.confirm.always = split_substrings(&patterns);
+    }
+    Ok(config)
+}
+
+/// Merges the project config over the user config at `user_path`: a key set in both takes the
+/// project's value, and tables such as `[timeout.tasks]` are merged key by key.
+///
+/// The user config can't set the [`PROJECT_ONLY_KEYS`].
+fn load_layers(user_path: Option<&Path>, cwd: &Path) -> Result<Config, RtError> {
+    let mut merged = match user_path {
+        Some(path) => read_table(path)?,
+        None => toml::Table::new(),
+    };
+    if let Some(key) = PROJECT_ONLY_KEYS
+        .iter()
+        .find(|key| merged.contains_key(**key))
+    {
+        return Err(RtError::Config {
+            path: user_path.map(Path::to_path_buf).unwrap_or_default(),
+            message: format!("`{key}` can only be set in {PROJECT_CONFIG_FILE}"),
+        });
+    }
+    let project_path = cwd
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file());
+    if let Some(path) = &project_path {
+        merge_tables(&mut merged, read_table(path)?);
+    }
+
+    toml::Value::Table(merged)
+        .try_into()
+        .map_err(|err: toml::de::Error| RtError::Config {
+            path: project_path
+                .or(user_path.map(Path::to_path_buf))
+                .unwrap_or_default(),
+            message: err.message().to_string(),
+        })
+}
+
+/// Reads a config file as a TOML table, checked against `Config` so errors name the file; a
+/// missing file is an empty table.
+fn read_table(path: &Path) -> Result<toml::Table, RtError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(toml::Table::new()),
+        Err(err) => return Err(RtError::Io(err)),
+    };
+
+    let config_error = |message: &str| RtError::Config {
+        path: path.to_path_buf(),
+        message: message.to_string(),
+    };
+    toml::from_str::<Config>(&content).map_err(|err| config_error(err.message()))?;
+    toml::from_str(&content).map_err(|err| config_error(err.message()))
+}
+
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    Some(default_config_dir()?.join("config.toml"))
+}
+
+/// Directory holding rt's user-level files (`config.toml`, `bookmarks.toml`).
+pub fn default_config_dir() -> Option<PathBuf> {
+    let xdg_config_home = env::var_os("XDG_CONFIG_HOME").map(PathBuf::from);
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let app_data = env::var_os("APPDATA").map(PathBuf::from);
+    config_dir_for_platform(
+        xdg_config_home.as_deref(),
+        home.as_deref(),
+        app_data.as_deref(),
+        cfg!(windows),
+    )
+}
+
+fn config_dir_for_platform(
+    xdg_config_home: Option<&Path>,
+    home: Option<&Path>,
+    app_data: Option<&Path>,
+    is_windows: bool,
+) -> Option<PathBuf> {
+    let base = match (xdg_config_home, is_windows) {
+        (Some(base), _) => base.to_path_buf(),
+        (None, true) => app_data?.to_path_buf(),
+        (None, false) => home?.join(".config"),
+    };
+    Some(base.join("rt"))
+}
+
+/// Matches `name` against a glob supporting `*` (any run) and `?` (any one char).
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Loads the user config at `path` alone; its temporary directory holds no `.rt.toml`.
+    fn load_from_path(path: &Path) -> Result<Config, RtError> {
+        load_layers(Some(path), path.parent().unwrap())
+    }
+
+    #[test]
+    fn project_config_overrides_the_user_config() {
+        let user = tempdir().unwrap();
+        let user_path = user.path().join("config.toml");
+        std::fs::write(
+            &user_path,
+            "[aliases]\nt = \"test\"\nb = \"build\"\n[timeout]\ndefault = 60\n",
+        )
+        .unwrap();
+        let project = tempdir().unwrap();
+        std::fs::write(
+            project.path().join(".rt.toml"),
+            "runner = \"make\"\ndefault_task = \"dev\"\n[aliases]\nt = \"test-all\"\n",
+        )
+        .unwrap();
+        let nested = project.path().join("src");
+        std::fs::create_dir(&nested).unwrap();
+
+        let config = load_layers(Some(&user_path), &nested).unwrap();
+        assert_eq!(config.runner, Some(Runner::Makefile));
+        assert_eq!(config.default_task.as_deref(), Some("dev"));
+        assert_eq!(config.aliases["t"], "test-all");
+        assert_eq!(config.aliases["b"], "build");
+        assert_eq!(config.timeout.default, Some(60));
+
+        std::fs::write(project.path().join(".rt.toml"), "runner = \"gradle\"\n").unwrap();
+        match load_layers(Some(&user_path), &nested).unwrap_err() {
+            RtError::Config { path, .. } => assert_eq!(path, project.path().join(".rt.toml")),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn user_config_rejects_project_only_keys() {
+        let user = tempdir().unwrap();
+        let user_path = user.path().join("config.toml");
+        for content in ["runner = \"just\"\n", "default_task = \"dev\"\n"] {
+            std::fs::write(&user_path, content).unwrap();
+            match load_from_path(&user_path).unwrap_err() {
+                RtError::Config { path, .. } => assert_eq!(path, user_path),
+                other => panic!("unexpected error: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn split_substrings_match_comma_separated_parts() {
+        let confirm = ConfirmConfig {
+            patterns: Vec::new(),
+            always: split_substrings("deploy, release, db[prod]"),
+        };
+        assert!(confirm.requires_confirmation("deploy-prod", false));
+        assert!(confirm.requires_confirmation("pre-release", false));
+        assert!(!confirm.requires_confirmation("build", false));
+        // Glob characters are matched literally.
+        assert!(confirm.requires_confirmation("db[prod]-reset", false));
+        assert!(!confirm.requires_confirmation("dbp", false));
+        assert!(split_substrings("").is_empty());
+        assert!(split_substrings(" , ").is_empty());
+    }
+
+    #[test]
+    fn glob_match_supports_wildcards() {
+        assert!(glob_match("deploy*", "deploy"));
+        assert!(glob_match("deploy*", "deploy-prod"));
+        assert!(glob_match("*prod*", "db-prod-reset"));
+        assert!(glob_match("clean", "clean"));
+        assert!(glob_match("te?t", "test"));
+        assert!(!glob_match("clean", "clean-all"));
+        assert!(!glob_match("deploy*", "predeploy"));
+    }
+
+    #[test]
+    fn confirm_patterns_only_match_risky_tasks() {
+        let confirm = ConfirmConfig {
+            patterns: vec!["deploy*".to_string(), "*prod*".to_string()],
+            always: Vec::new(),
+        };
+        assert!(confirm.requires_confirmation("deploy-staging", true));
+        assert!(confirm.requires_confirmation("seed-prod", true));
+        assert!(!confirm.requires_confirmation("build", true));
+        assert!(!confirm.requires_confirmation("test", true));
+        assert!(!confirm.requires_confirmation("deploy-staging", false));
+    }
+
+    #[test]
+    fn load_from_path_reads_confirm_patterns() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "prompt_args = true\n\n[confirm]\npatterns = [\"ship\"]\n",
+        )
+        .unwrap();
+
+        let config = load_from_path(&path).unwrap();
+        assert!(config.prompt_args);
+        assert_eq!(config.confirm.patterns, vec!["ship".to_string()]);
+    }
+
+    #[test]
+    fn load_from_path_reads_ui_settings() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[ui]\npage_size = 20\nvim_keys = true\ncompact = true\n",
+        )
+        .unwrap();
+
+        let config = load_from_path(&path).unwrap();
+        assert_eq!(
+            config.ui,
+            UiConfig {
+                page_size: Some(20),
+                vim_keys: true,
+                compact: true,
+            }
+        );
+        assert_eq!(config.confirm, ConfirmConfig::default());
+    }
+
+    #[test]
+    fn load_from_path_reads_timeouts_with_task_overrides() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[timeout]\ndefault = 60\ntasks = { \"integration-test\" = 300 }\n",
+        )
+        .unwrap();
+
+        let timeout = load_from_path(&path).unwrap().timeout;
+        assert_eq!(
+            timeout.for_task("integration-test"),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(timeout.for_task("lint"), Some(Duration::from_secs(60)));
+        assert_eq!(TimeoutConfig::default().for_task("lint"), None);
+    }
+
+    #[test]
+    fn load_from_path_reads_version_requirements() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[require]\njust = \">=1.14\"\n").unwrap();
+
+        let require = load_from_path(&path).unwrap().require;
+        assert_eq!(require["just"].to_string(), ">=1.14");
+
+        std::fs::write(&path, "[require]\njust = \"recent\"\n").unwrap();
+        match load_from_path(&path).unwrap_err() {
+            RtError::Config { message, .. } => assert!(message.contains("recent"), "{message}"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn load_from_path_reads_runner_priority_and_exclusions() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[runners]\npriority = [\"make\", \"just\"]\nexclude = [\"cargo-make\"]\n",
+        )
+        .unwrap();
+
+        let runners = load_from_path(&path).unwrap().runners;
+        assert_eq!(runners.priority, vec![Runner::Makefile, Runner::Justfile]);
+        assert_eq!(runners.exclude, vec![Runner::CargoMake]);
+
+        std::fs::write(&path, "[runners]\nexclude = [\"gradle\"]\n").unwrap();
+        match load_from_path(&path).unwrap_err() {
+            RtError::Config { message, .. } => assert!(message.contains("gradle"), "{message}"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn load_from_path_reads_mise_activation() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[mise]\nactivate = true\n").unwrap();
+
+        assert!(load_from_path(&path).unwrap().mise.activate);
+        assert!(!Config::default().mise.activate);
+    }
+
+    #[test]
+    fn load_from_path_reads_history_hashing() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[history]\nhash_commands = true\ndedupe = true\n").unwrap();
+
+        let history = load_from_path(&path).unwrap().history;
+        assert!(history.hash_commands);
+        assert!(history.dedupe);
+        assert_eq!(Config::default().history, HistoryConfig::default());
+    }
+
+    #[test]
+    fn load_from_path_defaults_when_missing() {
+        let dir = tempdir().unwrap();
+        let config = load_from_path(&dir.path().join("config.toml")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn load_from_path_rejects_invalid_toml() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[confirm]\npatterns = 1\n").unwrap();
+
+        match load_from_path(&path).unwrap_err() {
+            RtError::Config { path: err_path, .. } => assert_eq!(err_path, path),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn config_dir_prefers_xdg_then_home() {
+        assert_eq!(
+            config_dir_for_platform(
+                Some(Path::new("/tmp/xdg")),
+                Some(Path::new("/tmp/home")),
+                None,
+                false
+            ),
+            Some(PathBuf::from("/tmp/xdg/rt"))
+        );
+        assert_eq!(
+            config_dir_for_platform(None, Some(Path::new("/tmp/home")), None, false),
+            Some(PathBuf::from("/tmp/home/.config/rt"))
+        );
+        assert_eq!(
+            config_dir_for_platform(None, None, Some(Path::new("C:/AppData")), true),
+            Some(PathBuf::from("C:/AppData/rt"))
+        );
+        assert_eq!(config_dir_for_platform(None, None, None, false), None);
+    }
+}
@@ -1,8 +1,10 @@
 use std::path::{Path, PathBuf};
 
 use crate::RtError;
+use crate::config::RunnersConfig;
+use crate::exec::ToolResolver;
 
-const RUNNER_CANDIDATES: [(&str, Runner); 15] = [
+const RUNNER_CANDIDATES: [(&str, Runner); 27] = [
     ("Justfile", Runner::Justfile),
     ("justfile", Runner::Justfile),
     ("Taskfile.yml", Runner::Taskfile),
@@ -17,10 +19,25 @@ const RUNNER_CANDIDATES: [(&str, Runner); 15] = [
     ("Maskfile.md", Runner::Maskfile),
     ("mise.toml", Runner::Mise),
     ("Makefile.toml", Runner::CargoMake),
+    // Same lookup order as GNU make.
+    ("GNUmakefile", Runner::Makefile),
+    ("makefile", Runner::Makefile),
     ("Makefile", Runner::Makefile),
+    // Automake source, for projects where `./configure` hasn't generated a Makefile yet.
+    ("Makefile.am", Runner::Makefile),
+    ("Rakefile", Runner::Rake),
+    ("rakefile", Runner::Rake),
+    ("Earthfile", Runner::Earthly),
+    ("magefile.go", Runner::Mage),
+    ("Procfile", Runner::Procfile),
+    ("deno.json", Runner::Deno),
+    ("deno.jsonc", Runner::Deno),
+    ("composer.json", Runner::Composer),
+    ("package.json", Runner::PackageJson),
 ];
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, serde::Deserialize)]
+#[serde(try_from = "String")]
 pub enum Runner {
     Justfile,
     Taskfile,
@@ -28,23 +45,172 @@ pub enum Runner {
     Mise,
     CargoMake,
     Makefile,
+    Rake,
+    Earthly,
+    Mage,
+    Procfile,
+    Deno,
+    Composer,
+    PackageJson,
+}
+
+const RUNNERS: [Runner; 13] = [
+    Runner::Justfile,
+    Runner::Taskfile,
+    Runner::Maskfile,
+    Runner::Mise,
+    Runner::CargoMake,
+    Runner::Makefile,
+    Runner::Rake,
+    Runner::Earthly,
+    Runner::Mage,
+    Runner::Procfile,
+    Runner::Deno,
+    Runner::Composer,
+    Runner::PackageJson,
+];
+
+impl std::str::FromStr for Runner {
+    type Err = String;
+
+    /// Parses a runner by its display name (`just`, `task`, `cargo-make`, ...).
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        RUNNERS
+            .into_iter()
+            .find(|runner| runner_name(*runner) == value)
+            .ok_or_else(|| {
+                let names: Vec<&str> = RUNNERS.into_iter().map(runner_name).collect();
+                format!(
+                    "unknown runner `{value}`, expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl TryFrom<String> for Runner {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Detection {
     pub runner: Runner,
     pub runner_file: PathBuf,
+    /// The manager that runs `package.json` scripts; `None` for every other runner.
+    pub package_manager: Option<PackageManager>,
+    /// The file was given with `--file` rather than found, so the runner is pointed at it.
+    pub explicit_file: bool,
+    /// Whether `package.json` workspace scripts are listed and run, as `[package_json]` sets.
+    pub workspaces: bool,
 }
 
-/// Detects the task runner used in the given directory.
-pub fn detect_runner(dir_path: &Path) -> Result<Detection, RtError> {
-    for (name, runner) in RUNNER_CANDIDATES {
+impl Detection {
+    fn new(runner: Runner, runner_file: PathBuf) -> Self {
+        let package_manager = (runner == Runner::PackageJson)
+            .then(|| runner_file.parent().map(detect_package_manager))
+            .flatten();
+        Detection {
+            runner,
+            runner_file,
+            package_manager,
+            explicit_file: false,
+            workspaces: false,
+        }
+    }
+
+    /// Whether this is an automake `Makefile.am`, whose targets are read from the source.
+    pub fn is_automake_source(&self) -> bool {
+        self.runner == Runner::Makefile
+            && self
+                .runner_file
+                .file_name()
+                .is_some_and(|name| name == "Makefile.am")
+    }
+
+    /// Returns the program that runs this detection's tasks.
+    pub fn command(&self) -> &'static str {
+        match self.package_manager {
+            Some(manager) => manager.command(),
+            None => runner_command(self.runner),
+        }
+    }
+}
+
+/// The JavaScript package manager that runs `package.json` scripts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PackageManager {
+    Npm,
+    Pnpm,
+    Yarn,
+    Bun,
+}
+
+impl PackageManager {
+    pub fn command(self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Bun => "bun",
+        }
+    }
+}
+
+const LOCKFILES: [(&str, PackageManager); 5] = [
+    ("pnpm-lock.yaml", PackageManager::Pnpm),
+    ("yarn.lock", PackageManager::Yarn),
+    // Bun 1.2 writes a text `bun.lock`; older versions the binary `bun.lockb`.
+    ("bun.lock", PackageManager::Bun),
+    ("bun.lockb", PackageManager::Bun),
+    ("package-lock.json", PackageManager::Npm),
+];
+
+/// Picks the package manager from the lockfiles in `dir`, defaulting to npm.
+///
+/// When several lockfiles exist, the most recently modified one wins.
+pub fn detect_package_manager(dir: &Path) -> PackageManager {
+    let mut newest: Option<(std::time::SystemTime, PackageManager)> = None;
+    for (name, manager) in LOCKFILES {
+        let Ok(metadata) = std::fs::metadata(dir.join(name)) else {
+            continue;
+        };
+        let modified = metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
+        if newest.is_none_or(|(time, _)| modified > time) {
+            newest = Some((modified, manager));
+        }
+    }
+    newest.map_or(PackageManager::Npm, |(_, manager)| manager)
+}
+
+/// Moves the `priority` runners to the front, in their listed order, and drops `exclude`d ones.
+///
+/// The sort is stable, so a runner's file names keep their lookup order and unlisted runners
+/// their default priority.
+fn ordered_candidates(preferences: &RunnersConfig) -> Vec<(&'static str, Runner)> {
+    let mut candidates: Vec<(&'static str, Runner)> = RUNNER_CANDIDATES
+        .into_iter()
+        .filter(|(_, runner)| !preferences.exclude.contains(runner))
+        .collect();
+    candidates.sort_by_key(|(_, runner)| {
+        preferences
+            .priority
+            .iter()
+            .position(|preferred| preferred == runner)
+            .unwrap_or(usize::MAX)
+    });
+    candidates
+}
+
+/// Detects the task runner used in the given directory, in the order `preferences` sets.
+pub fn detect_runner(dir_path: &Path, preferences: &RunnersConfig) -> Result<Detection, RtError> {
+    for (name, runner) in ordered_candidates(preferences) {
         let path = dir_path.join(name);
         if path.is_file() {
-            return Ok(Detection {
-                runner,
-                runner_file: path,
-            });
+            return Ok(Detection::new(runner, path));
         }
     }
 
@@ -53,22 +219,33 @@ pub fn detect_runner(dir_path: &Path) -> Result<Detection, RtError> {
     })
 }
 
+/// Returns the nearest directory at or above `start` that contains a runner file.
+pub fn find_runner_dir(start: &Path, preferences: &RunnersConfig) -> Result<PathBuf, RtError> {
+    start
+        .ancestors()
+        .find(|dir| detect_runner(dir, preferences).is_ok())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| RtError::NoRunnerFound {
+            cwd: start.to_path_buf(),
+        })
+}
+
 /// Detects all available runners in the given directory, in priority order.
-pub fn detect_runners(dir_path: &Path) -> Result<Vec<Detection>, RtError> {
+pub fn detect_runners(
+    dir_path: &Path,
+    preferences: &RunnersConfig,
+) -> Result<Vec<Detection>, RtError> {
     let mut seen = std::collections::HashSet::new();
     let mut detections = Vec::new();
 
-    for (name, runner) in RUNNER_CANDIDATES {
+    for (name, runner) in ordered_candidates(preferences) {
         if seen.contains(&runner) {
             continue;
         }
         let path = dir_path.join(name);
         if path.is_file() {
             seen.insert(runner);
-            detections.push(Detection {
-                runner,
-                runner_file: path,
-            });
+            detections.push(Detection::new(runner, path));
         }
     }
 
@@ -81,6 +258,189 @@ pub fn detect_runners(dir_path: &Path) -> Result<Vec<Detection>, RtError> {
     }
 }
 
+/// Detects `runner` in the given directory, ignoring every other runner file.
+///
+/// `[runners] exclude` doesn't apply, since the runner was asked for by name.
+pub fn detect_named_runner(dir_path: &Path, runner: Runner) -> Result<Detection, RtError> {
+    RUNNER_CANDIDATES
+        .into_iter()
+        .filter(|(_, candidate)| *candidate == runner)
+        .map(|(name, _)| dir_path.join(name))
+        .find(|path| path.is_file())
+        .map(|path| Detection::new(runner, path))
+        .ok_or_else(|| RtError::RunnerNotDetected {
+            runner: runner_name(runner),
+            cwd: dir_path.to_path_buf(),
+        })
+}
+
+/// Builds a detection for a runner file given with `--file` instead of found in a directory.
+///
+/// The runner comes from `runner`, else from a known file name, else it's just, the only runner
+/// that can be pointed at another file for now. The path is made absolute because tasks are listed
+/// from the file's directory but run from the current one.
+pub fn detect_file(path: &Path, runner: Option<Runner>) -> Result<Detection, RtError> {
+    let runner = runner
+        .or_else(|| {
+            let name = path.file_name()?;
+            RUNNER_CANDIDATES
+                .into_iter()
+                .find(|(candidate, _)| name == *candidate)
+                .map(|(_, runner)| runner)
+        })
+        .unwrap_or(Runner::Justfile);
+    if runner != Runner::Justfile {
+        return Err(RtError::FileUnsupported {
+            runner: runner_name(runner),
+        });
+    }
+    if !path.is_file() {
+        return Err(RtError::RunnerFileNotFound {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let runner_file = std::path::absolute(path).map_err(RtError::Io)?;
+    Ok(Detection {
+        explicit_file: true,
+        ..Detection::new(runner, runner_file)
+    })
+}
+
+/// Several files in one directory for the same runner, of which rt reads only the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateRunnerFiles {
+    pub runner: Runner,
+    /// The file `detect_runner` picks.
+    pub used: PathBuf,
+    /// Files for the same runner that rt ignores.
+    pub ignored: Vec<PathBuf>,
+}
+
+/// Finds runners with more than one file in `dir_path`, such as `justfile` next to `Justfile`.
+///
+/// File names are matched exactly against the directory listing, so a case-insensitive file
+/// system doesn't report one file under two spellings. `Makefile.am` isn't a duplicate of the
+/// Makefile `./configure` generates from it.
+pub fn duplicate_runner_files(dir_path: &Path) -> Vec<DuplicateRunnerFiles> {
+    let Ok(entries) = std::fs::read_dir(dir_path) else {
+        return Vec::new();
+    };
+    let names: std::collections::HashSet<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|kind| !kind.is_dir()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+
+    let mut duplicates: Vec<DuplicateRunnerFiles> = Vec::new();
+    for (name, runner) in RUNNER_CANDIDATES {
+        if name == "Makefile.am" || !names.contains(name) {
+            continue;
+        }
+        let path = dir_path.join(name);
+        match duplicates.iter_mut().find(|found| found.runner == runner) {
+            Some(found) => found.ignored.push(path),
+            None => duplicates.push(DuplicateRunnerFiles {
+                runner,
+                used: path,
+                ignored: Vec::new(),
+            }),
+        }
+    }
+    duplicates.retain(|found| !found.ignored.is_empty());
+    duplicates
+}
+
+/// Non-interactive summary of the runners found in a directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectionReport {
+    pub dir: PathBuf,
+    pub runners: Vec<RunnerReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunnerReport {
+    pub detection: Detection,
+    pub tool: &'static str,
+    pub tool_present: bool,
+    pub task_count: Option<usize>,
+}
+
+/// Builds a detection report for `dir` without prompting or running anything.
+///
+/// Tool presence comes from `resolver`, so tests don't depend on PATH.
+pub fn detection_report(
+    dir: &Path,
+    preferences: &RunnersConfig,
+    resolver: &dyn ToolResolver,
+) -> DetectionReport {
+    let runners = detect_runners(dir, preferences)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|detection| {
+            let tool = detection.command();
+            RunnerReport {
+                tool_present: resolver.resolve(tool).is_some(),
+                detection,
+                tool,
+                task_count: None,
+            }
+        })
+        .collect();
+
+    DetectionReport {
+        dir: dir.to_path_buf(),
+        runners,
+    }
+}
+
+impl DetectionReport {
+    /// Returns `{"dir", "runners": [{"runner", "file", "tool_present"}]}` for scripts and editors.
+    pub fn to_json(&self) -> serde_json::Value {
+        let runners: Vec<serde_json::Value> = self
+            .runners
+            .iter()
+            .map(|runner| {
+                serde_json::json!({
+                    "runner": runner_name(runner.detection.runner),
+                    "file": runner.detection.runner_file,
+                    "tool_present": runner.tool_present,
+                })
+            })
+            .collect();
+        serde_json::json!({ "dir": self.dir, "runners": runners })
+    }
+
+    /// Fills in task counts for runners whose tool is present, using `count_tasks`.
+    pub fn with_task_counts(mut self, count_tasks: &dyn Fn(&Detection) -> Option<usize>) -> Self {
+        for runner in &mut self.runners {
+            if runner.tool_present {
+                runner.task_count = count_tasks(&runner.detection);
+            }
+        }
+        self
+    }
+}
+
+/// Returns the runner's display name, e.g. `cargo-make` where the command is `cargo`.
+pub fn runner_name(runner: Runner) -> &'static str {
+    match runner {
+        Runner::Justfile => "just",
+        Runner::Taskfile => "task",
+        Runner::Maskfile => "mask",
+        Runner::Mise => "mise",
+        Runner::CargoMake => "cargo-make",
+        Runner::Makefile => "make",
+        Runner::Rake => "rake",
+        Runner::Earthly => "earthly",
+        Runner::Mage => "mage",
+        Runner::Procfile => "procfile",
+        Runner::Deno => "deno",
+        Runner::Composer => "composer",
+        Runner::PackageJson => "package-json",
+    }
+}
+
 /// Returns the command name for the given runner.
 pub fn runner_command(runner: Runner) -> &'static str {
     match runner {
@@ -91,12 +451,22 @@ pub fn runner_command(runner: Runner) -> &'static str {
         // cargo-make is a subcommand of cargo, so we need to check cargo
         Runner::CargoMake => "cargo",
         Runner::Makefile => "make",
+        Runner::Rake => "rake",
+        Runner::Earthly => "earthly",
+        Runner::Mage => "mage",
+        // Procfiles have no canonical runner; `sh` is the fallback launcher (see exec).
+        Runner::Procfile => "sh",
+        Runner::Deno => "deno",
+        Runner::Composer => "composer",
+        // The concrete manager comes from the lockfile; see `Detection::command`.
+        Runner::PackageJson => "npm",
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::OnlyResolver;
     use tempfile::tempdir;
 
     fn touch(dir: &Path, name: &str) -> PathBuf {
@@ -108,13 +478,199 @@ mod tests {
     #[test]
     fn detect_none_returns_error() {
         let dir = tempdir().unwrap();
-        let err = detect_runner(dir.path()).unwrap_err();
+        let err = detect_runner(dir.path(), &RunnersConfig::default()).unwrap_err();
         match err {
             RtError::NoRunnerFound { .. } => {}
             other => panic!("unexpected error: {other:?}"),
         }
     }
 
+    #[test]
+    fn detect_file_treats_unknown_names_as_justfiles() {
+        let dir = tempdir().unwrap();
+        let path = touch(dir.path(), "scratch.just");
+        let detection = detect_file(&path, None).unwrap();
+        assert_eq!(detection.runner, Runner::Justfile);
+        assert_eq!(detection.runner_file, path);
+        assert!(detection.explicit_file);
+
+        let makefile = touch(dir.path(), "Makefile");
+        assert!(matches!(
+            detect_file(&makefile, None),
+            Err(RtError::FileUnsupported { runner: "make" })
+        ));
+        assert!(matches!(
+            detect_file(&path, Some(Runner::Taskfile)),
+            Err(RtError::FileUnsupported { runner: "task" })
+        ));
+        assert!(matches!(
+            detect_file(&dir.path().join("missing.just"), None),
+            Err(RtError::RunnerFileNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn ordered_candidates_apply_priority_and_exclusions() {
+        let preferences = RunnersConfig {
+            priority: vec![Runner::Makefile, Runner::Taskfile],
+            exclude: vec![Runner::CargoMake],
+        };
+        let candidates = ordered_candidates(&preferences);
+        let names: Vec<&str> = candidates.iter().take(7).map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "GNUmakefile",
+                "makefile",
+                "Makefile",
+                "Makefile.am",
+                "Taskfile.yml",
+                "taskfile.yml",
+                "Taskfile.yaml",
+            ]
+        );
+        assert!(
+            !candidates
+                .iter()
+                .any(|(_, runner)| *runner == Runner::CargoMake)
+        );
+        assert_eq!(
+            ordered_candidates(&RunnersConfig::default()),
+            RUNNER_CANDIDATES.to_vec()
+        );
+    }
+
+    #[test]
+    fn find_runner_dir_walks_up_to_nearest_runner() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "justfile");
+        let nested = dir.path().join("crates").join("core");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(
+            find_runner_dir(&nested, &RunnersConfig::default()).unwrap(),
+            dir.path()
+        );
+        touch(&nested, "Makefile");
+        assert_eq!(
+            find_runner_dir(&nested, &RunnersConfig::default()).unwrap(),
+            nested
+        );
+    }
+
+    #[test]
+    fn runner_parses_from_display_name() {
+        assert_eq!("cargo-make".parse::<Runner>(), Ok(Runner::CargoMake));
+        assert_eq!("task".parse::<Runner>(), Ok(Runner::Taskfile));
+        assert!(
+            "gradle"
+                .parse::<Runner>()
+                .unwrap_err()
+                .contains("cargo-make")
+        );
+    }
+
+    #[test]
+    fn detect_named_runner_skips_higher_priority_runners() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "Makefile");
+        let taskfile = touch(dir.path(), "Taskfile.yml");
+
+        let detection = detect_named_runner(dir.path(), Runner::Taskfile).unwrap();
+        assert_eq!(detection.runner_file, taskfile);
+        match detect_named_runner(dir.path(), Runner::Justfile).unwrap_err() {
+            RtError::RunnerNotDetected { runner, .. } => assert_eq!(runner, "just"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn detect_falls_back_to_automake_source() {
+        let dir = tempdir().unwrap();
+        let source = touch(dir.path(), "Makefile.am");
+
+        let detection = detect_runner(dir.path(), &RunnersConfig::default()).unwrap();
+        assert_eq!(detection.runner, Runner::Makefile);
+        assert!(detection.is_automake_source());
+        assert_eq!(detection.runner_file, source);
+
+        touch(dir.path(), "Makefile");
+        assert!(
+            !detect_runner(dir.path(), &RunnersConfig::default())
+                .unwrap()
+                .is_automake_source()
+        );
+    }
+
+    #[test]
+    fn detect_finds_gnumakefile_only_directory() {
+        let dir = tempdir().unwrap();
+        let path = touch(dir.path(), "GNUmakefile");
+
+        let detection = detect_runner(dir.path(), &RunnersConfig::default()).unwrap();
+        assert_eq!(detection.runner, Runner::Makefile);
+        assert_eq!(detection.runner_file, path);
+    }
+
+    #[test]
+    fn duplicate_runner_files_reports_case_variants_and_the_file_used() {
+        let dir = tempdir().unwrap();
+        let upper = touch(dir.path(), "Justfile");
+        let lower = touch(dir.path(), "justfile");
+        let yml = touch(dir.path(), "Taskfile.yml");
+        let yaml = touch(dir.path(), "Taskfile.yaml");
+        let dist = touch(dir.path(), "taskfile.dist.yml");
+        touch(dir.path(), "Makefile");
+        touch(dir.path(), "Makefile.am");
+
+        assert_eq!(
+            duplicate_runner_files(dir.path()),
+            vec![
+                DuplicateRunnerFiles {
+                    runner: Runner::Justfile,
+                    used: upper.clone(),
+                    ignored: vec![lower],
+                },
+                DuplicateRunnerFiles {
+                    runner: Runner::Taskfile,
+                    used: yml.clone(),
+                    ignored: vec![yaml, dist],
+                },
+            ]
+        );
+        assert_eq!(
+            detect_runner(dir.path(), &RunnersConfig::default())
+                .unwrap()
+                .runner_file,
+            upper
+        );
+        assert_eq!(
+            detect_named_runner(dir.path(), Runner::Taskfile)
+                .unwrap()
+                .runner_file,
+            yml
+        );
+    }
+
+    #[test]
+    fn duplicate_runner_files_is_empty_without_duplicates() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "justfile");
+        touch(dir.path(), "Makefile");
+        assert!(duplicate_runner_files(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn detect_runners_lists_make_once_with_gnumakefile_first() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "Makefile");
+        let gnu = touch(dir.path(), "GNUmakefile");
+
+        let detections = detect_runners(dir.path(), &RunnersConfig::default()).unwrap();
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].runner_file, gnu);
+    }
+
     #[test]
     fn detect_prefers_justfile_over_others() {
         let dir = tempdir().unwrap();
@@ -124,7 +680,7 @@ mod tests {
         touch(dir.path(), "Taskfile.yml");
         let just_path = touch(dir.path(), "justfile");
 
-        let detection = detect_runner(dir.path()).unwrap();
+        let detection = detect_runner(dir.path(), &RunnersConfig::default()).unwrap();
         assert_eq!(detection.runner, Runner::Justfile);
         let name = detection
             .runner_file
@@ -147,7 +703,7 @@ mod tests {
         let yml = touch(dir.path(), "Taskfile.yml");
         touch(dir.path(), "Taskfile.yaml");
 
-        let detection = detect_runner(dir.path()).unwrap();
+        let detection = detect_runner(dir.path(), &RunnersConfig::default()).unwrap();
         assert_eq!(detection.runner, Runner::Taskfile);
         assert_eq!(detection.runner_file, yml);
     }
@@ -160,11 +716,16 @@ mod tests {
         assert_eq!(runner_command(Runner::Mise), "mise");
         assert_eq!(runner_command(Runner::CargoMake), "cargo");
         assert_eq!(runner_command(Runner::Makefile), "make");
+        assert_eq!(runner_command(Runner::Earthly), "earthly");
+        assert_eq!(runner_command(Runner::Mage), "mage");
+        assert_eq!(runner_command(Runner::Composer), "composer");
+        assert_eq!(runner_command(Runner::Procfile), "sh");
     }
 
     #[test]
     fn detect_runners_returns_all_in_priority_order() {
         let dir = tempdir().unwrap();
+        touch(dir.path(), "Procfile");
         touch(dir.path(), "Makefile");
         touch(dir.path(), "Makefile.toml");
         touch(dir.path(), "mise.toml");
@@ -172,7 +733,7 @@ mod tests {
         touch(dir.path(), "Taskfile.yml");
         touch(dir.path(), "justfile");
 
-        let detections = detect_runners(dir.path()).unwrap();
+        let detections = detect_runners(dir.path(), &RunnersConfig::default()).unwrap();
         let runners: Vec<Runner> = detections.into_iter().map(|d| d.runner).collect();
 
         assert_eq!(
@@ -184,10 +745,112 @@ mod tests {
                 Runner::Mise,
                 Runner::CargoMake,
                 Runner::Makefile,
+                Runner::Procfile,
             ]
         );
     }
 
+    #[test]
+    fn detection_report_to_json_lists_runners_without_tasks() {
+        let dir = tempdir().unwrap();
+        let justfile = touch(dir.path(), "justfile");
+        let makefile = touch(dir.path(), "Makefile");
+
+        let json = detection_report(dir.path(), &RunnersConfig::default(), &OnlyResolver("just"))
+            .to_json();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "dir": dir.path(),
+                "runners": [
+                    {"runner": "just", "file": justfile, "tool_present": true},
+                    {"runner": "make", "file": makefile, "tool_present": false},
+                ],
+            })
+        );
+    }
+
+    fn touch_at(dir: &Path, name: &str, secs: u64) {
+        let file = std::fs::File::create(dir.join(name)).unwrap();
+        let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+        file.set_modified(time).unwrap();
+    }
+
+    #[test]
+    fn detect_package_manager_reads_lockfiles() {
+        let dir = tempdir().unwrap();
+        assert_eq!(detect_package_manager(dir.path()), PackageManager::Npm);
+
+        touch(dir.path(), "yarn.lock");
+        assert_eq!(detect_package_manager(dir.path()), PackageManager::Yarn);
+    }
+
+    #[test]
+    fn detect_package_manager_reads_both_bun_lockfiles() {
+        for lockfile in ["bun.lock", "bun.lockb"] {
+            let dir = tempdir().unwrap();
+            touch(dir.path(), lockfile);
+            assert_eq!(
+                detect_package_manager(dir.path()),
+                PackageManager::Bun,
+                "{lockfile}"
+            );
+        }
+    }
+
+    #[test]
+    fn detect_package_manager_prefers_newest_lockfile() {
+        let dir = tempdir().unwrap();
+        touch_at(dir.path(), "pnpm-lock.yaml", 1_000);
+        touch_at(dir.path(), "bun.lockb", 2_000);
+        touch_at(dir.path(), "package-lock.json", 1_500);
+
+        assert_eq!(detect_package_manager(dir.path()), PackageManager::Bun);
+    }
+
+    #[test]
+    fn detect_package_json_threads_manager_into_detection() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "package.json");
+        touch(dir.path(), "pnpm-lock.yaml");
+
+        let detection = detect_runner(dir.path(), &RunnersConfig::default()).unwrap();
+        assert_eq!(detection.runner, Runner::PackageJson);
+        assert_eq!(detection.package_manager, Some(PackageManager::Pnpm));
+        assert_eq!(detection.command(), "pnpm");
+    }
+
+    #[test]
+    fn detection_report_uses_injected_tool_presence() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "justfile");
+        touch(dir.path(), "Makefile");
+
+        let report = detection_report(dir.path(), &RunnersConfig::default(), &OnlyResolver("just"))
+            .with_task_counts(&|detection| (detection.runner == Runner::Justfile).then_some(4));
+
+        assert_eq!(report.dir, dir.path());
+        let summary: Vec<(Runner, &str, bool, Option<usize>)> = report
+            .runners
+            .iter()
+            .map(|r| (r.detection.runner, r.tool, r.tool_present, r.task_count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Runner::Justfile, "just", true, Some(4)),
+                (Runner::Makefile, "make", false, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn detection_report_is_empty_without_runners() {
+        let dir = tempdir().unwrap();
+        let report = detection_report(dir.path(), &RunnersConfig::default(), &OnlyResolver("make"));
+        assert!(report.runners.is_empty());
+    }
+
     #[test]
     fn detect_runners_deduplicates_case_variants() {
         let dir = tempdir().unwrap();
@@ -196,7 +859,7 @@ mod tests {
         touch(dir.path(), "Taskfile.yml");
         touch(dir.path(), "taskfile.yaml");
 
-        let detections = detect_runners(dir.path()).unwrap();
+        let detections = detect_runners(dir.path(), &RunnersConfig::default()).unwrap();
         let runners: Vec<Runner> = detections.into_iter().map(|d| d.runner).collect();
 
         assert_eq!(runners, vec![Runner::Justfile, Runner::Taskfile]);
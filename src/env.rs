@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use crate::config::glob_match;
+use crate::detect::Detection;
+use crate::parser;
+
+/// Key patterns whose values are masked when an environment is printed.
+///
+/// `AUTH` only matches as a whole `_`-separated segment, so `GIT_AUTHOR_NAME` stays visible.
+const REDACTED_KEY_PATTERNS: [&str; 11] = [
+    "*TOKEN*",
+    "*SECRET*",
+    "*PASSWORD*",
+    "*PASSWD*",
+    "*API_KEY*",
+    "*PRIVATE_KEY*",
+    "*CREDENTIAL*",
+    "AUTH",
+    "AUTH_*",
+    "*_AUTH",
+    "*_AUTH_*",
+];
+
+const REDACTED_VALUE: &str = "********";
+
+/// A `KEY=VALUE` pair given with `--env`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvAssignment {
+    pub key: String,
+    pub value: String,
+}
+
+impl std::str::FromStr for EnvAssignment {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some((key, assigned))
+                if !key.is_empty() && !key.contains(char::is_whitespace) && !key.contains('\0') =>
+            {
+                Ok(Self {
+                    key: key.to_string(),
+                    value: assigned.to_string(),
+                })
+            }
+            _ => Err(format!("expected KEY=VALUE, got `{value}`")),
+        }
+    }
+}
+
+/// Returns the environment a task's child process would see, sorted by key.
+///
+/// Starts from `base` (rt's own environment) and adds variables the runner loads itself: just's
+/// `set dotenv-load` entries, which don't override variables already set, and exported justfile
+/// variables, which do.
+pub fn child_environment(
+    base: impl IntoIterator<Item = (String, String)>,
+    detection: Option<&Detection>,
+) -> BTreeMap<String, String> {
+    let mut env: BTreeMap<String, String> = base.into_iter().collect();
+    let dotenv = detection
+        .and_then(|detection| parser::dotenv_file(detection.runner, &detection.runner_file))
+        .and_then(|path| std::fs::read_to_string(path).ok());
+    if let Some(content) = dotenv {
+        for (key, value) in parse_dotenv(&content) {
+            env.entry(key).or_insert(value);
+        }
+    }
+    if let Some(detection) = detection {
+        env.extend(parser::exported_variables(
+            detection.runner,
+            &detection.runner_file,
+        ));
+    }
+    env
+}
+
+/// Returns whether the value of `key` should be hidden when printed.
+pub fn is_redacted(key: &str) -> bool {
+    let key = key.to_ascii_uppercase();
+    REDACTED_KEY_PATTERNS
+        .iter()
+        .any(|pattern| glob_match(pattern, &key))
+}
+
+/// Formats `KEY=value` lines, masking values of sensitive keys.
+pub fn format_dump(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(key, value)| {
+            let value = if is_redacted(key) {
+                REDACTED_VALUE
+            } else {
+                value
+            };
+            format!("{key}={value}\n")
+        })
+        .collect()
+}
+
+/// Parses `KEY=value` lines of a dotenv file, allowing `export` and quoted values.
+fn parse_dotenv(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = ['\'', '"']
+                .iter()
+                .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::Runner;
+    use crate::test_support::detection_at;
+    use tempfile::tempdir;
+
+    #[test]
+    fn env_assignment_requires_key_and_equals_sign() {
+        let parsed: EnvAssignment = "DEBUG=1".parse().unwrap();
+        assert_eq!((parsed.key.as_str(), parsed.value.as_str()), ("DEBUG", "1"));
+        let with_equals: EnvAssignment = "OPTS=a=b".parse().unwrap();
+        assert_eq!(with_equals.value, "a=b");
+        let empty: EnvAssignment = "EMPTY=".parse().unwrap();
+        assert_eq!(empty.value, "");
+
+        for invalid in ["DEBUG", "=1", "MY VAR=1"] {
+            assert_eq!(
+                invalid.parse::<EnvAssignment>().unwrap_err(),
+                format!("expected KEY=VALUE, got `{invalid}`")
+            );
+        }
+    }
+
+    #[test]
+    fn child_environment_adds_just_dotenv_without_overriding() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(&path, "set dotenv-load\n\nbuild:\n  echo build\n").unwrap();
+        std::fs::write(
+            dir.path().join(".env"),
+            "# local\nexport DATABASE_URL='postgres://localhost'\nHOME=/elsewhere\n",
+        )
+        .unwrap();
+        let detection = detection_at(Runner::Justfile, path);
+
+        let env = child_environment(
+            [("HOME".to_string(), "/home/me".to_string())],
+            Some(&detection),
+        );
+        assert_eq!(env["HOME"], "/home/me");
+        assert_eq!(env["DATABASE_URL"], "postgres://localhost");
+    }
+
+    #[test]
+    fn child_environment_applies_exported_justfile_variables() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(&path, "set export\nRUST_LOG := 'debug'\n").unwrap();
+        let detection = detection_at(Runner::Justfile, path);
+
+        let env = child_environment(
+            [("RUST_LOG".to_string(), "info".to_string())],
+            Some(&detection),
+        );
+        assert_eq!(env["RUST_LOG"], "debug");
+    }
+
+    #[test]
+    fn format_dump_sorts_keys_and_masks_secrets() {
+        let env = child_environment(
+            [
+                ("PATH".to_string(), "/usr/bin".to_string()),
+                ("GITHUB_TOKEN".to_string(), "ghp_123".to_string()),
+                ("db_password".to_string(), "hunter2".to_string()),
+            ],
+            None,
+        );
+        assert_eq!(
+            format_dump(&env),
+            "GITHUB_TOKEN=********\nPATH=/usr/bin\ndb_password=********\n"
+        );
+    }
+
+    #[test]
+    fn is_redacted_matches_auth_only_as_a_key_segment() {
+        for key in ["AUTH", "auth_header", "NPM_CONFIG__AUTH", "BASIC_AUTH_USER"] {
+            assert!(is_redacted(key), "{key}");
+        }
+        for key in ["GIT_AUTHOR_NAME", "AUTHOR", "OAUTHLIB_INSECURE"] {
+            assert!(!is_redacted(key), "{key}");
+        }
+    }
+}
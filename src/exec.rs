@@ -1,98 +1,819 @@
-use std::path::Path;
-use std::process::Command;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 use crate::RtError;
-use crate::detect::{Runner, runner_command};
+use crate::detect::{Detection, PackageManager, Runner, runner_command};
+use crate::exit_code;
+use crate::parser;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RunResult {
     pub exit_code: i32,
+    /// rt killed the task because it outlived `RunOptions::timeout`.
+    pub timed_out: bool,
     pub program: String,
     pub args: Vec<String>,
+    /// Wall-clock time from spawning the command until it exited.
+    pub duration: Duration,
+}
+
+/// How often a task with a time limit is polled for exit.
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long a timed-out task's remaining output may take to reach the `--log` file.
+const TEE_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Settings for a single task run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    /// Kill the task and exit with `exit_code::TIMEOUT` once it runs this long.
+    pub timeout: Option<Duration>,
+    /// Run the task through `mise exec --` so it sees mise-managed tools.
+    pub mise_exec: bool,
+    /// Extra environment variables for the task (`--env`); not recorded in history.
+    pub env: Vec<(String, String)>,
+    /// Append the task's stdout and stderr to this file while still showing them (`--log`).
+    pub log: Option<PathBuf>,
+}
+
+/// Returns whether `detection` is a non-mise runner in a project with a `mise.toml`.
+///
+/// `mise run` activates tools itself, so only the other runners need wrapping.
+pub fn mise_manages(detection: &Detection) -> bool {
+    detection.runner != Runner::Mise
+        && detection
+            .runner_file
+            .parent()
+            .is_some_and(|dir| dir.join("mise.toml").is_file())
+}
+
+/// Wraps `invocation` as `mise exec -- <program> <args>`.
+fn mise_exec(invocation: Invocation) -> Invocation {
+    let mut args = vec!["exec".to_string(), "--".to_string(), invocation.program];
+    args.extend(invocation.args);
+    Invocation {
+        program: runner_command(Runner::Mise).to_string(),
+        args,
+    }
+}
+
+/// The program and argv that run a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+/// Resolves runner tools to the program that gets spawned.
+///
+/// The default resolves through PATH; tests inject their own to avoid depending on the environment.
+pub trait ToolResolver {
+    /// Returns the program to spawn for `tool`, or `None` when the tool isn't installed.
+    fn resolve(&self, tool: &str) -> Option<PathBuf>;
+}
+
+/// Resolves tools with `which`, spawning them by name.
+pub struct WhichResolver;
+
+impl ToolResolver for WhichResolver {
+    fn resolve(&self, tool: &str) -> Option<PathBuf> {
+        which::which(tool).ok().map(|_| PathBuf::from(tool))
+    }
 }
 
 pub fn run(
-    runner: Runner,
+    detection: &Detection,
     task: &str,
     passthrough: &[String],
     cwd: &Path,
+    options: &RunOptions,
 ) -> Result<RunResult, RtError> {
-    let program = runner_command(runner).to_string();
-    let mut args = Vec::new();
-    if runner == Runner::CargoMake {
+    run_with(detection, task, passthrough, cwd, options, &WhichResolver)
+}
+
+fn run_with(
+    detection: &Detection,
+    task: &str,
+    passthrough: &[String],
+    cwd: &Path,
+    options: &RunOptions,
+    resolver: &dyn ToolResolver,
+) -> Result<RunResult, RtError> {
+    let invocation = planned_invocation(detection, task, passthrough, options)?;
+    let command_line = preview_command(detection, task, passthrough)?;
+    let program = if invocation.program.contains('/') {
+        PathBuf::from(&invocation.program)
+    } else {
+        resolver
+            .resolve(&invocation.program)
+            .ok_or_else(|| RtError::ToolMissingCommand {
+                tool: invocation.program.clone(),
+            })?
+    };
+
+    let log = options.log.as_deref().map(open_log).transpose()?;
+    let mut command = Command::new(program);
+    command
+        .args(&invocation.args)
+        .envs(options.env.iter().map(|(key, value)| (key, value)))
+        // Set last, so they replace inherited or `--env` values of the same name.
+        .env("RT_TASK", task)
+        .env("RT_COMMAND", command_line)
+        .current_dir(cwd);
+    if log.is_some() {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    let foreground = options.timeout.is_some() && isolate_process_group(&mut command);
+
+    let started = Instant::now();
+    let mut child = command.spawn().map_err(RtError::Spawn)?;
+    let tees = log
+        .map(|log| spawn_tees(&mut child, log))
+        .unwrap_or_default();
+    let exit = wait_with_timeout(child, options.timeout, foreground).map_err(RtError::Spawn)?;
+    join_tees(tees, exit.timed_out);
+
+    Ok(RunResult {
+        exit_code: exit.code,
+        timed_out: exit.timed_out,
+        program: invocation.program,
+        args: invocation.args,
+        duration: started.elapsed(),
+    })
+}
+
+/// Opens the `--log` file for appending, creating it if needed.
+fn open_log(path: &Path) -> Result<File, RtError> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|source| RtError::LogFile {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Starts copying the child's piped stdout and stderr to rt's own and to `log`.
+fn spawn_tees(child: &mut Child, log: File) -> Vec<JoinHandle<()>> {
+    let log = Arc::new(Mutex::new(log));
+    let mut tees = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let log = Arc::clone(&log);
+        tees.push(thread::spawn(move || tee(stdout, io::stdout(), &log)));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tees.push(thread::spawn(move || tee(stderr, io::stderr(), &log)));
+    }
+    tees
+}
+
+/// Waits for the `--log` copies to finish draining the task's output.
+///
+/// After a timeout the task's process group is dead, but a process that left the group (a
+/// daemon) may still hold the pipes open, so the wait is bounded by `TEE_DRAIN_TIMEOUT`.
+fn join_tees(tees: Vec<JoinHandle<()>>, timed_out: bool) {
+    let deadline = Instant::now() + TEE_DRAIN_TIMEOUT;
+    for tee in tees {
+        while timed_out && !tee.is_finished() && Instant::now() < deadline {
+            thread::sleep(TIMEOUT_POLL_INTERVAL);
+        }
+        if !timed_out || tee.is_finished() {
+            let _ = tee.join();
+        }
+    }
+}
+
+/// Writes each chunk read from `source` to `terminal` and `log` as soon as it arrives.
+///
+/// Nothing is held back waiting for a newline, so progress output shows up live. Write errors are
+/// ignored, and `source` is still drained, so a closed terminal never blocks the task.
+fn tee(mut source: impl Read, mut terminal: impl Write, log: &Mutex<File>) {
+    let mut buffer = [0; 8192];
+    loop {
+        let read = match source.read(&mut buffer) {
+            Ok(0) => return,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => return,
+        };
+        let chunk = &buffer[..read];
+        let _ = terminal.write_all(chunk).and_then(|()| terminal.flush());
+        // One lock per chunk keeps stdout and stderr chunks whole in the file.
+        if let Ok(mut log) = log.lock() {
+            let _ = log.write_all(chunk);
+        }
+    }
+}
+
+/// Builds the command line `run` spawns for `task`, including any `mise exec` wrapping.
+pub fn planned_invocation(
+    detection: &Detection,
+    task: &str,
+    passthrough: &[String],
+    options: &RunOptions,
+) -> Result<Invocation, RtError> {
+    let invocation = invocation(detection, task, passthrough)?;
+    Ok(if options.mise_exec {
+        mise_exec(invocation)
+    } else {
+        invocation
+    })
+}
+
+/// How a task that rt waited for ended.
+struct Exit {
+    code: i32,
+    /// rt killed the task once it outlived its time limit.
+    timed_out: bool,
+}
+
+/// Waits for `child`, killing its process group once `timeout` elapses.
+///
+/// A `foreground` task holds the terminal for the run; when Ctrl-Z stops it, rt stops too and
+/// resumes it on `fg`, and the time spent stopped doesn't count against the limit.
+fn wait_with_timeout(
+    mut child: Child,
+    timeout: Option<Duration>,
+    foreground: bool,
+) -> io::Result<Exit> {
+    let Some(timeout) = timeout else {
+        return Ok(Exit {
+            code: exit_code::from_status(child.wait()?),
+            timed_out: false,
+        });
+    };
+
+    #[cfg(unix)]
+    let _forward = ForwardSignals::to_group_of(&child);
+    #[cfg(unix)]
+    let _terminal = foreground.then(|| TerminalHandoff::to_group_of(&child));
+    let mut deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Exit {
+                code: exit_code::from_status(status),
+                timed_out: false,
+            });
+        }
+        if foreground && task_stopped(&child) {
+            let stopped = Instant::now();
+            suspend_with(&child);
+            deadline += stopped.elapsed();
+            continue;
+        }
+        if Instant::now() >= deadline {
+            kill_process_group(&mut child)?;
+            child.wait()?;
+            return Ok(Exit {
+                code: exit_code::TIMEOUT,
+                timed_out: true,
+            });
+        }
+        std::thread::sleep(TIMEOUT_POLL_INTERVAL);
+    }
+}
+
+/// Starts a task with a time limit in its own process group, so a timeout also kills the
+/// processes it started (a runner's `sleep`, a test server) rather than only the runner.
+///
+/// A background group can't read the terminal, so when rt holds it, the group takes it over
+/// before the task starts. Returns whether it does.
+#[cfg(unix)]
+fn isolate_process_group(command: &mut Command) -> bool {
+    use std::os::unix::process::CommandExt;
+    command.process_group(0);
+    let foreground = owns_terminal();
+    if foreground {
+        // SAFETY: the hook only makes async-signal-safe calls. `process_group` has already run,
+        // so `getpgrp` is the task's own group.
+        unsafe {
+            command.pre_exec(|| {
+                take_terminal(libc::getpgrp());
+                Ok(())
+            });
+        }
+    }
+    foreground
+}
+
+#[cfg(not(unix))]
+fn isolate_process_group(_command: &mut Command) -> bool {
+    false
+}
+
+/// Whether stdin is a terminal whose foreground process group is rt's.
+#[cfg(unix)]
+fn owns_terminal() -> bool {
+    // SAFETY: both calls only query the terminal.
+    unsafe {
+        libc::isatty(libc::STDIN_FILENO) == 1
+            && libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp()
+    }
+}
+
+/// Makes `group` the terminal's foreground group.
+///
+/// A background caller would get `SIGTTOU` for this, so it's ignored for the call.
+#[cfg(unix)]
+fn take_terminal(group: libc::pid_t) {
+    // SAFETY: `signal` and `tcsetpgrp` are async-signal-safe; the old handler is put back.
+    unsafe {
+        let previous = libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+        libc::tcsetpgrp(libc::STDIN_FILENO, group);
+        libc::signal(libc::SIGTTOU, previous);
+    }
+}
+
+/// Gives the terminal to a task's process group until dropped, then takes it back for rt.
+#[cfg(unix)]
+struct TerminalHandoff;
+
+#[cfg(unix)]
+impl TerminalHandoff {
+    fn to_group_of(child: &Child) -> Self {
+        // The task takes the terminal itself too; whichever runs first, it can't read before.
+        take_terminal(child.id() as libc::pid_t);
+        Self
+    }
+}
+
+#[cfg(unix)]
+impl Drop for TerminalHandoff {
+    fn drop(&mut self) {
+        // SAFETY: `getpgrp` can't fail.
+        take_terminal(unsafe { libc::getpgrp() });
+    }
+}
+
+/// Whether the task was stopped, e.g. by Ctrl-Z, since the last check. Exits are left for
+/// `Child::try_wait` to reap.
+#[cfg(unix)]
+fn task_stopped(child: &Child) -> bool {
+    // SAFETY: `siginfo_t` is plain data, and `WSTOPPED` without `WEXITED` never reaps the child.
+    unsafe {
+        let mut info: libc::siginfo_t = std::mem::zeroed();
+        libc::waitid(
+            libc::P_PID,
+            child.id(),
+            &mut info,
+            libc::WSTOPPED | libc::WNOHANG,
+        ) == 0
+            && info.si_pid() != 0
+    }
+}
+
+#[cfg(not(unix))]
+fn task_stopped(_child: &Child) -> bool {
+    false
+}
+
+/// Stops rt as well, so the shell sees the job stop, and continues the task once rt is
+/// continued, back in the foreground after `fg`.
+#[cfg(unix)]
+fn suspend_with(child: &Child) {
+    let group = child.id() as libc::pid_t;
+    // SAFETY: `getpgrp` can't fail, and `raise`/`killpg` only send signals.
+    unsafe {
+        take_terminal(libc::getpgrp());
+        libc::raise(libc::SIGTSTP);
+        if owns_terminal() {
+            take_terminal(group);
+        }
+        libc::killpg(group, libc::SIGCONT);
+    }
+}
+
+#[cfg(not(unix))]
+fn suspend_with(_child: &Child) {}
+
+/// Kills every process in the group `isolate_process_group` started `child` in.
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) -> io::Result<()> {
+    // SAFETY: `killpg` only sends a signal; the group id is the child's own pid.
+    if unsafe { libc::killpg(child.id() as libc::pid_t, libc::SIGKILL) } == 0 {
+        return Ok(());
+    }
+    child.kill()
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut Child) -> io::Result<()> {
+    child.kill()
+}
+
+/// The process group of the timed task that `ForwardSignals` passes signals to; 0 when none.
+#[cfg(unix)]
+static TASK_GROUP: AtomicI32 = AtomicI32::new(0);
+
+#[cfg(unix)]
+extern "C" fn forward_to_task_group(signal: libc::c_int) {
+    let group = TASK_GROUP.load(Ordering::Relaxed);
+    if group > 0 {
+        // SAFETY: `killpg` is async-signal-safe.
+        unsafe {
+            libc::killpg(group, signal);
+        }
+    }
+}
+
+/// Passes Ctrl-C and `SIGTERM` on to a task in its own process group until dropped.
+///
+/// The terminal only signals its foreground group, which is rt's, so without this Ctrl-C would
+/// end rt and leave the task running. rt then reports the task's exit as usual.
+#[cfg(unix)]
+struct ForwardSignals {
+    previous: [libc::sighandler_t; 2],
+}
+
+#[cfg(unix)]
+impl ForwardSignals {
+    const SIGNALS: [libc::c_int; 2] = [libc::SIGINT, libc::SIGTERM];
+
+    fn to_group_of(child: &Child) -> Self {
+        TASK_GROUP.store(child.id() as i32, Ordering::Relaxed);
+        let handler = forward_to_task_group as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        // SAFETY: the handler only reads an atomic and calls `killpg`.
+        let previous = Self::SIGNALS.map(|signal| unsafe { libc::signal(signal, handler) });
+        Self { previous }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ForwardSignals {
+    fn drop(&mut self) {
+        for (signal, previous) in Self::SIGNALS.into_iter().zip(self.previous) {
+            // SAFETY: restores the handler `to_group_of` replaced.
+            unsafe {
+                libc::signal(signal, previous);
+            }
+        }
+        TASK_GROUP.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Builds the command line that runs `task` with `passthrough` for the detected runner.
+pub fn invocation(
+    detection: &Detection,
+    task: &str,
+    passthrough: &[String],
+) -> Result<Invocation, RtError> {
+    if detection.runner == Runner::Procfile {
+        return procfile_invocation(detection, task, passthrough, procfile_launcher());
+    }
+    if detection.runner == Runner::PackageJson {
+        return package_script_invocation(detection, task, passthrough);
+    }
+
+    let mut args = runner_file_args(detection);
+    if detection.runner == Runner::CargoMake {
         args.push("make".to_string());
     }
-    if runner == Runner::Mise {
+    if detection.runner == Runner::Mise {
         args.push("run".to_string());
     }
-    args.push(task.to_string());
+    if detection.runner == Runner::Deno {
+        args.push("task".to_string());
+    }
+    if detection.runner == Runner::Composer {
+        args.push("run-script".to_string());
+    }
+    if detection.runner == Runner::Earthly && !task.starts_with('+') {
+        args.push(format!("+{task}"));
+    } else {
+        args.push(task.to_string());
+    }
+    if detection.runner == Runner::Taskfile {
+        let uses_cli_args = parser::taskfile_uses_cli_args(&detection.runner_file, task);
+        args.extend(taskfile_passthrough(passthrough, uses_cli_args));
+    } else if detection.runner == Runner::Composer {
+        // composer takes options after the script name as its own unless they follow `--`.
+        if passthrough.first().is_some_and(|arg| arg != "--") {
+            args.push("--".to_string());
+        }
+        args.extend(passthrough.iter().cloned());
+    } else {
+        args.extend(passthrough.iter().cloned());
+    }
+
+    Ok(Invocation {
+        program: detection.command().to_string(),
+        args,
+    })
+}
+
+/// Arguments that point the runner at a file given with `--file`; empty for detected files.
+///
+/// just would run recipes next to the file, so the current directory is passed as well, as for
+/// every other rt run.
+pub fn runner_file_args(detection: &Detection) -> Vec<String> {
+    if !detection.explicit_file || detection.runner != Runner::Justfile {
+        return Vec::new();
+    }
+    vec![
+        "--justfile".to_string(),
+        detection.runner_file.display().to_string(),
+        "--working-directory".to_string(),
+        ".".to_string(),
+    ]
+}
+
+/// Runs a `package.json` script with the detected manager, e.g. `pnpm run build`.
+///
+/// With `detection.workspaces`, workspace scripts (`web:build`) run through the manager's
+/// workspace option instead. npm only forwards arguments after `--`, so one is inserted unless
+/// the user typed it. `yarn workspace` only takes package names, so a package without one
+/// can't be run through Yarn.
+fn package_script_invocation(
+    detection: &Detection,
+    task: &str,
+    passthrough: &[String],
+) -> Result<Invocation, RtError> {
+    let program = detection.command();
+    let workspace_script = detection
+        .workspaces
+        .then(|| parser::package_workspace_script(&detection.runner_file, task))
+        .flatten();
+    let mut args: Vec<String> = match workspace_script {
+        Some((workspace, script)) => match detection.package_manager {
+            Some(PackageManager::Pnpm) => vec!["--filter".into(), workspace, "run".into(), script],
+            Some(PackageManager::Yarn) if workspace.starts_with("./") => {
+                return Err(RtError::UnnamedYarnWorkspace {
+                    selector: workspace,
+                });
+            }
+            Some(PackageManager::Yarn) => vec!["workspace".into(), workspace, "run".into(), script],
+            Some(PackageManager::Bun) => vec!["run".into(), "--filter".into(), workspace, script],
+            _ => vec!["run".into(), script, "--workspace".into(), workspace],
+        },
+        None => vec!["run".to_string(), task.to_string()],
+    };
+    let needs_separator = program == PackageManager::Npm.command()
+        && passthrough.first().is_some_and(|arg| arg != "--");
+    if needs_separator {
+        args.push("--".to_string());
+    }
     args.extend(passthrough.iter().cloned());
+    Ok(Invocation {
+        program: program.to_string(),
+        args,
+    })
+}
 
-    let mut command = base_command(runner)?;
-    let status = command
-        .arg(task)
-        .args(passthrough)
-        .current_dir(cwd)
-        .status()
-        .map_err(RtError::Spawn)?;
+/// Orders passthrough for a Taskfile task that uses `CLI_ARGS`: `VAR=value` CLI variables first,
+/// then every other argument, flags and their values included, in order after `--`.
+fn taskfile_passthrough(passthrough: &[String], uses_cli_args: bool) -> Vec<String> {
+    // Without `CLI_ARGS`, extra words are more tasks to run (`rt build lint`) or `task`'s own
+    // flags, so they're passed as given; so is anything that already has a `--`.
+    if !uses_cli_args || passthrough.iter().any(|arg| arg == "--") {
+        return passthrough.to_vec();
+    }
 
-    Ok(RunResult {
-        exit_code: status.code().unwrap_or(2),
-        program,
+    let (mut before, cli_args): (Vec<String>, Vec<String>) = passthrough
+        .iter()
+        .cloned()
+        .partition(|arg| is_cli_variable(arg));
+    if !cli_args.is_empty() {
+        before.push("--".to_string());
+        before.extend(cli_args);
+    }
+    before
+}
+
+/// Takes the Make target out of `KEY=VALUE` overrides typed before it.
+///
+/// Make accepts overrides anywhere, so `rt CC=clang build` runs `make build CC=clang`. When every
+/// token is an override, the task stays as given and Make builds its default goal.
+pub fn make_target_and_overrides(task: &str, passthrough: &[String]) -> (String, Vec<String>) {
+    if !is_cli_variable(task) {
+        return (task.to_string(), passthrough.to_vec());
+    }
+
+    match passthrough.iter().position(|arg| !is_cli_variable(arg)) {
+        Some(idx) => {
+            let mut rest = vec![task.to_string()];
+            rest.extend(passthrough[..idx].iter().cloned());
+            rest.extend(passthrough[idx + 1..].iter().cloned());
+            (passthrough[idx].clone(), rest)
+        }
+        None => (task.to_string(), passthrough.to_vec()),
+    }
+}
+
+/// Returns whether `arg` has the `NAME=value` shape runners treat as a variable assignment.
+pub fn is_cli_variable(arg: &str) -> bool {
+    match arg.split_once('=') {
+        Some((name, _)) => {
+            let mut chars = name.chars();
+            chars
+                .next()
+                .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+                && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+        }
+        None => false,
+    }
+}
+
+/// Process managers that can start a single Procfile entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcfileLauncher {
+    Foreman,
+    Overmind,
+    /// Runs the entry's command line directly with `sh -c`.
+    Shell,
+}
+
+/// Picks the Procfile launcher from `RT_PROCFILE_LAUNCHER`, else the first one found in PATH.
+pub fn procfile_launcher() -> ProcfileLauncher {
+    let configured = std::env::var("RT_PROCFILE_LAUNCHER").ok();
+    procfile_launcher_from(configured.as_deref(), &|tool| which::which(tool).is_ok())
+}
+
+fn procfile_launcher_from(
+    configured: Option<&str>,
+    tool_present: &dyn Fn(&str) -> bool,
+) -> ProcfileLauncher {
+    match configured.map(str::trim) {
+        Some("foreman") => return ProcfileLauncher::Foreman,
+        Some("overmind") => return ProcfileLauncher::Overmind,
+        Some("sh" | "shell") => return ProcfileLauncher::Shell,
+        _ => {}
+    }
+
+    if tool_present("foreman") {
+        ProcfileLauncher::Foreman
+    } else if tool_present("overmind") {
+        ProcfileLauncher::Overmind
+    } else {
+        ProcfileLauncher::Shell
+    }
+}
+
+fn procfile_invocation(
+    detection: &Detection,
+    task: &str,
+    passthrough: &[String],
+    launcher: ProcfileLauncher,
+) -> Result<Invocation, RtError> {
+    let (program, mut args) = match launcher {
+        ProcfileLauncher::Foreman => ("foreman", vec!["start".to_string(), task.to_string()]),
+        ProcfileLauncher::Overmind => (
+            "overmind",
+            vec!["start".to_string(), "-l".to_string(), task.to_string()],
+        ),
+        ProcfileLauncher::Shell => {
+            let content = std::fs::read_to_string(&detection.runner_file).map_err(RtError::Io)?;
+            let command =
+                parser::procfile_command(&content, task).ok_or_else(|| RtError::UnknownTask {
+                    task: task.to_string(),
+                    suggestion: None,
+                })?;
+            let mut line = command;
+            for arg in passthrough {
+                line.push(' ');
+                line.push_str(&shell_quote(arg));
+            }
+            return Ok(Invocation {
+                program: "sh".to_string(),
+                args: vec!["-c".to_string(), line],
+            });
+        }
+    };
+    args.extend(passthrough.iter().cloned());
+    Ok(Invocation {
+        program: program.to_string(),
         args,
     })
 }
 
-pub fn run_program(program: &str, args: &[String], cwd: &Path) -> Result<RunResult, RtError> {
+/// Runs `program` with `args` in `cwd`, with `env` (`--env`) added to the inherited environment.
+pub fn run_program(
+    program: &str,
+    args: &[String],
+    cwd: &Path,
+    env: &[(String, String)],
+) -> Result<RunResult, RtError> {
     if !program.contains('/') && which::which(program).is_err() {
         return Err(RtError::ToolMissingCommand {
             tool: program.to_string(),
         });
     }
 
+    let started = Instant::now();
     let status = Command::new(program)
         .args(args)
+        .envs(env.iter().map(|(key, value)| (key, value)))
         .current_dir(cwd)
         .status()
         .map_err(RtError::Spawn)?;
 
     Ok(RunResult {
-        exit_code: status.code().unwrap_or(2),
+        exit_code: exit_code::from_status(status),
+        timed_out: false,
         program: program.to_string(),
         args: args.to_vec(),
+        duration: started.elapsed(),
     })
 }
 
-pub fn base_command(runner: Runner) -> Result<Command, RtError> {
-    let program = runner_command(runner);
-    ensure_tool(program)?;
-    let mut command = Command::new(program);
-    if runner == Runner::CargoMake {
-        command.arg("make");
+/// Runs a saved command line through `<shell> -c`, as typed by the user.
+pub fn run_shell(
+    command: &str,
+    cwd: &Path,
+    shell: &str,
+    env: &[(String, String)],
+) -> Result<RunResult, RtError> {
+    let invocation = shell_invocation(command, shell);
+    run_program(&invocation.program, &invocation.args, cwd, env)
+}
+
+/// The `shell -c command` invocation `run_shell` spawns.
+pub fn shell_invocation(command: &str, shell: &str) -> Invocation {
+    Invocation {
+        program: shell.to_string(),
+        args: vec!["-c".to_string(), command.to_string()],
     }
-    Ok(command)
 }
 
-pub fn ensure_tool(tool: &'static str) -> Result<(), RtError> {
-    match which::which(tool) {
-        Ok(_) => Ok(()),
-        Err(_) => Err(RtError::ToolMissing { tool }),
+/// Runs a hook command line through `<shell> -c` with extra environment variables.
+///
+/// Unlike `run_shell`, the result isn't meant for history.
+pub fn run_hook(
+    command: &str,
+    cwd: &Path,
+    shell: &str,
+    env: &[(&str, String)],
+) -> Result<i32, RtError> {
+    let status = Command::new(shell)
+        .arg("-c")
+        .arg(command)
+        .envs(env.iter().map(|(key, value)| (*key, value)))
+        .current_dir(cwd)
+        .status()
+        .map_err(RtError::Spawn)?;
+    Ok(exit_code::from_status(status))
+}
+
+/// The shell for command lines when `--exec-shell` isn't given: `$SHELL`, or `sh`.
+pub fn default_shell() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.trim().is_empty())
+        .unwrap_or_else(|| "sh".to_string())
+}
+
+/// Opens `path` in `$VISUAL`/`$EDITOR` (falling back to `vi`), jumping to `line` when known.
+pub fn open_in_editor(path: &Path, line: Option<usize>, cwd: &Path) -> Result<i32, RtError> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    let status = editor_command(&editor, path, line)
+        .current_dir(cwd)
+        .status()
+        .map_err(RtError::Spawn)?;
+    Ok(exit_code::from_status(status))
+}
+
+fn editor_command(editor: &str, path: &Path, line: Option<usize>) -> Command {
+    let mut parts = editor.split_whitespace();
+    let mut command = Command::new(parts.next().unwrap_or("vi"));
+    command.args(parts);
+    if let Some(line) = line {
+        command.arg(format!("+{line}"));
     }
+    command.arg(path);
+    command
 }
 
-pub fn preview_command(runner: Runner, task: &str, passthrough: &[String]) -> String {
-    let mut parts = Vec::new();
-    let program = runner_command(runner);
+pub fn base_command(runner: Runner, resolver: &dyn ToolResolver) -> Result<Command, RtError> {
+    let program = ensure_tool(runner_command(runner), resolver)?;
+    let mut command = Command::new(program);
     if runner == Runner::CargoMake {
-        parts.push("make".to_string());
-    }
-    if runner == Runner::Mise {
-        parts.push("run".to_string());
+        command.arg("make");
     }
-    parts.push(task.to_string());
-    parts.extend(passthrough.iter().cloned());
+    Ok(command)
+}
 
-    format_program_args(program, &parts)
+pub fn ensure_tool(tool: &'static str, resolver: &dyn ToolResolver) -> Result<PathBuf, RtError> {
+    resolver.resolve(tool).ok_or(RtError::ToolMissing { tool })
+}
+
+pub fn preview_command(
+    detection: &Detection,
+    task: &str,
+    passthrough: &[String],
+) -> Result<String, RtError> {
+    let invocation = invocation(detection, task, passthrough)?;
+    Ok(format_program_args(&invocation.program, &invocation.args))
 }
 
 pub fn format_program_args(program: &str, args: &[String]) -> String {
@@ -101,34 +822,37 @@ pub fn format_program_args(program: &str, args: &[String]) -> String {
     parts.extend(args.iter().cloned());
     parts
         .into_iter()
-        .map(|part| quote_shell_arg(&part))
+        .map(|part| shell_quote(&part))
         .collect::<Vec<_>>()
         .join(" ")
 }
 
-fn quote_shell_arg(value: &str) -> String {
+/// Quotes `value` as a single POSIX shell word, leaving plain words untouched.
+pub fn shell_quote(value: &str) -> String {
     if value.is_empty() {
         return "''".to_string();
     }
-    if value.chars().any(|c| {
-        c.is_whitespace()
-            || matches!(
-                c,
-                '\'' | '"' | '\\' | '$' | '`' | '!' | '&' | '|' | ';' | '<' | '>'
-            )
+    if value.chars().all(|c| {
+        c.is_ascii_alphanumeric()
+            || matches!(c, '-' | '_' | '.' | '/' | ':' | '=' | '@' | '%' | '+' | ',')
     }) {
-        return format!("'{}'", value.replace('\'', "'\\''"));
+        return value.to_string();
     }
-    value.to_string()
+    format!("'{}'", value.replace('\'', "'\\''"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{FakeResolver, detection, detection_at};
+
+    fn preview_command(runner: Runner, task: &str, passthrough: &[String]) -> String {
+        super::preview_command(&detection(runner), task, passthrough).unwrap()
+    }
 
     #[test]
     fn base_command_for_cargo_make_includes_make_subcommand() {
-        let command = base_command(Runner::CargoMake).unwrap();
+        let command = base_command(Runner::CargoMake, &WhichResolver).unwrap();
         assert_eq!(command.get_program(), "cargo");
         let args: Vec<String> = command
             .get_args()
@@ -137,9 +861,226 @@ mod tests {
         assert_eq!(args, vec!["make".to_string()]);
     }
 
+    #[test]
+    fn base_command_reports_missing_tool_from_resolver() {
+        let err = base_command(Runner::Justfile, &FakeResolver(None)).unwrap_err();
+        match err {
+            RtError::ToolMissing { tool } => assert_eq!(tool, "just"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn run_with_reports_missing_tool_from_resolver() {
+        let cwd = std::env::current_dir().unwrap();
+        let err = run_with(
+            &detection(Runner::Makefile),
+            "build",
+            &[],
+            &cwd,
+            &RunOptions::default(),
+            &FakeResolver(None),
+        )
+        .unwrap_err();
+        match err {
+            RtError::ToolMissingCommand { tool } => assert_eq!(tool, "make"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn run_with_wraps_task_in_mise_exec() {
+        let cwd = std::env::current_dir().unwrap();
+        let options = RunOptions {
+            mise_exec: true,
+            ..Default::default()
+        };
+        let result = run_with(
+            &detection(Runner::Makefile),
+            "build",
+            &["V=1".to_string()],
+            &cwd,
+            &options,
+            &FakeResolver(Some("true")),
+        )
+        .unwrap();
+        assert_eq!(result.program, "mise");
+        assert_eq!(
+            result.args,
+            vec!["exec", "--", "make", "build", "V=1"]
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn planned_invocation_matches_what_run_spawns() {
+        let passthrough = ["--flag".to_string()];
+        let plain = planned_invocation(
+            &detection(Runner::Justfile),
+            "build",
+            &passthrough,
+            &RunOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            format_program_args(&plain.program, &plain.args),
+            "just build --flag"
+        );
+
+        let options = RunOptions {
+            mise_exec: true,
+            ..Default::default()
+        };
+        let wrapped = planned_invocation(
+            &detection(Runner::Justfile),
+            "build",
+            &passthrough,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            format_program_args(&wrapped.program, &wrapped.args),
+            "mise exec -- just build --flag"
+        );
+    }
+
+    #[test]
+    fn invocation_points_just_at_an_explicit_file() {
+        let detection = Detection {
+            runner_file: PathBuf::from("/tmp/scratch.just"),
+            explicit_file: true,
+            ..detection(Runner::Justfile)
+        };
+        let invocation = invocation(&detection, "build", &["--flag".to_string()]).unwrap();
+        assert_eq!(
+            format_program_args(&invocation.program, &invocation.args),
+            "just --justfile /tmp/scratch.just --working-directory . build --flag"
+        );
+    }
+
+    #[test]
+    fn mise_manages_other_runners_next_to_mise_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let at = |runner| detection_at(runner, dir.path().join("runner-file"));
+        assert!(!mise_manages(&at(Runner::Makefile)));
+
+        std::fs::write(dir.path().join("mise.toml"), "").unwrap();
+        assert!(mise_manages(&at(Runner::Makefile)));
+        assert!(!mise_manages(&at(Runner::Mise)));
+    }
+
+    #[test]
+    fn run_with_kills_task_past_its_timeout() {
+        let cwd = std::env::current_dir().unwrap();
+        // `sleep` stands in for the runner, so the task name is the sleep duration.
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let result = run_with(
+            &detection(Runner::Justfile),
+            "5",
+            &[],
+            &cwd,
+            &options,
+            &FakeResolver(Some("sleep")),
+        )
+        .unwrap();
+        assert_eq!(result.exit_code, exit_code::TIMEOUT);
+        assert!(result.timed_out);
+        assert!(result.duration < Duration::from_secs(5));
+
+        let result = run_with(
+            &detection(Runner::Justfile),
+            "0",
+            &[],
+            &cwd,
+            &options,
+            &FakeResolver(Some("sleep")),
+        )
+        .unwrap();
+        assert_eq!(result.exit_code, 0);
+    }
+
+    #[test]
+    fn run_with_tees_output_into_the_log_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("task.log");
+        std::fs::write(&log, "earlier\n").unwrap();
+        let options = RunOptions {
+            log: Some(log.clone()),
+            ..Default::default()
+        };
+        // `echo` stands in for the runner, so it prints the task name.
+        let result = run_with(
+            &detection(Runner::Justfile),
+            "built",
+            &[],
+            dir.path(),
+            &options,
+            &FakeResolver(Some("echo")),
+        )
+        .unwrap();
+        assert_eq!(result.exit_code, 0);
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "earlier\nbuilt\n");
+    }
+
+    #[test]
+    fn run_with_logs_all_output_of_a_task_exiting_124_itself() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("task.log");
+        let options = RunOptions {
+            log: Some(log.clone()),
+            ..Default::default()
+        };
+        // `sh` stands in for the runner: the task name and passthrough become `sh -c SCRIPT`.
+        let result = run_with(
+            &detection(Runner::Justfile),
+            "-c",
+            &["printf 'one\\ntwo\\n'; exit 124".to_string()],
+            dir.path(),
+            &options,
+            &FakeResolver(Some("sh")),
+        )
+        .unwrap();
+        assert_eq!(result.exit_code, exit_code::TIMEOUT);
+        assert!(!result.timed_out);
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "one\ntwo\n");
+    }
+
+    #[test]
+    fn tee_copies_every_chunk_to_terminal_and_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("task.log");
+        let log = Mutex::new(File::create(&path).unwrap());
+        let mut terminal = Vec::new();
+        tee(&b"line one\npartial"[..], &mut terminal, &log);
+        assert_eq!(terminal, b"line one\npartial");
+        assert_eq!(std::fs::read(&path).unwrap(), b"line one\npartial");
+    }
+
+    #[test]
+    fn run_with_spawns_resolved_program_and_records_tool_name() {
+        let cwd = std::env::current_dir().unwrap();
+        let result = run_with(
+            &detection(Runner::Justfile),
+            "build",
+            &[],
+            &cwd,
+            &RunOptions::default(),
+            &FakeResolver(Some("false")),
+        )
+        .unwrap();
+        assert_eq!(result.exit_code, 1);
+        assert_eq!(result.program, "just");
+        assert_eq!(result.args, vec!["build".to_string()]);
+    }
+
     #[test]
     fn ensure_tool_returns_error_for_missing_binary() {
-        let err = ensure_tool("__rt_missing_tool_for_test__").unwrap_err();
+        let err = ensure_tool("__rt_missing_tool_for_test__", &WhichResolver).unwrap_err();
         match err {
             RtError::ToolMissing { tool } => assert_eq!(tool, "__rt_missing_tool_for_test__"),
             other => panic!("unexpected error: {other:?}"),
@@ -166,6 +1107,119 @@ mod tests {
         assert_eq!(preview, "just test 'hello world' 'a'\\''b' '$HOME'");
     }
 
+    #[test]
+    fn package_scripts_run_through_detected_manager() {
+        let args = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let with_manager = |manager| Detection {
+            package_manager: Some(manager),
+            ..detection(Runner::PackageJson)
+        };
+
+        let npm = with_manager(PackageManager::Npm);
+        assert_eq!(
+            super::preview_command(&npm, "test", &args(&["--watch"])).unwrap(),
+            "npm run test -- --watch"
+        );
+        assert_eq!(
+            super::preview_command(&npm, "test", &args(&["--", "--watch"])).unwrap(),
+            "npm run test -- --watch"
+        );
+        assert_eq!(
+            super::preview_command(&npm, "build", &[]).unwrap(),
+            "npm run build"
+        );
+
+        let pnpm = with_manager(PackageManager::Pnpm);
+        assert_eq!(
+            super::preview_command(&pnpm, "test", &args(&["--watch"])).unwrap(),
+            "pnpm run test --watch"
+        );
+    }
+
+    #[test]
+    fn invocation_targets_workspace_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("package.json");
+        std::fs::write(&root, r#"{"workspaces": ["web"]}"#).unwrap();
+        std::fs::create_dir(dir.path().join("web")).unwrap();
+        std::fs::write(
+            dir.path().join("web/package.json"),
+            r#"{"name": "web", "scripts": {"build": "vite build"}}"#,
+        )
+        .unwrap();
+        let args = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let preview_with = |manager, workspaces, passthrough: &[&str]| {
+            let detection = Detection {
+                runner: Runner::PackageJson,
+                runner_file: root.clone(),
+                package_manager: Some(manager),
+                explicit_file: false,
+                workspaces,
+            };
+            super::preview_command(&detection, "web:build", &args(passthrough)).unwrap()
+        };
+        let preview = |manager, passthrough: &[&str]| preview_with(manager, true, passthrough);
+
+        assert_eq!(
+            preview(PackageManager::Npm, &["--watch"]),
+            "npm run build --workspace web -- --watch"
+        );
+        assert_eq!(
+            preview(PackageManager::Pnpm, &[]),
+            "pnpm --filter web run build"
+        );
+        assert_eq!(
+            preview(PackageManager::Yarn, &[]),
+            "yarn workspace web run build"
+        );
+        assert_eq!(
+            preview(PackageManager::Bun, &[]),
+            "bun run --filter web build"
+        );
+        // Workspace scripts are opt-in; without them, `web:build` is a root script name.
+        assert_eq!(
+            preview_with(PackageManager::Pnpm, false, &[]),
+            "pnpm run web:build"
+        );
+    }
+
+    #[test]
+    fn invocation_picks_unnamed_workspaces_by_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("package.json");
+        std::fs::write(&root, r#"{"workspaces": ["apps/*"]}"#).unwrap();
+        std::fs::create_dir_all(dir.path().join("apps/docs")).unwrap();
+        std::fs::write(
+            dir.path().join("apps/docs/package.json"),
+            r#"{"scripts": {"dev": "vitepress dev"}}"#,
+        )
+        .unwrap();
+        let invocation = |manager| {
+            let detection = Detection {
+                runner: Runner::PackageJson,
+                runner_file: root.clone(),
+                package_manager: Some(manager),
+                explicit_file: false,
+                workspaces: true,
+            };
+            super::preview_command(&detection, "docs:dev", &[])
+        };
+
+        assert_eq!(
+            invocation(PackageManager::Pnpm).unwrap(),
+            "pnpm --filter ./apps/docs run dev"
+        );
+        assert_eq!(
+            invocation(PackageManager::Npm).unwrap(),
+            "npm run dev --workspace ./apps/docs"
+        );
+        // `yarn workspace` only takes names, so an unnamed package is refused up front.
+        match invocation(PackageManager::Yarn).unwrap_err() {
+            RtError::UnnamedYarnWorkspace { selector } => assert_eq!(selector, "./apps/docs"),
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
     #[test]
     fn preview_command_handles_runner_specific_prefixes() {
         assert_eq!(
@@ -176,29 +1230,265 @@ mod tests {
             preview_command(Runner::CargoMake, "build", &[]),
             "cargo make build"
         );
+        assert_eq!(
+            preview_command(Runner::Deno, "dev", &["--port=8000".to_string()]),
+            "deno task dev --port=8000"
+        );
+        assert_eq!(
+            preview_command(Runner::Rake, "db:migrate", &[]),
+            "rake db:migrate"
+        );
+        assert_eq!(
+            preview_command(Runner::Earthly, "build", &["--VERSION=1.2".to_string()]),
+            "earthly +build --VERSION=1.2"
+        );
+        assert_eq!(
+            preview_command(Runner::Mage, "docker:push", &[]),
+            "mage docker:push"
+        );
+        assert_eq!(
+            preview_command(Runner::Composer, "test", &["--filter=Unit".to_string()]),
+            "composer run-script test -- --filter=Unit"
+        );
+    }
+
+    #[test]
+    fn taskfile_passthrough_separates_cli_args_only_for_tasks_using_them() {
+        let args = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            taskfile_passthrough(&args(&["ENV=prod", "extra"]), true),
+            args(&["ENV=prod", "--", "extra"])
+        );
+        assert_eq!(
+            taskfile_passthrough(&args(&["a", "ENV=prod", "--output", "file"]), true),
+            args(&["ENV=prod", "--", "a", "--output", "file"])
+        );
+        assert_eq!(
+            taskfile_passthrough(&args(&["ENV=prod"]), true),
+            args(&["ENV=prod"])
+        );
+        assert_eq!(
+            taskfile_passthrough(&args(&["x", "--", "ENV=prod"]), true),
+            args(&["x", "--", "ENV=prod"])
+        );
+        // `rt build lint` runs two tasks when `build` doesn't use CLI_ARGS.
+        assert_eq!(
+            preview_command(Runner::Taskfile, "build", &args(&["lint", "--force"])),
+            "task build lint --force"
+        );
+    }
+
+    #[test]
+    fn taskfile_invocation_reads_cli_args_use_from_the_taskfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let detection = detection_at(Runner::Taskfile, dir.path().join("Taskfile.yml"));
+        std::fs::write(
+            &detection.runner_file,
+            "version: '3'\ntasks:\n  deploy:\n    cmds:\n      - ./deploy {{.CLI_ARGS}}\n",
+        )
+        .unwrap();
+        let passthrough = vec!["ENV=prod".to_string(), "extra".to_string()];
+        assert_eq!(
+            super::preview_command(&detection, "deploy", &passthrough).unwrap(),
+            "task deploy ENV=prod -- extra"
+        );
+    }
+
+    #[test]
+    fn make_overrides_round_trip_through_preview_and_run() {
+        let args = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let (target, overrides) = make_target_and_overrides("CC=clang", &args(&["build"]));
+        assert_eq!(target, "build");
+        assert_eq!(overrides, args(&["CC=clang"]));
+
+        let detection = detection(Runner::Makefile);
+        assert_eq!(
+            preview_command(Runner::Makefile, &target, &overrides),
+            "make build CC=clang"
+        );
+        let cwd = std::env::current_dir().unwrap();
+        let result = run_with(
+            &detection,
+            &target,
+            &overrides,
+            &cwd,
+            &RunOptions::default(),
+            &FakeResolver(Some("true")),
+        )
+        .unwrap();
+        assert_eq!(result.program, "make");
+        assert_eq!(result.args, args(&["build", "CC=clang"]));
+    }
+
+    #[test]
+    fn make_target_and_overrides_keeps_order_of_remaining_args() {
+        let args = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            make_target_and_overrides("CC=clang", &args(&["O=2", "build", "-j4"])),
+            ("build".to_string(), args(&["CC=clang", "O=2", "-j4"]))
+        );
+        assert_eq!(
+            make_target_and_overrides("build", &args(&["CC=clang"])),
+            ("build".to_string(), args(&["CC=clang"]))
+        );
+        assert_eq!(
+            make_target_and_overrides("CC=clang", &[]),
+            ("CC=clang".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn is_cli_variable_requires_identifier_name() {
+        assert!(is_cli_variable("ENV=prod"));
+        assert!(is_cli_variable("_X="));
+        assert!(!is_cli_variable("--env=prod"));
+        assert!(!is_cli_variable("1X=2"));
+        assert!(!is_cli_variable("plain"));
+    }
+
+    #[test]
+    fn procfile_launcher_prefers_configuration_then_path() {
+        let none = |_: &str| false;
+        let overmind_only = |tool: &str| tool == "overmind";
+        assert_eq!(
+            procfile_launcher_from(Some("sh"), &overmind_only),
+            ProcfileLauncher::Shell
+        );
+        assert_eq!(
+            procfile_launcher_from(None, &overmind_only),
+            ProcfileLauncher::Overmind
+        );
+        assert_eq!(procfile_launcher_from(None, &none), ProcfileLauncher::Shell);
+    }
+
+    #[test]
+    fn procfile_shell_invocation_runs_entry_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Procfile");
+        std::fs::write(&path, "web: bundle exec puma -p $PORT\nworker: sidekiq\n").unwrap();
+        let detection = detection_at(Runner::Procfile, path);
+
+        let passthrough = ["-q".to_string(), "a b".to_string()];
+        let shell =
+            procfile_invocation(&detection, "worker", &passthrough, ProcfileLauncher::Shell)
+                .unwrap();
+        assert_eq!(shell.program, "sh");
+        assert_eq!(
+            shell.args,
+            vec!["-c".to_string(), "sidekiq -q 'a b'".to_string()]
+        );
+
+        let foreman =
+            procfile_invocation(&detection, "web", &[], ProcfileLauncher::Foreman).unwrap();
+        assert_eq!(foreman.program, "foreman");
+        assert_eq!(foreman.args, vec!["start".to_string(), "web".to_string()]);
     }
 
     #[test]
     fn run_program_returns_success_exit_code() {
         let cwd = std::env::current_dir().unwrap();
-        let result = run_program("true", &[], &cwd).unwrap();
+        let result = run_program("true", &[], &cwd, &[]).unwrap();
         assert_eq!(result.exit_code, 0);
     }
 
     #[test]
     fn run_program_returns_command_exit_code() {
         let cwd = std::env::current_dir().unwrap();
-        let result = run_program("false", &[], &cwd).unwrap();
+        let result = run_program("false", &[], &cwd, &[]).unwrap();
         assert_eq!(result.exit_code, 1);
     }
 
     #[test]
     fn run_program_passes_arguments() {
         let cwd = std::env::current_dir().unwrap();
-        let result = run_program("true", &["hello world".to_string()], &cwd).unwrap();
+        let result = run_program("true", &["hello world".to_string()], &cwd, &[]).unwrap();
+        assert_eq!(result.exit_code, 0);
+    }
+
+    #[test]
+    fn run_shell_runs_command_line_through_sh() {
+        let cwd = std::env::current_dir().unwrap();
+        let result = run_shell("exit 3", &cwd, "sh", &[]).unwrap();
+        assert_eq!(result.exit_code, 3);
+        assert_eq!(result.program, "sh");
+        assert_eq!(result.args, vec!["-c".to_string(), "exit 3".to_string()]);
+    }
+
+    #[test]
+    fn shell_invocation_previews_what_run_shell_spawns() {
+        let invocation = shell_invocation("echo hi", "bash");
+        assert_eq!(
+            format_program_args(&invocation.program, &invocation.args),
+            "bash -c 'echo hi'"
+        );
+    }
+
+    #[test]
+    fn run_shell_passes_env_assignments() {
+        let cwd = std::env::current_dir().unwrap();
+        let env = [("RT_TEST_FOO".to_string(), "bar".to_string())];
+        let result = run_shell("test \"$RT_TEST_FOO\" = bar", &cwd, "sh", &env).unwrap();
         assert_eq!(result.exit_code, 0);
     }
 
+    #[test]
+    fn run_shell_spawns_the_chosen_shell() {
+        let cwd = std::env::current_dir().unwrap();
+        let result = run_shell("exit 4", &cwd, "/bin/sh", &[]).unwrap();
+        assert_eq!(result.exit_code, 4);
+        assert_eq!(result.program, "/bin/sh");
+        assert_eq!(result.args, vec!["-c".to_string(), "exit 4".to_string()]);
+    }
+
+    #[test]
+    fn editor_command_passes_line_before_path() {
+        let command = editor_command("code -w", Path::new("justfile"), Some(12));
+        assert_eq!(command.get_program(), "code");
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(args, vec!["-w", "+12", "justfile"]);
+    }
+
+    #[test]
+    fn shell_quote_round_trips_through_shell_word_splitting() {
+        let samples = [
+            "",
+            "plain",
+            "--flag=value",
+            "hello world",
+            "a'b",
+            "''",
+            "$HOME",
+            "`id`",
+            "a\"b",
+            "back\\slash",
+            "semi;colon",
+            "glob*?",
+            "~user",
+            "#comment",
+            "tab\tnew\nline",
+            "(sub)",
+            "{brace}",
+            "[set]",
+            "x&&y",
+            "pipe|",
+            "<in>",
+            "ünïcödé",
+            "!bang",
+        ];
+        for first in samples {
+            for second in samples {
+                let args = vec![first.to_string(), second.to_string()];
+                let line = format_program_args("just", &args);
+                let split = shell_words::split(&line).unwrap();
+                assert_eq!(split[0], "just", "line: {line}");
+                assert_eq!(&split[1..], &args[..], "line: {line}");
+            }
+        }
+    }
+
     #[test]
     fn format_program_args_quotes_special_args() {
         let command = format_program_args(
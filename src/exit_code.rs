@@ -0,0 +1,52 @@
+//! Exit codes rt reports.
+//!
+//! A task that runs exits rt with the task's own code, so rt keeps its own failures in the
+//! 120–125 range, which task runners and shells leave alone by convention. Scripts can tell
+//! "rt could not run the task" from "the task exited N" by checking that range, except for
+//! [`TIMEOUT`]: that task did run, and rt killed it.
+
+use std::process::ExitStatus;
+
+/// Nothing to run: no runner file, missing tool, unknown task, or no tasks listed.
+pub const NOTHING_TO_RUN: i32 = 120;
+/// rt itself failed: I/O, spawning, config, bookmark, or prompt errors.
+pub const RT_ERROR: i32 = 121;
+/// The command line couldn't be parsed.
+pub const USAGE: i32 = 122;
+/// The task was killed after outliving its timeout, as with `timeout(1)`.
+pub const TIMEOUT: i32 = 124;
+
+/// The code a finished child reports, or `128 + signal` when a signal killed it, like shells do.
+pub fn from_status(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    RT_ERROR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rt_codes_stay_in_reserved_range() {
+        for code in [NOTHING_TO_RUN, RT_ERROR, USAGE, TIMEOUT] {
+            assert!((120..=125).contains(&code), "{code}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_status_maps_signals_like_shells() {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(from_status(ExitStatus::from_raw(3 << 8)), 3);
+        assert_eq!(from_status(ExitStatus::from_raw(9)), 137);
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// Output format for `rt --graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Text,
+    Dot,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(Self::Text),
+            "dot" => Ok(Self::Dot),
+            other => Err(format!(
+                "unknown graph format `{other}` (expected `text` or `dot`)"
+            )),
+        }
+    }
+}
+
+/// Renders tasks and their dependencies in the given format.
+pub fn render(format: GraphFormat, nodes: &[(String, Vec<String>)]) -> String {
+    match format {
+        GraphFormat::Text => render_text(nodes),
+        GraphFormat::Dot => render_dot(nodes),
+    }
+}
+
+/// Renders a tree per top-level task (one nothing else depends on).
+fn render_text(nodes: &[(String, Vec<String>)]) -> String {
+    let depended_on: HashSet<&str> = nodes
+        .iter()
+        .flat_map(|(_, deps)| deps.iter().map(String::as_str))
+        .collect();
+    let mut printed = HashSet::new();
+    let mut out = String::new();
+
+    let roots = nodes
+        .iter()
+        .filter(|(name, _)| !depended_on.contains(name.as_str()));
+    for (name, _) in roots {
+        write_tree(nodes, name, "", &mut vec![], &mut printed, &mut out);
+    }
+    // Tasks only reachable through a cycle have no root; show them on their own.
+    for (name, _) in nodes {
+        if !printed.contains(name.as_str()) {
+            write_tree(nodes, name, "", &mut vec![], &mut printed, &mut out);
+        }
+    }
+    out
+}
+
+fn write_tree<'a>(
+    nodes: &'a [(String, Vec<String>)],
+    name: &'a str,
+    prefix: &str,
+    ancestors: &mut Vec<&'a str>,
+    printed: &mut HashSet<&'a str>,
+    out: &mut String,
+) {
+    if ancestors.is_empty() {
+        out.push_str(name);
+        out.push('\n');
+    }
+    printed.insert(name);
+
+    let deps = nodes
+        .iter()
+        .find(|(node, _)| node == name)
+        .map(|(_, deps)| deps.as_slice())
+        .unwrap_or_default();
+    ancestors.push(name);
+    for (idx, dep) in deps.iter().enumerate() {
+        let last = idx + 1 == deps.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        if ancestors.contains(&dep.as_str()) {
+            out.push_str(&format!("{prefix}{branch}{dep} (cycle)\n"));
+            continue;
+        }
+        // A shared dependency is expanded once; later occurrences point back to it.
+        if printed.contains(dep.as_str()) && has_deps(nodes, dep) {
+            out.push_str(&format!("{prefix}{branch}{dep} (see above)\n"));
+            continue;
+        }
+        out.push_str(&format!("{prefix}{branch}{dep}\n"));
+        write_tree(
+            nodes,
+            dep,
+            &format!("{prefix}{indent}"),
+            ancestors,
+            printed,
+            out,
+        );
+    }
+    ancestors.pop();
+}
+
+fn has_deps(nodes: &[(String, Vec<String>)], name: &str) -> bool {
+    nodes
+        .iter()
+        .any(|(node, deps)| node == name && !deps.is_empty())
+}
+
+fn render_dot(nodes: &[(String, Vec<String>)]) -> String {
+    let mut out = String::from("digraph {\n");
+    for (name, _) in nodes {
+        out.push_str(&format!("  {};\n", dot_id(name)));
+    }
+    for (name, deps) in nodes {
+        for dep in deps {
+            out.push_str(&format!("  {} -> {};\n", dot_id(name), dot_id(dep)));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes() -> Vec<(String, Vec<String>)> {
+        vec![
+            ("fmt".to_string(), vec![]),
+            ("build".to_string(), vec!["fmt".to_string()]),
+            (
+                "deploy".to_string(),
+                vec!["build".to_string(), "test".to_string()],
+            ),
+            ("test".to_string(), vec!["build".to_string()]),
+            ("lint".to_string(), vec![]),
+        ]
+    }
+
+    #[test]
+    fn render_text_prints_trees_from_top_level_tasks() {
+        assert_eq!(
+            render(GraphFormat::Text, &nodes()),
+            "\
+deploy
+├── build
+│   └── fmt
+└── test
+    └── build (see above)
+lint
+"
+        );
+    }
+
+    #[test]
+    fn render_text_expands_shared_dependencies_once() {
+        let nodes = vec![
+            (
+                "release".to_string(),
+                vec!["web".to_string(), "api".to_string()],
+            ),
+            ("web".to_string(), vec!["deps".to_string()]),
+            ("api".to_string(), vec!["deps".to_string()]),
+            ("deps".to_string(), vec!["fetch".to_string()]),
+            ("fetch".to_string(), vec![]),
+        ];
+        assert_eq!(
+            render(GraphFormat::Text, &nodes),
+            "\
+release
+├── web
+│   └── deps
+│       └── fetch
+└── api
+    └── deps (see above)
+"
+        );
+    }
+
+    #[test]
+    fn render_text_marks_cycles() {
+        let nodes = vec![
+            ("a".to_string(), vec!["b".to_string()]),
+            ("b".to_string(), vec!["a".to_string()]),
+        ];
+        assert_eq!(
+            render(GraphFormat::Text, &nodes),
+            "a\n└── b\n    └── a (cycle)\n"
+        );
+    }
+
+    #[test]
+    fn render_dot_emits_nodes_and_edges() {
+        let dot = render(GraphFormat::Dot, &nodes());
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.contains("  \"lint\";\n"));
+        assert!(dot.contains("  \"deploy\" -> \"build\";\n"));
+        assert!(dot.contains("  \"test\" -> \"build\";\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn graph_format_parses_known_names() {
+        assert_eq!("dot".parse::<GraphFormat>(), Ok(GraphFormat::Dot));
+        assert_eq!("text".parse::<GraphFormat>(), Ok(GraphFormat::Text));
+        assert!("svg".parse::<GraphFormat>().is_err());
+    }
+}
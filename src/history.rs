@@ -1,12 +1,21 @@
+use std::collections::HashMap;
 use std::env;
 use std::fs::{self, OpenOptions};
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::num::NonZeroU8;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
-use time::format_description::well_known::Rfc3339;
+use time::format_description::well_known::iso8601::{Config, EncodedConfig, TimePrecision};
+use time::format_description::well_known::{Iso8601, Rfc3339};
+
+use crate::exec::format_program_args;
+
+/// Schema version written by this build; newer records still parse, minus the fields we don't know.
+pub const SCHEMA_VERSION: u8 = 3;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HistoryRecord {
@@ -28,6 +37,23 @@ pub struct HistoryRecord {
     // Exit code is required to distinguish successful and failed runs.
     #[serde(rename = "exit_code")]
     pub exit_code: i32,
+    // Duration was added in schema 3, so older records leave it out.
+    #[serde(
+        rename = "duration_ms",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration_ms: Option<u64>,
+    // Hashed records keep the program but replace `args` with a hash of the command line.
+    #[serde(rename = "hashed", default, skip_serializing_if = "is_false")]
+    pub hashed: bool,
+    // Description of the task picked in rt's selector; absent for other runs and older records.
+    #[serde(rename = "desc", default, skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
 
 pub struct RecordInput<'a> {
@@ -35,19 +61,70 @@ pub struct RecordInput<'a> {
     pub args: &'a [String],
     pub working_directory: &'a Path,
     pub exit_code: i32,
+    pub duration: Duration,
+    /// The task's description, when it's known from the task list.
+    pub description: Option<&'a str>,
+    /// Record a hash of the command instead of its arguments (`[history] hash_commands`).
+    pub hash_command: bool,
+    /// Skip the record when it repeats the newest one (`[history] dedupe`).
+    pub dedupe: bool,
 }
 
 impl HistoryRecord {
     pub fn from_input(input: RecordInput<'_>) -> Self {
         Self {
-            schema_version: 2,
+            schema_version: SCHEMA_VERSION,
             timestamp: current_timestamp(),
             program: input.program.to_string(),
-            args: input.args.to_vec(),
+            args: if input.hash_command {
+                vec![command_hash(input.program, input.args)]
+            } else {
+                input.args.to_vec()
+            },
             working_directory: input.working_directory.to_string_lossy().into_owned(),
             exit_code: input.exit_code,
+            duration_ms: Some(u64::try_from(input.duration.as_millis()).unwrap_or(u64::MAX)),
+            hashed: input.hash_command,
+            // A description would say which task a hashed record ran.
+            desc: input
+                .description
+                .filter(|_| !input.hash_command)
+                .map(str::to_string),
         }
     }
+
+    /// Whether `other` ran the same command in the same directory, whatever its outcome.
+    pub fn same_command(&self, other: &HistoryRecord) -> bool {
+        self.program == other.program
+            && self.args == other.args
+            && self.working_directory == other.working_directory
+    }
+
+    /// The command line as shown to users; hashed records show `program #hash`.
+    pub fn display_command(&self) -> String {
+        if self.hashed {
+            format!("{} #{}", self.program, self.args.join(" "))
+        } else {
+            format_program_args(&self.program, &self.args)
+        }
+    }
+}
+
+/// A stable 64-bit FNV-1a hash of the command line, as hex.
+///
+/// The standard library's hasher may change between Rust releases, which would split the
+/// counts of one command across rt builds. The hash is unsalted, so it only obscures commands:
+/// short ones can be recovered by hashing guesses.
+fn command_hash(program: &str, args: &[String]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let hash = std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .flat_map(|part| part.bytes().chain(std::iter::once(0)))
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        });
+    format!("{hash:016x}")
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +144,18 @@ impl HistoryStore {
     }
 
     pub fn append(&self, record: &HistoryRecord) -> io::Result<()> {
+        self.append_with(record, false).map(|_| ())
+    }
+
+    /// Appends `record` unless the newest record ran the same command in the same directory.
+    ///
+    /// Exit code and duration don't matter, so rerunning a failing task until it passes leaves
+    /// one line. Returns whether the record was written.
+    pub fn append_unless_repeated(&self, record: &HistoryRecord) -> io::Result<bool> {
+        self.append_with(record, true)
+    }
+
+    fn append_with(&self, record: &HistoryRecord, dedupe: bool) -> io::Result<bool> {
         if let Some(parent) = self.path.parent() {
             fs::create_dir_all(parent)?;
         }
@@ -74,12 +163,23 @@ impl HistoryStore {
         let mut file = open_history_file_for_append(&self.path)?;
         file.try_lock_exclusive()?;
 
+        // Checked under the lock, so a concurrent run can't slip a record in between.
+        if dedupe
+            && self
+                .read_last(1)?
+                .last()
+                .is_some_and(|last| last.record.same_command(record))
+        {
+            file.unlock()?;
+            return Ok(false);
+        }
+
         let json =
             serde_json::to_string(record).map_err(|err| io::Error::other(format!("{err}")))?;
         writeln!(file, "{json}")?;
         file.flush()?;
         file.unlock()?;
-        Ok(())
+        Ok(true)
     }
 
     pub fn read_all(&self) -> io::Result<Vec<StoredRecord>> {
@@ -93,16 +193,264 @@ impl HistoryStore {
 
         for line in reader.lines() {
             let line = line?;
-            if line.trim().is_empty() {
-                continue;
-            }
-            if let Ok(record) = serde_json::from_str::<HistoryRecord>(&line) {
-                records.push(StoredRecord { raw: line, record });
+            if let Some(record) = parse_line(line) {
+                records.push(record);
             }
         }
 
         Ok(records)
     }
+
+    /// Returns the last `n` valid records, oldest first, reading the file backward in blocks.
+    ///
+    /// Only the tail of the file is read and parsed, so recent history stays cheap to load from a
+    /// large file.
+    pub fn read_last(&self, n: usize) -> io::Result<Vec<StoredRecord>> {
+        self.read_last_in_blocks(n, READ_BLOCK_SIZE)
+    }
+
+    fn read_last_in_blocks(&self, n: usize, block_size: u64) -> io::Result<Vec<StoredRecord>> {
+        if n == 0 || !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut file = OpenOptions::new().read(true).open(&self.path)?;
+        let mut pos = file.seek(SeekFrom::End(0))?;
+        // Bytes of a line that continues into an earlier block, carried over until its start is read.
+        let mut partial = Vec::new();
+        let mut newest_first = Vec::new();
+
+        while pos > 0 && newest_first.len() < n {
+            let len = block_size.min(pos);
+            pos -= len;
+            file.seek(SeekFrom::Start(pos))?;
+            let mut block = vec![0; len as usize];
+            file.read_exact(&mut block)?;
+            block.append(&mut partial);
+
+            let mut lines = block.split(|byte| *byte == b'\n');
+            if pos > 0 {
+                partial = lines.next().unwrap_or_default().to_vec();
+            }
+            let complete: Vec<&[u8]> = lines.collect();
+            for line in complete.into_iter().rev() {
+                let Ok(line) = String::from_utf8(line.to_vec()) else {
+                    continue;
+                };
+                if let Some(record) = parse_line(line) {
+                    newest_first.push(record);
+                    if newest_first.len() == n {
+                        break;
+                    }
+                }
+            }
+        }
+
+        newest_first.reverse();
+        Ok(newest_first)
+    }
+}
+
+/// Returns a warning when `records` include any written by a newer rt than this build.
+pub fn newer_schema_warning(records: &[StoredRecord]) -> Option<String> {
+    let newest = records
+        .iter()
+        .map(|entry| entry.record.schema_version)
+        .max()?;
+    (newest > SCHEMA_VERSION).then(|| {
+        format!(
+            "history contains records from a newer rt (v={newest}); some fields may be ignored."
+        )
+    })
+}
+
+/// Groups history by working directory, most recently active first, newest command first within.
+pub fn format_cwd_tree(records: &[StoredRecord]) -> String {
+    let mut groups: Vec<(&str, Vec<String>)> = Vec::new();
+    for entry in records.iter().rev() {
+        let record = &entry.record;
+        let command = record.display_command();
+        match groups
+            .iter_mut()
+            .find(|(dir, _)| *dir == record.working_directory)
+        {
+            Some((_, commands)) => commands.push(command),
+            None => groups.push((&record.working_directory, vec![command])),
+        }
+    }
+
+    let mut out = String::new();
+    for (idx, (dir, commands)) in groups.iter().enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        out.push_str(dir);
+        out.push('\n');
+        for command in commands {
+            out.push_str("  ");
+            out.push_str(command);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Aggregated run counts and durations over a set of history records.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct HistorySummary {
+    pub runs: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Per command line, most-run first.
+    pub commands: Vec<UsageStats>,
+    /// Per engine, the program's file name (`just`, `make`, ...), most-run first.
+    pub engines: Vec<UsageStats>,
+    /// Per working directory, most-run first.
+    pub directories: Vec<UsageStats>,
+}
+
+/// Counts for one command, engine, or directory in a [`HistorySummary`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UsageStats {
+    pub name: String,
+    pub runs: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub total_duration_ms: u64,
+    /// Average over runs that recorded a duration; `None` when none did (schema 2 records).
+    pub avg_duration_ms: Option<u64>,
+    #[serde(skip)]
+    timed_runs: u64,
+}
+
+impl UsageStats {
+    fn add(&mut self, record: &HistoryRecord) {
+        self.runs += 1;
+        if record.exit_code == 0 {
+            self.succeeded += 1;
+        } else {
+            self.failed += 1;
+        }
+        if let Some(duration_ms) = record.duration_ms {
+            self.total_duration_ms = self.total_duration_ms.saturating_add(duration_ms);
+            self.timed_runs += 1;
+            self.avg_duration_ms = Some(self.total_duration_ms / self.timed_runs);
+        }
+    }
+}
+
+/// Aggregates `records` by command line, engine, and working directory.
+pub fn summarize(records: &[StoredRecord]) -> HistorySummary {
+    let mut commands = HashMap::new();
+    let mut engines = HashMap::new();
+    let mut directories = HashMap::new();
+    let mut total = UsageStats::default();
+    for entry in records {
+        let record = &entry.record;
+        total.add(record);
+        let command = record.display_command();
+        let engine = Path::new(&record.program).file_name().map_or_else(
+            || record.program.clone(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        for (groups, key) in [
+            (&mut commands, command),
+            (&mut engines, engine),
+            (&mut directories, record.working_directory.clone()),
+        ] {
+            groups
+                .entry(key.clone())
+                .or_insert_with(|| UsageStats {
+                    name: key,
+                    ..UsageStats::default()
+                })
+                .add(record);
+        }
+    }
+    HistorySummary {
+        runs: total.runs,
+        succeeded: total.succeeded,
+        failed: total.failed,
+        commands: most_run_first(commands),
+        engines: most_run_first(engines),
+        directories: most_run_first(directories),
+    }
+}
+
+fn most_run_first(groups: HashMap<String, UsageStats>) -> Vec<UsageStats> {
+    let mut stats: Vec<UsageStats> = groups.into_values().collect();
+    stats.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.name.cmp(&b.name)));
+    stats
+}
+
+/// Renders `summary` as one JSON object for dashboards, stamped with `generated_at`.
+pub fn summary_json(summary: &HistorySummary, generated_at: &str) -> String {
+    let mut value = serde_json::to_value(summary).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.insert("generated_at".to_string(), generated_at.into());
+    }
+    value.to_string()
+}
+
+/// Commands listed by `--stats`.
+const STATS_TOP_COMMANDS: usize = 10;
+
+/// Renders `--stats` as compact tables: overall counts, most-run commands, and engines.
+pub fn format_history_stats(summary: &HistorySummary) -> String {
+    let mut out = format!(
+        "{} runs: {} succeeded, {} failed\n",
+        summary.runs, summary.succeeded, summary.failed
+    );
+    if summary.runs == 0 {
+        return out;
+    }
+
+    out.push_str("\nMost run:\n  RUNS  FAILED  COMMAND\n");
+    for stats in summary.commands.iter().take(STATS_TOP_COMMANDS) {
+        out.push_str(&format!(
+            "  {:>4}  {:>6}  {}\n",
+            stats.runs, stats.failed, stats.name
+        ));
+    }
+
+    let width = summary
+        .engines
+        .iter()
+        .map(|stats| stats.name.len())
+        .max()
+        .unwrap_or_default()
+        .max("ENGINE".len());
+    out.push_str(&format!(
+        "\nBy engine:\n  {:<width$}  RUNS  AVG\n",
+        "ENGINE"
+    ));
+    for stats in &summary.engines {
+        let avg = stats.avg_duration_ms.map_or_else(
+            || "-".to_string(),
+            |ms| format!("{:.1}s", ms as f64 / 1000.0),
+        );
+        out.push_str(&format!(
+            "  {:<width$}  {:>4}  {avg}\n",
+            stats.name, stats.runs
+        ));
+    }
+    out
+}
+
+/// Block size for reading history backward; a few typical records per block.
+const READ_BLOCK_SIZE: u64 = 8 * 1024;
+
+fn parse_line(line: String) -> Option<StoredRecord> {
+    let line = line.trim_end_matches('\r');
+    if line.trim().is_empty() {
+        return None;
+    }
+    serde_json::from_str::<HistoryRecord>(line)
+        .ok()
+        .map(|record| StoredRecord {
+            raw: line.to_string(),
+            record,
+        })
 }
 
 fn open_history_file_for_append(path: &Path) -> io::Result<std::fs::File> {
@@ -117,17 +465,23 @@ fn open_history_file_for_append(path: &Path) -> io::Result<std::fs::File> {
 }
 
 pub fn append_default(input: RecordInput<'_>) -> io::Result<()> {
+    let dedupe = input.dedupe;
     let record = HistoryRecord::from_input(input);
-    append_record_default(&record)
+    append_record_default(&record, dedupe)
 }
 
-fn append_record_default(record: &HistoryRecord) -> io::Result<()> {
+fn append_record_default(record: &HistoryRecord, dedupe: bool) -> io::Result<()> {
     let candidates = default_history_paths();
     let mut last_error = None;
 
     for path in candidates {
         let store = HistoryStore::new(path);
-        match store.append(record) {
+        let appended = if dedupe {
+            store.append_unless_repeated(record).map(|_| ())
+        } else {
+            store.append(record)
+        };
+        match appended {
             Ok(()) => return Ok(()),
             Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Err(err),
             Err(err) => last_error = Some(err),
@@ -137,8 +491,25 @@ fn append_record_default(record: &HistoryRecord) -> io::Result<()> {
     Err(last_error.unwrap_or_else(|| io::Error::other("failed to write history")))
 }
 
-pub fn read_default() -> io::Result<Vec<StoredRecord>> {
-    read_from_paths(default_history_paths())
+/// Reads history from every default location, oldest first, keeping at most the newest `limit`.
+pub fn read_default(limit: Option<usize>) -> io::Result<Vec<StoredRecord>> {
+    read_from_paths(default_history_paths(), limit)
+}
+
+/// Empties every default history file that exists, so no location keeps old records.
+pub fn clear_default() -> io::Result<()> {
+    clear_paths(&default_history_paths())
+}
+
+fn clear_paths(paths: &[PathBuf]) -> io::Result<()> {
+    for path in paths.iter().filter(|path| path.is_file()) {
+        let file = OpenOptions::new().write(true).open(path)?;
+        // Truncate under the same lock appends take, so a concurrent run can't interleave.
+        file.lock_exclusive()?;
+        file.set_len(0)?;
+        file.unlock()?;
+    }
+    Ok(())
 }
 
 fn default_history_paths() -> Vec<PathBuf> {
@@ -155,13 +526,17 @@ fn default_history_paths() -> Vec<PathBuf> {
     )
 }
 
-fn read_from_paths(paths: Vec<PathBuf>) -> io::Result<Vec<StoredRecord>> {
+fn read_from_paths(paths: Vec<PathBuf>, limit: Option<usize>) -> io::Result<Vec<StoredRecord>> {
     let mut all_records = Vec::new();
     let mut last_error = None;
 
     for path in paths {
         let store = HistoryStore::new(path);
-        match store.read_all() {
+        let records = match limit {
+            Some(limit) => store.read_last(limit),
+            None => store.read_all(),
+        };
+        match records {
             Ok(mut records) => all_records.append(&mut records),
             Err(err) => last_error = Some(err),
         }
@@ -171,6 +546,8 @@ fn read_from_paths(paths: Vec<PathBuf>) -> io::Result<Vec<StoredRecord>> {
         return last_error.map_or_else(|| Ok(Vec::new()), Err);
     }
 
+    // `sort_by` is stable, so equal timestamps keep read order: earlier paths first, then line
+    // order.
     all_records.sort_by(|a, b| {
         let a_ts = OffsetDateTime::parse(&a.record.timestamp, &Rfc3339).ok();
         let b_ts = OffsetDateTime::parse(&b.record.timestamp, &Rfc3339).ok();
@@ -181,6 +558,10 @@ fn read_from_paths(paths: Vec<PathBuf>) -> io::Result<Vec<StoredRecord>> {
             (None, None) => a.record.timestamp.cmp(&b.record.timestamp),
         }
     });
+    if let Some(limit) = limit {
+        let excess = all_records.len().saturating_sub(limit);
+        all_records.drain(..excess);
+    }
     Ok(all_records)
 }
 
@@ -235,10 +616,21 @@ fn push_unique_path(paths: &mut Vec<PathBuf>, path: PathBuf) {
     }
 }
 
-fn current_timestamp() -> String {
+/// RFC 3339 with exactly three fractional digits, so runs within the same second still sort apart.
+const TIMESTAMP_FORMAT: EncodedConfig = Config::DEFAULT
+    .set_time_precision(TimePrecision::Second {
+        decimal_digits: NonZeroU8::new(3),
+    })
+    .encode();
+
+pub fn current_timestamp() -> String {
     let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-    now.format(&Rfc3339)
-        .unwrap_or_else(|_| "1970-01-01T00:00:00+00:00".to_string())
+    format_timestamp(now)
+}
+
+fn format_timestamp(at: OffsetDateTime) -> String {
+    at.format(&Iso8601::<TIMESTAMP_FORMAT>)
+        .unwrap_or_else(|_| "1970-01-01T00:00:00.000+00:00".to_string())
 }
 
 #[cfg(test)]
@@ -254,6 +646,9 @@ mod tests {
             args: args.iter().map(|arg| (*arg).to_string()).collect(),
             working_directory: "/repo".to_string(),
             exit_code,
+            duration_ms: None,
+            hashed: false,
+            desc: None,
         }
     }
 
@@ -302,15 +697,43 @@ mod tests {
             args: &["test".to_string()],
             working_directory: &cwd,
             exit_code: 7,
+            duration: Duration::from_millis(1500),
+            description: Some("run the tests"),
+            hash_command: false,
+            dedupe: false,
         });
-        assert_eq!(record.schema_version, 2);
+        assert_eq!(record.schema_version, 3);
+        assert_eq!(record.duration_ms, Some(1500));
         assert_eq!(record.program, "just");
         assert_eq!(record.args, vec!["test".to_string()]);
         assert_eq!(record.working_directory, "/repo");
         assert_eq!(record.exit_code, 7);
+        assert_eq!(record.desc.as_deref(), Some("run the tests"));
         assert!(record.timestamp.contains('T'));
     }
 
+    #[test]
+    fn clear_paths_empties_existing_files_and_skips_missing_ones() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("state/history.jsonl");
+        let missing = dir.path().join("missing/history.jsonl");
+        let store = HistoryStore::new(first.clone());
+        store
+            .append(&sample_record(
+                "2026-02-21T12:34:56+09:00",
+                "make",
+                &["build"],
+                0,
+            ))
+            .unwrap();
+
+        clear_paths(&[first.clone(), missing.clone()]).unwrap();
+
+        assert!(store.read_all().unwrap().is_empty());
+        assert_eq!(fs::metadata(&first).unwrap().len(), 0);
+        assert!(!missing.exists());
+    }
+
     #[test]
     fn store_append_creates_directories_and_can_read_back() {
         let dir = tempdir().unwrap();
@@ -326,6 +749,138 @@ mod tests {
         assert_eq!(records[0].record, record);
     }
 
+    #[test]
+    fn from_input_hashes_the_command_when_asked() {
+        let cwd = PathBuf::from("/repo");
+        let record_for = |args: &[String]| {
+            HistoryRecord::from_input(RecordInput {
+                program: "just",
+                args,
+                working_directory: &cwd,
+                exit_code: 0,
+                duration: Duration::from_millis(20),
+                description: Some("deploy to prod"),
+                hash_command: true,
+                dedupe: false,
+            })
+        };
+        let secret = ["deploy".to_string(), "TOKEN=abc".to_string()];
+        let record = record_for(&secret);
+
+        assert!(record.hashed);
+        assert_eq!(record.program, "just");
+        assert_eq!(record.args, vec!["e880c74de76e29ae".to_string()]);
+        assert_eq!(record.display_command(), "just #e880c74de76e29ae");
+        assert_eq!(record.desc, None);
+        assert!(!serde_json::to_string(&record).unwrap().contains("TOKEN"));
+        assert_eq!(record_for(&secret).args, record.args);
+        assert_ne!(record_for(&["deploy".to_string()]).args, record.args);
+    }
+
+    fn stored(record: HistoryRecord) -> StoredRecord {
+        StoredRecord {
+            raw: serde_json::to_string(&record).unwrap(),
+            record,
+        }
+    }
+
+    #[test]
+    fn summary_json_aggregates_commands_engines_and_directories() {
+        let timed = |exit_code, duration_ms| HistoryRecord {
+            duration_ms: Some(duration_ms),
+            ..sample_record(
+                "2026-02-21T12:34:56+09:00",
+                "/usr/bin/just",
+                &["test"],
+                exit_code,
+            )
+        };
+        let mut other_dir = sample_record("2026-02-21T12:40:00+09:00", "make", &["build"], 0);
+        other_dir.working_directory = "/other".to_string();
+        let records = vec![
+            stored(timed(0, 100)),
+            stored(timed(1, 300)),
+            stored(sample_record(
+                "2026-02-21T12:35:00+09:00",
+                "/usr/bin/just",
+                &["test"],
+                0,
+            )),
+            stored(other_dir),
+        ];
+
+        let json = summary_json(&summarize(&records), "2026-02-22T00:00:00+00:00");
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "generated_at": "2026-02-22T00:00:00+00:00",
+                "runs": 4,
+                "succeeded": 3,
+                "failed": 1,
+                "commands": [
+                    {"name": "/usr/bin/just test", "runs": 3, "succeeded": 2, "failed": 1, "total_duration_ms": 400, "avg_duration_ms": 200},
+                    {"name": "make build", "runs": 1, "succeeded": 1, "failed": 0, "total_duration_ms": 0, "avg_duration_ms": null}
+                ],
+                "engines": [
+                    {"name": "just", "runs": 3, "succeeded": 2, "failed": 1, "total_duration_ms": 400, "avg_duration_ms": 200},
+                    {"name": "make", "runs": 1, "succeeded": 1, "failed": 0, "total_duration_ms": 0, "avg_duration_ms": null}
+                ],
+                "directories": [
+                    {"name": "/repo", "runs": 3, "succeeded": 2, "failed": 1, "total_duration_ms": 400, "avg_duration_ms": 200},
+                    {"name": "/other", "runs": 1, "succeeded": 1, "failed": 0, "total_duration_ms": 0, "avg_duration_ms": null}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn append_unless_repeated_skips_the_same_command_in_the_same_directory() {
+        let dir = tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().join("history.jsonl"));
+        let first = sample_record("2026-02-21T12:00:00+09:00", "just", &["test"], 1);
+        let rerun = HistoryRecord {
+            duration_ms: Some(40),
+            ..sample_record("2026-02-21T12:01:00+09:00", "just", &["test"], 0)
+        };
+        let mut elsewhere = rerun.clone();
+        elsewhere.working_directory = "/other".to_string();
+
+        assert!(store.append_unless_repeated(&first).unwrap());
+        assert!(!store.append_unless_repeated(&rerun).unwrap());
+        assert!(store.append_unless_repeated(&elsewhere).unwrap());
+        assert!(store.append_unless_repeated(&first).unwrap());
+
+        let records = store.read_all().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].record, first);
+        assert_eq!(records[1].record, elsewhere);
+    }
+
+    #[test]
+    fn future_versioned_records_parse_with_a_warning() {
+        let dir = tempdir().unwrap();
+        let history_path = dir.path().join("history.jsonl");
+        fs::write(
+            &history_path,
+            concat!(
+                "{\"version\":2,\"timestamp\":\"2026-02-21T12:34:56+09:00\",\"program\":\"make\",\"args\":[],\"working_directory\":\"/repo\",\"exit_code\":0}\n",
+                "{\"version\":4,\"timestamp\":\"2026-02-21T12:35:56+09:00\",\"program\":\"just\",\"args\":[\"test\"],\"working_directory\":\"/repo\",\"exit_code\":0,\"host\":\"ci\"}\n"
+            ),
+        )
+        .unwrap();
+
+        let records = HistoryStore::new(history_path).read_all().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].record.program, "just");
+        assert_eq!(
+            newer_schema_warning(&records).as_deref(),
+            Some("history contains records from a newer rt (v=4); some fields may be ignored.")
+        );
+        assert_eq!(newer_schema_warning(&records[..1]), None);
+    }
+
     #[test]
     fn store_read_all_ignores_invalid_json_lines() {
         let dir = tempdir().unwrap();
@@ -346,6 +901,70 @@ mod tests {
         assert_eq!(records[0].record.args, vec!["build".to_string()]);
     }
 
+    #[test]
+    fn timestamps_round_trip_with_milliseconds() {
+        let at = OffsetDateTime::parse("2026-02-21T12:00:00.123456789+09:00", &Rfc3339).unwrap();
+        let formatted = format_timestamp(at);
+        assert_eq!(formatted, "2026-02-21T12:00:00.123+09:00");
+
+        let parsed = OffsetDateTime::parse(&formatted, &Rfc3339).unwrap();
+        assert_eq!(parsed.millisecond(), 123);
+        assert_eq!(parsed.offset(), at.offset());
+        assert_eq!(parsed.unix_timestamp(), at.unix_timestamp());
+
+        let whole_second = OffsetDateTime::parse("2026-02-21T03:00:00Z", &Rfc3339).unwrap();
+        assert_eq!(format_timestamp(whole_second), "2026-02-21T03:00:00.000Z");
+        assert!(OffsetDateTime::parse(&current_timestamp(), &Rfc3339).is_ok());
+    }
+
+    #[test]
+    fn read_from_paths_sorts_by_milliseconds_within_a_second() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let store = HistoryStore::new(path.clone());
+        for (ts, arg) in [
+            ("2026-02-21T12:00:00.250+09:00", "second"),
+            ("2026-02-21T12:00:00.005+09:00", "first"),
+            ("2026-02-21T12:00:00.900+09:00", "third"),
+        ] {
+            store.append(&sample_record(ts, "make", &[arg], 0)).unwrap();
+        }
+
+        let records = read_from_paths(vec![path], None).unwrap();
+        let args: Vec<&str> = records
+            .iter()
+            .map(|record| record.record.args[0].as_str())
+            .collect();
+        assert_eq!(args, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn read_from_paths_orders_equal_timestamps_by_path_then_line() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.jsonl");
+        let second = dir.path().join("second.jsonl");
+        let store_first = HistoryStore::new(first.clone());
+        let store_second = HistoryStore::new(second.clone());
+        for (store, ts, arg) in [
+            (&store_second, "2026-02-21T12:00:00+09:00", "c"),
+            (&store_first, "2026-02-21T12:00:00+09:00", "a"),
+            (&store_second, "2026-02-21T11:59:59+09:00", "early"),
+            // The same instant as the others, written in UTC.
+            (&store_first, "2026-02-21T03:00:00Z", "b"),
+        ] {
+            store.append(&sample_record(ts, "make", &[arg], 0)).unwrap();
+        }
+
+        for _ in 0..3 {
+            let records = read_from_paths(vec![first.clone(), second.clone()], None).unwrap();
+            let args: Vec<&str> = records
+                .iter()
+                .map(|record| record.record.args[0].as_str())
+                .collect();
+            assert_eq!(args, vec!["early", "a", "b", "c"]);
+        }
+    }
+
     #[test]
     fn read_from_paths_merges_and_sorts_by_timestamp() {
         let dir = tempdir().unwrap();
@@ -378,7 +997,7 @@ mod tests {
             ))
             .unwrap();
 
-        let records = read_from_paths(vec![first, second]).unwrap();
+        let records = read_from_paths(vec![first, second], None).unwrap();
         let commands: Vec<String> = records
             .into_iter()
             .map(|record| format!("{} {}", record.record.program, record.record.args.join(" ")))
@@ -393,6 +1012,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn read_last_reads_only_tail_of_large_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut content = String::new();
+        for idx in 0..300 {
+            let record = sample_record("2026-02-21T12:00:00+09:00", "make", &[&idx.to_string()], 0);
+            content.push_str(&serde_json::to_string(&record).unwrap());
+            content.push('\n');
+            if idx % 20 == 0 {
+                content.push_str("not json\n\n");
+            }
+        }
+        fs::write(&path, content).unwrap();
+        let store = HistoryStore::new(path);
+
+        // Small blocks make the 50 records span many of them, as a long history would.
+        let records = store.read_last_in_blocks(50, 256).unwrap();
+
+        let args: Vec<&str> = records
+            .iter()
+            .map(|record| record.record.args[0].as_str())
+            .collect();
+        let expected: Vec<String> = (250..300).map(|idx| idx.to_string()).collect();
+        assert_eq!(args, expected);
+        assert!(store.read_last(0).unwrap().is_empty());
+        assert_eq!(store.read_all().unwrap().len(), 300);
+    }
+
+    #[test]
+    fn read_last_handles_records_spanning_blocks() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let store = HistoryStore::new(path.clone());
+        let long_arg = "x".repeat(READ_BLOCK_SIZE as usize * 2 + 17);
+        for idx in 0..5 {
+            let arg = format!("{idx}-{long_arg}");
+            store
+                .append(&sample_record(
+                    "2026-02-21T12:00:00+09:00",
+                    "make",
+                    &[&arg],
+                    0,
+                ))
+                .unwrap();
+        }
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{\"truncated\":").unwrap();
+
+        assert!(fs::metadata(&path).unwrap().len() > READ_BLOCK_SIZE * 10);
+        let records = store.read_last(3).unwrap();
+        let prefixes: Vec<&str> = records
+            .iter()
+            .map(|record| &record.record.args[0][..2])
+            .collect();
+        assert_eq!(prefixes, vec!["2-", "3-", "4-"]);
+        assert_eq!(records[2].record.args[0].len(), long_arg.len() + 2);
+        assert_eq!(store.read_last(10).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn read_from_paths_limit_keeps_newest_across_files() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.jsonl");
+        let second = dir.path().join("second.jsonl");
+        let store_first = HistoryStore::new(first.clone());
+        let store_second = HistoryStore::new(second.clone());
+        for (store, ts, arg) in [
+            (&store_first, "2026-02-21T12:01:00+09:00", "a"),
+            (&store_second, "2026-02-21T12:02:00+09:00", "b"),
+            (&store_first, "2026-02-21T12:03:00+09:00", "c"),
+            (&store_second, "2026-02-21T12:04:00+09:00", "d"),
+        ] {
+            store.append(&sample_record(ts, "make", &[arg], 0)).unwrap();
+        }
+
+        let records = read_from_paths(vec![first, second], Some(2)).unwrap();
+        let args: Vec<&str> = records
+            .iter()
+            .map(|record| record.record.args[0].as_str())
+            .collect();
+        assert_eq!(args, vec!["c", "d"]);
+    }
+
     #[test]
     fn read_from_paths_ignores_unreadable_path_if_others_work() {
         let dir = tempdir().unwrap();
@@ -410,7 +1113,7 @@ mod tests {
             ))
             .unwrap();
 
-        let records = read_from_paths(vec![unreadable, valid]).unwrap();
+        let records = read_from_paths(vec![unreadable, valid], None).unwrap();
         assert_eq!(records.len(), 1);
         assert_eq!(records[0].record.program, "make");
         assert_eq!(records[0].record.args, vec!["e".to_string()]);
@@ -457,4 +1160,65 @@ mod tests {
             )]
         );
     }
+
+    #[test]
+    fn format_cwd_tree_orders_directories_by_latest_activity() {
+        let run = |ts: &str, dir: &str, arg: &str| {
+            stored(HistoryRecord {
+                working_directory: dir.to_string(),
+                ..sample_record(ts, "just", &[arg], 0)
+            })
+        };
+        let records = vec![
+            run("2026-02-21T12:00:00+09:00", "/api", "build"),
+            run("2026-02-21T12:01:00+09:00", "/web", "dev"),
+            run("2026-02-21T12:02:00+09:00", "/api", "test"),
+        ];
+        assert_eq!(
+            format_cwd_tree(&records),
+            "/api\n  just test\n  just build\n\n/web\n  just dev\n"
+        );
+        assert_eq!(format_cwd_tree(&[]), "");
+    }
+
+    #[test]
+    fn format_history_stats_prints_counts_top_commands_and_engines() {
+        let timed = |exit_code, duration_ms| {
+            stored(HistoryRecord {
+                duration_ms: Some(duration_ms),
+                ..sample_record("2026-02-21T12:00:00+09:00", "just", &["test"], exit_code)
+            })
+        };
+        let records = vec![
+            timed(0, 1000),
+            timed(2, 2000),
+            stored(sample_record(
+                "2026-02-21T12:02:00+09:00",
+                "make",
+                &["build"],
+                0,
+            )),
+        ];
+
+        assert_eq!(
+            format_history_stats(&summarize(&records)),
+            concat!(
+                "3 runs: 2 succeeded, 1 failed\n",
+                "\n",
+                "Most run:\n",
+                "  RUNS  FAILED  COMMAND\n",
+                "     2       1  just test\n",
+                "     1       0  make build\n",
+                "\n",
+                "By engine:\n",
+                "  ENGINE  RUNS  AVG\n",
+                "  just       2  1.5s\n",
+                "  make       1  -\n",
+            )
+        );
+        assert_eq!(
+            format_history_stats(&summarize(&[])),
+            "0 runs: 0 succeeded, 0 failed\n"
+        );
+    }
 }
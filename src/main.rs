@@ -1,14 +1,26 @@
+mod bookmarks;
+mod capabilities;
+mod config;
 mod detect;
+mod env;
 mod exec;
+mod exit_code;
+mod graph;
 mod history;
 mod parser;
+mod prompt;
 mod task_args;
 mod tasks;
+mod term;
+#[cfg(test)]
+mod test_support;
 
-use bpaf::Bpaf;
+use bpaf::{Bpaf, Parser, construct, long};
 use inquire::error::InquireError;
 use std::fmt;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 fn main() {
     let cli = parse_cli();
@@ -29,9 +41,163 @@ struct Args {
     /// Prompt for task arguments interactively.
     #[bpaf(long("args"), switch)]
     prompt_args: bool,
+    /// Never prompt for task arguments; let the runner report missing ones.
+    #[bpaf(long("no-args"), switch)]
+    no_args: bool,
+    /// Prompt for every declared task parameter, prefilled with defaults.
+    #[bpaf(long("prompt-all-args"), switch)]
+    prompt_all_args: bool,
+    /// Detect runners and run tasks in this directory instead of the current one.
+    #[bpaf(long("cwd"), argument("DIR"))]
+    cwd: Option<PathBuf>,
+    /// Shell that runs bookmark, `--amend-last` and `--allow-missing-runner` command lines.
+    #[bpaf(long("exec-shell"), argument("SHELL"))]
+    exec_shell: Option<String>,
     /// Select a previously executed command from rt history and run it.
     #[bpaf(long("history"), switch)]
     history: bool,
+    /// With `--history`, print commands grouped by directory instead of opening the picker.
+    #[bpaf(long("cwd-tree"), switch)]
+    cwd_tree: bool,
+    /// Print history statistics: counts, most-run commands, and durations per engine.
+    #[bpaf(long("stats"), switch)]
+    stats: bool,
+    /// With `--history`, only show commands containing this text.
+    #[bpaf(long("filter"), argument("TEXT"))]
+    filter: Option<String>,
+    /// Edit the most recent history command, then run the edited command line.
+    #[bpaf(long("amend-last"), switch)]
+    amend_last: bool,
+    /// List tasks in the selector in their original order, without recently run ones first.
+    #[bpaf(long("no-recent"), switch)]
+    no_recent: bool,
+    /// Rerun the most recent command that failed in the current directory.
+    #[bpaf(long("last-failed-here"), switch)]
+    last_failed_here: bool,
+    /// Rerun the most recent history command without opening the picker.
+    #[bpaf(long("last"), switch)]
+    last: bool,
+    /// Empty every rt history file after asking for confirmation.
+    #[bpaf(long("clear-history"), switch)]
+    clear_history: bool,
+    /// Skip confirmation prompts, e.g. for `--clear-history` in scripts.
+    #[bpaf(long("yes"), switch)]
+    yes: bool,
+    /// Run this command line when the task exits non-zero, with `RT_TASK` and `RT_EXIT` set.
+    #[bpaf(long("on-fail"), argument("COMMAND"))]
+    on_fail: Option<String>,
+    /// Print the command that would run, without running it or recording history.
+    #[bpaf(long("dry-run"), switch)]
+    dry_run: bool,
+    /// Pick the runner, task, and arguments interactively, then print the command instead of running it.
+    #[bpaf(long("pick"), switch)]
+    pick: bool,
+    /// Ask for confirmation before running tasks matching the `[confirm] patterns` config.
+    #[bpaf(long("confirm-destructive"), switch)]
+    confirm_destructive: bool,
+    /// Set an environment variable for the task; repeatable.
+    #[bpaf(long("env"), argument("KEY=VALUE"), many)]
+    env: Vec<env::EnvAssignment>,
+    /// Kill the task after this many seconds and exit with 124; overrides `[timeout]`.
+    #[bpaf(long("timeout"), argument("SECONDS"))]
+    timeout: Option<u64>,
+    /// Read at most this many of the newest history records per file for `--history`; `--stats` reads them all.
+    #[bpaf(
+        long("max-history-read"),
+        argument("N"),
+        fallback(DEFAULT_MAX_HISTORY_READ),
+        display_fallback
+    )]
+    max_history_read: usize,
+    /// Run every command recorded in a history-format JSONL file, in order.
+    #[bpaf(long("replay"), argument("FILE"))]
+    replay: Option<PathBuf>,
+    /// Show the task's output and also write it to FILE, truncating it first.
+    #[bpaf(long("log"), argument("FILE"))]
+    log: Option<PathBuf>,
+    /// With `--log`, append to the file instead of truncating it.
+    #[bpaf(long("log-append"), switch)]
+    log_append: bool,
+    /// Run the task N times, stopping at the first failure unless `--keep-going`.
+    #[bpaf(long("repeat"), argument("N"))]
+    repeat: Option<NonZeroUsize>,
+    /// With `--replay` or `--repeat`, continue past failing runs instead of stopping.
+    #[bpaf(long("keep-going"), switch)]
+    keep_going: bool,
+    /// Without a runner file, run the task as a shell command (e.g. `"npm test"`) and record it.
+    #[bpaf(long("allow-missing-runner"), switch)]
+    allow_missing_runner: bool,
+    /// Save the quoted command as a bookmark runnable from anywhere with `rt @NAME`.
+    #[bpaf(long("bookmark"), argument("NAME"))]
+    bookmark: Option<String>,
+    /// List saved bookmarks.
+    #[bpaf(long("bookmarks"), switch)]
+    bookmarks: bool,
+    /// Print the runners found in the given directory (default: current) without listing tasks.
+    #[bpaf(long("print-detected"), switch)]
+    print_detected: bool,
+    /// Print machine-readable JSON instead of text (with `--list`, `--print-detected` or `--stats`).
+    #[bpaf(long("json"), switch)]
+    json: bool,
+    /// With `--list`, print each task through a template such as `{name}\t{description}`.
+    #[bpaf(long("format"), argument("TEMPLATE"))]
+    format: Option<String>,
+    /// Report detected runners, whether their tools are installed, and task counts.
+    #[bpaf(long("doctor"), switch)]
+    doctor: bool,
+    /// Check detected runners' tool versions against `[require]` in the config; exit 1 if any fail.
+    #[bpaf(long("runner-env-check"), switch)]
+    runner_env_check: bool,
+    /// Show the command a task runs and its recipe source, without running it.
+    #[bpaf(long("explain"), switch)]
+    explain: bool,
+    /// Print the environment the task would run with (secrets masked), without running it.
+    #[bpaf(long("env-dump"), switch)]
+    env_dump: bool,
+    /// Print the nearest directory (here or above) containing a runner file.
+    #[bpaf(long("print-runner-dir"), switch)]
+    print_runner_dir: bool,
+    /// Open the runner file in $EDITOR at the given task's recipe.
+    #[bpaf(long("edit"), switch)]
+    edit: bool,
+    /// Show task names only, without descriptions, in the picker and `--list`.
+    #[bpaf(long("compact"), switch)]
+    compact: bool,
+    /// Never use colors in output, as with `NO_COLOR`.
+    #[bpaf(long("no-color"), switch)]
+    no_color: bool,
+    /// Print the runner's task listing exactly as its list command outputs it.
+    #[bpaf(long("runner-list-raw"), switch)]
+    runner_list_raw: bool,
+    /// Print extra detail: the `--runner-list-raw` command, piped `--list` descriptions, history warnings.
+    #[bpaf(long("verbose"), switch)]
+    verbose: bool,
+    /// Print only the task's output and errors, without rt's own notices; overrides `--verbose`.
+    #[bpaf(long("quiet"), switch)]
+    quiet: bool,
+    /// Print the detected runner's tasks instead of opening the picker.
+    #[bpaf(long("list"), switch)]
+    list: bool,
+    /// Write the detected runner's tasks and descriptions to FILE as a sorted snapshot.
+    #[bpaf(long("dump-tasks"), argument("FILE"))]
+    dump_tasks: Option<PathBuf>,
+    /// With `--dump-tasks`, compare the tasks with FILE instead; print the diff and exit 1 on drift.
+    #[bpaf(long("check"), switch)]
+    check: bool,
+    /// Use this runner (`just`, `task`, `make`, ...) even when others are present.
+    #[bpaf(long("runner"), argument("NAME"))]
+    runner: Option<detect::Runner>,
+    /// Read tasks from this justfile instead of detecting one; `-` reads it from stdin.
+    #[bpaf(long("file"), long("justfile"), argument("PATH"))]
+    file: Option<PathBuf>,
+    /// With `--list`, print tasks under headings: `group` (runner groups) or `prefix`.
+    #[bpaf(long("group-by"), argument("GROUPING"))]
+    group_by: Option<tasks::GroupBy>,
+    #[bpaf(external(graph_format))]
+    graph: Option<graph::GraphFormat>,
+    /// Print a one-line result summary after direct runs too (always shown after picker runs).
+    #[bpaf(long("summary"), switch)]
+    summary: bool,
     /// Task name to run in your task runner files (e.g. `build`, `test`).
     #[bpaf(positional("task"))]
     task: Option<String>,
@@ -39,24 +205,170 @@ struct Args {
     rest: Vec<String>,
 }
 
+/// Parses `--graph` with an optional `text`/`dot` format.
+fn graph_format() -> impl Parser<Option<graph::GraphFormat>> {
+    let with_format = long("graph")
+        .help("Print task dependencies as a tree, or as Graphviz with `--graph dot`.")
+        .argument::<graph::GraphFormat>("FORMAT");
+    let bare = long("graph").req_flag(graph::GraphFormat::Text).hide();
+    construct!([with_format, bare]).optional()
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Cli {
     pub prompt_args: bool,
+    pub no_args: bool,
+    pub prompt_all_args: bool,
+    pub cwd: Option<PathBuf>,
+    pub exec_shell: Option<String>,
     pub history: bool,
+    pub cwd_tree: bool,
+    pub stats: bool,
+    pub filter: Option<String>,
+    pub amend_last: bool,
+    pub last: bool,
+    pub last_failed_here: bool,
+    pub no_recent: bool,
+    pub clear_history: bool,
+    pub yes: bool,
+    pub max_history_read: usize,
+    pub on_fail: Option<String>,
+    pub dry_run: bool,
+    pub pick: bool,
+    pub confirm_destructive: bool,
+    pub env: Vec<env::EnvAssignment>,
+    pub timeout: Option<u64>,
+    pub replay: Option<PathBuf>,
+    pub log: Option<PathBuf>,
+    pub log_append: bool,
+    pub repeat: Option<NonZeroUsize>,
+    pub keep_going: bool,
+    pub allow_missing_runner: bool,
+    pub bookmark: Option<String>,
+    pub bookmarks: bool,
+    pub print_detected: bool,
+    pub json: bool,
+    pub format: Option<String>,
+    pub doctor: bool,
+    pub runner_env_check: bool,
+    pub explain: bool,
+    pub env_dump: bool,
+    pub print_runner_dir: bool,
+    pub edit: bool,
+    pub compact: bool,
+    pub no_color: bool,
+    pub runner_list_raw: bool,
+    pub verbose: bool,
+    pub quiet: bool,
+    pub list: bool,
+    pub dump_tasks: Option<PathBuf>,
+    pub check: bool,
+    pub runner: Option<detect::Runner>,
+    pub file: Option<PathBuf>,
+    /// `--file -`: the runner file is a temporary copy of stdin, so runs aren't recorded.
+    pub file_from_stdin: bool,
+    pub group_by: Option<tasks::GroupBy>,
+    pub graph: Option<graph::GraphFormat>,
+    pub summary: bool,
     pub task: Option<String>,
     pub passthrough: Vec<String>,
 }
 
 pub fn parse_cli() -> Cli {
-    let raw = args().run();
-    Cli::from_raw(raw)
+    match args().run_inner(bpaf::Args::current_args()) {
+        Ok(raw) => Cli::from_raw(raw),
+        Err(failure) => {
+            failure.print_message(100);
+            // `--help` and `--version` also end up here, and exit successfully.
+            let code = match failure.exit_code() {
+                0 => 0,
+                _ => exit_code::USAGE,
+            };
+            std::process::exit(code);
+        }
+    }
 }
 
 impl Cli {
+    fn shell(&self) -> String {
+        self.exec_shell.clone().unwrap_or_else(exec::default_shell)
+    }
+
+    /// The `--env` assignments as key-value pairs.
+    fn env_vars(&self) -> Vec<(String, String)> {
+        self.env
+            .iter()
+            .map(|assignment| (assignment.key.clone(), assignment.value.clone()))
+            .collect()
+    }
+
+    fn arg_prompt(&self, config: &config::Config) -> ArgPrompt {
+        if self.no_args {
+            ArgPrompt::Never
+        } else if self.prompt_all_args {
+            ArgPrompt::All
+        } else if self.prompt_args || self.pick || config.prompt_args {
+            ArgPrompt::Optional
+        } else {
+            ArgPrompt::Required
+        }
+    }
+
     fn from_raw(raw: Args) -> Self {
         Self {
             prompt_args: raw.prompt_args,
+            no_args: raw.no_args,
+            prompt_all_args: raw.prompt_all_args,
+            cwd: raw.cwd,
+            exec_shell: raw.exec_shell,
             history: raw.history,
+            cwd_tree: raw.cwd_tree,
+            stats: raw.stats,
+            filter: raw.filter,
+            amend_last: raw.amend_last,
+            last: raw.last,
+            last_failed_here: raw.last_failed_here,
+            no_recent: raw.no_recent,
+            clear_history: raw.clear_history,
+            yes: raw.yes,
+            max_history_read: raw.max_history_read,
+            on_fail: raw.on_fail,
+            dry_run: raw.dry_run,
+            pick: raw.pick,
+            confirm_destructive: raw.confirm_destructive,
+            env: raw.env,
+            timeout: raw.timeout,
+            replay: raw.replay,
+            log: raw.log,
+            log_append: raw.log_append,
+            repeat: raw.repeat,
+            keep_going: raw.keep_going,
+            allow_missing_runner: raw.allow_missing_runner,
+            bookmark: raw.bookmark,
+            bookmarks: raw.bookmarks,
+            print_detected: raw.print_detected,
+            json: raw.json,
+            format: raw.format,
+            doctor: raw.doctor,
+            runner_env_check: raw.runner_env_check,
+            explain: raw.explain,
+            env_dump: raw.env_dump,
+            print_runner_dir: raw.print_runner_dir,
+            edit: raw.edit,
+            compact: raw.compact,
+            no_color: raw.no_color,
+            runner_list_raw: raw.runner_list_raw,
+            verbose: raw.verbose && !raw.quiet,
+            quiet: raw.quiet,
+            list: raw.list,
+            dump_tasks: raw.dump_tasks,
+            check: raw.check,
+            runner: raw.runner,
+            file_from_stdin: raw.file.as_deref() == Some(Path::new("-")),
+            file: raw.file,
+            group_by: raw.group_by,
+            graph: raw.graph,
+            summary: raw.summary,
             task: raw.task,
             passthrough: normalize_passthrough(raw.rest),
         }
@@ -71,51 +383,803 @@ fn normalize_passthrough(rest: Vec<String>) -> Vec<String> {
     }
 }
 
+/// Returns the directory rt works in: `--cwd` (relative to the current directory) or the current one.
+fn resolve_cwd(dir: Option<&Path>) -> Result<PathBuf, RtError> {
+    let current = std::env::current_dir().map_err(RtError::Io)?;
+    let Some(dir) = dir else {
+        return Ok(current);
+    };
+
+    // Collecting components drops `.` segments, so `--cwd .` records the same path as no `--cwd`.
+    let dir: PathBuf = current.join(dir).components().collect();
+    if !dir.is_dir() {
+        return Err(RtError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("--cwd {} is not a directory", dir.display()),
+        )));
+    }
+    Ok(dir)
+}
+
+/// A runner file read from stdin for `--file -`, removed again when rt is done with it.
+struct StdinRunnerFile {
+    path: PathBuf,
+    _dir: tempfile::TempDir,
+}
+
+impl StdinRunnerFile {
+    fn read() -> Result<Self, RtError> {
+        let content = std::io::read_to_string(std::io::stdin()).map_err(RtError::Io)?;
+        // `TempDir` is created with mode 0700 under a random name, and `create_new` refuses to
+        // follow a planted symlink, so no other user can redirect or read the write.
+        let dir = tempfile::Builder::new()
+            .prefix("rt-")
+            .tempdir()
+            .map_err(RtError::Io)?;
+        let path = dir.path().join("justfile");
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+            .open(&path)
+            .and_then(|mut file| std::io::Write::write_all(&mut file, content.as_bytes()))
+            .map_err(RtError::Io)?;
+        Ok(StdinRunnerFile { path, _dir: dir })
+    }
+}
+
 /// Runs tasks based on the provided CLI arguments.
-fn run(cli: Cli) -> Result<i32, RtError> {
-    let cwd = std::env::current_dir().map_err(RtError::Io)?;
-    if cli.history {
-        return rerun_from_history(&cwd);
+fn run(mut cli: Cli) -> Result<i32, RtError> {
+    let cwd = resolve_cwd(cli.cwd.as_deref())?;
+    // Kept until the run finishes, since the runner reads the file while the task runs.
+    let _stdin_file = if cli.file_from_stdin {
+        let stdin_file = StdinRunnerFile::read()?;
+        cli.file = Some(stdin_file.path.clone());
+        Some(stdin_file)
+    } else {
+        None
+    };
+    let mut config = config::load(&cwd)?;
+    config.ui.compact |= cli.compact;
+    if cli.runner.is_none() {
+        cli.runner = config.runner;
+    }
+    if cli.no_color {
+        term::disable_color();
+    }
+    if cli.quiet {
+        term::enable_quiet(cli.summary);
+    }
+    if cli.history || cli.stats {
+        let filter = HistoryFilter {
+            text: cli.filter.clone(),
+            cwd: cli.cwd.is_some().then(|| cwd.clone()),
+            failed_only: false,
+        };
+        // Stats summarize the whole history; the read cap only bounds what's listed.
+        let max_read = (!cli.stats).then_some(cli.max_history_read);
+        let records = read_history(max_read, cli.verbose, &filter)?;
+        if cli.stats {
+            let summary = history::summarize(&records);
+            if cli.json {
+                println!(
+                    "{}",
+                    history::summary_json(&summary, &history::current_timestamp())
+                );
+            } else {
+                print!("{}", history::format_history_stats(&summary));
+            }
+            return Ok(0);
+        }
+        if cli.cwd_tree {
+            print!("{}", history::format_cwd_tree(&records));
+            return Ok(0);
+        }
+        return rerun_from_history(&records, &cwd, &cli.env_vars(), &config);
     }
 
-    if let Some(task) = cli.task {
-        let detection = detect::detect_runner(&cwd)?;
-        let passthrough =
-            match collect_passthrough(&detection, &task, &cli.passthrough, cli.prompt_args)? {
-                Some(args) => args,
-                None => return Ok(0),
-            };
-        return execute_and_record(&detection, &task, &passthrough, &cwd);
+    if cli.amend_last {
+        return amend_last(&cwd, &cli.shell(), &cli.env_vars(), &config.history);
+    }
+
+    if cli.clear_history {
+        return clear_history(cli.yes);
+    }
+
+    if cli.last {
+        return rerun_last(&cwd, &cli.env_vars(), &config);
+    }
+
+    if cli.last_failed_here {
+        return rerun_last_failed_here(&cwd, &cli, &config);
+    }
+
+    if let Some(path) = cli.replay.as_deref() {
+        return replay(path, &cwd, cli.keep_going, &cli.env_vars(), &config.history);
+    }
+
+    if let Some(name) = cli.bookmark.as_deref() {
+        return save_bookmark(name, cli.task.as_deref(), &cli.passthrough);
+    }
+
+    if cli.bookmarks {
+        return list_bookmarks();
+    }
+
+    if let Some(name) = cli.task.as_deref().and_then(|task| task.strip_prefix('@')) {
+        return run_bookmark(name, &cwd, &cli, &config);
+    }
+
+    if cli.print_detected {
+        let dir = cli
+            .task
+            .as_deref()
+            .map_or_else(|| cwd.clone(), PathBuf::from);
+        return print_detected(&dir, cli.json, &config.runners);
+    }
+
+    if cli.doctor {
+        return doctor(&cwd, &config);
     }
 
-    let detections = detect::detect_runners(&cwd)?;
+    if cli.runner_env_check {
+        return runner_env_check(&cwd, &config);
+    }
+
+    if cli.explain {
+        return explain_task(&cwd, cli.task.as_deref(), &cli.passthrough, &config);
+    }
+
+    if cli.print_runner_dir {
+        println!(
+            "{}",
+            detect::find_runner_dir(&cwd, &config.runners)?.display()
+        );
+        return Ok(0);
+    }
+
+    if cli.env_dump {
+        return print_env_dump(&cwd, &cli, &config);
+    }
+
+    if cli.edit {
+        return edit_task(&cwd, cli.task.as_deref(), &config.runners);
+    }
+
+    if cli.runner_list_raw {
+        let detection = match cli.file.as_deref() {
+            Some(file) => detect::detect_file(file, cli.runner)?,
+            None => detect::detect_runner(&cwd, &config.runners)?,
+        };
+        let listing = tasks::raw_listing(&detection)?;
+        if cli.verbose {
+            eprintln!("$ {}", listing.source);
+        }
+        print!("{}", listing.output);
+        return Ok(0);
+    }
+
+    if cli.list || cli.group_by.is_some() {
+        let compact = list_is_compact(config.ui.compact, cli.verbose, term::stdout_is_terminal());
+        let detection = detect_for(&cwd, &cli, &config)?;
+        if cli.json {
+            return print_task_json(&detection, cli.group_by);
+        }
+        if let Some(format) = cli.format.as_deref() {
+            return print_task_template(&detection, format);
+        }
+        return print_task_list(&detection, cli.group_by, compact);
+    }
+
+    if let Some(path) = cli.dump_tasks.as_deref() {
+        let detection = detect_for(&cwd, &cli, &config)?;
+        return dump_tasks(&detection, path, cli.check);
+    }
+
+    if let Some(format) = cli.graph {
+        return print_graph(&cwd, format, &config.runners);
+    }
+
+    // Without a terminal, missing arguments are left for the runner to report, as with `--no-args`.
+    let arg_prompt = if term::can_prompt() {
+        cli.arg_prompt(&config)
+    } else {
+        ArgPrompt::Never
+    };
+    if cli.pick && !term::can_prompt() {
+        return Err(RtError::NoTerminal);
+    }
+    // `--pick` is for choosing interactively, so it doesn't fall back to `default_task`.
+    let task = cli
+        .task
+        .as_deref()
+        .or_else(|| config.default_task.as_deref().filter(|_| !cli.pick))
+        .map(|task| config.aliases.get(task).map_or(task, String::as_str));
+    if let Some(task) = task {
+        let detection = match detect_for(&cwd, &cli, &config) {
+            Ok(detection) => detection,
+            Err(RtError::NoRunnerFound { .. }) if cli.allow_missing_runner && !cli.pick => {
+                let command = shell_command_line(Some(task), &cli.passthrough);
+                if cli.dry_run {
+                    print_shell_dry_run(&command, &cli.shell());
+                    return Ok(0);
+                }
+                let result = run_shell_and_record(
+                    &command,
+                    &cwd,
+                    &cli.shell(),
+                    &cli.env_vars(),
+                    &config.history,
+                )?;
+                if cli.summary {
+                    print_summary(&result);
+                }
+                return Ok(result.exit_code);
+            }
+            Err(err) => return Err(err),
+        };
+        let (task, cli_passthrough) = if detection.runner == detect::Runner::Makefile {
+            exec::make_target_and_overrides(task, &cli.passthrough)
+        } else {
+            (task.to_string(), cli.passthrough.clone())
+        };
+        let task = task.as_str();
+        let passthrough = match collect_passthrough(
+            &detection,
+            task,
+            &cli_passthrough,
+            &cli.env_vars(),
+            arg_prompt,
+            &config.ui,
+        )? {
+            Some(args) => args,
+            None => return Ok(0),
+        };
+        let task = tasks::TaskItem {
+            name: task.to_string(),
+            description: None,
+            group: None,
+        };
+        return execute_and_record(&detection, &task, &passthrough, &cwd, &cli, &config, false);
+    }
+
+    if !term::can_prompt() {
+        return Err(RtError::NoTerminal);
+    }
+    let detections = match (cli.file.is_some(), cli.runner) {
+        (false, None) => detect::detect_runners(&cwd, &config.runners)?
+            .into_iter()
+            .map(|detection| configure_detection(detection, &config))
+            .collect(),
+        _ => vec![detect_for(&cwd, &cli, &config)?],
+    };
     let detection = if detections.len() == 1 {
         detections.into_iter().next()
     } else {
-        select_runner(detections)?
+        select_runner(detections, &config.ui)?
     };
 
     let detection = match detection {
         Some(detection) => detection,
         None => return Ok(0),
     };
-    let runner = detection.runner;
-
-    let task = tasks::select_task(runner)?;
+    let recent = recent_runs(&cwd, cli.no_recent);
+    let task = tasks::select_task(&detection, &config.ui, &recent)?;
     match task {
         Some(task) => {
-            let passthrough =
-                match collect_passthrough(&detection, &task, &cli.passthrough, cli.prompt_args)? {
-                    Some(args) => args,
-                    None => return Ok(0),
-                };
-            execute_and_record(&detection, &task, &passthrough, &cwd)
+            let passthrough = match collect_passthrough(
+                &detection,
+                &task.name,
+                &cli.passthrough,
+                &cli.env_vars(),
+                arg_prompt,
+                &config.ui,
+            )? {
+                Some(args) => args,
+                None => return Ok(0),
+            };
+            execute_and_record(&detection, &task, &passthrough, &cwd, &cli, &config, true)
         }
         None => Ok(0),
     }
 }
 
+fn save_bookmark(name: &str, task: Option<&str>, rest: &[String]) -> Result<i32, RtError> {
+    if !bookmarks::is_valid_name(name) {
+        return Err(RtError::InvalidBookmark {
+            name: name.to_string(),
+        });
+    }
+    let command = shell_command_line(task, rest);
+    if command.trim().is_empty() {
+        return Err(RtError::InvalidBookmark {
+            name: name.to_string(),
+        });
+    }
+
+    let store = bookmarks::BookmarkStore::open_default()?;
+    store.save(name, &command)?;
+    println!("Saved @{name}: {command}");
+    Ok(0)
+}
+
+/// Joins a command typed as one quoted argument, or as several, into a shell command line.
+fn shell_command_line(task: Option<&str>, rest: &[String]) -> String {
+    task.into_iter()
+        .chain(rest.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Prints the `shell -c command` line `--dry-run` would otherwise run, for shell commands.
+fn print_shell_dry_run(command: &str, shell: &str) {
+    let invocation = exec::shell_invocation(command, shell);
+    println!(
+        "{}",
+        exec::format_program_args(&invocation.program, &invocation.args)
+    );
+}
+
+fn run_shell_and_record(
+    command: &str,
+    cwd: &Path,
+    shell: &str,
+    env: &[(String, String)],
+    history_config: &config::HistoryConfig,
+) -> Result<exec::RunResult, RtError> {
+    let result = exec::run_shell(command, cwd, shell, env)?;
+    record_history(&result, cwd, None, history_config);
+    Ok(result)
+}
+
+/// Appends a finished run to the default history, as `[history]` configures.
+///
+/// `description` is the task's, when it's known from the task list.
+fn record_history(
+    result: &exec::RunResult,
+    cwd: &Path,
+    description: Option<&str>,
+    history_config: &config::HistoryConfig,
+) {
+    let _ = history::append_default(history::RecordInput {
+        program: &result.program,
+        args: &result.args,
+        working_directory: cwd,
+        exit_code: result.exit_code,
+        duration: result.duration,
+        description,
+        hash_command: history_config.hash_commands,
+        dedupe: history_config.dedupe,
+    });
+}
+
+fn list_bookmarks() -> Result<i32, RtError> {
+    let store = bookmarks::BookmarkStore::open_default()?;
+    let saved = store.load()?;
+    if saved.is_empty() {
+        println!("No bookmarks in {}", store.path().display());
+        return Ok(0);
+    }
+
+    let width = saved.keys().map(|name| name.len()).max().unwrap_or(0);
+    for (name, command) in &saved {
+        println!("@{name:<width$}  {command}");
+    }
+    Ok(0)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BookmarkChoice {
+    name: String,
+    command: String,
+}
+
+impl fmt::Display for BookmarkChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@{} - {}", self.name, self.command)
+    }
+}
+
+/// Runs the bookmark `name`, or picks one interactively when `rt @` names none.
+fn run_bookmark(
+    name: &str,
+    cwd: &Path,
+    cli: &Cli,
+    config: &config::Config,
+) -> Result<i32, RtError> {
+    let saved = bookmarks::BookmarkStore::open_default()?.load()?;
+    let picked = name.is_empty();
+    let command = if picked {
+        let choices: Vec<BookmarkChoice> = saved
+            .into_iter()
+            .map(|(name, command)| BookmarkChoice { name, command })
+            .collect();
+        if choices.is_empty() {
+            return Ok(0);
+        }
+        match prompt::select("Select bookmark", choices, &config.ui).prompt() {
+            Ok(choice) => choice.command,
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+                return Ok(0);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    } else {
+        saved
+            .get(name)
+            .cloned()
+            .ok_or_else(|| RtError::UnknownBookmark {
+                name: name.to_string(),
+            })?
+    };
+
+    if cli.dry_run {
+        print_shell_dry_run(&command, &cli.shell());
+        return Ok(0);
+    }
+    let result = run_shell_and_record(
+        &command,
+        cwd,
+        &cli.shell(),
+        &cli.env_vars(),
+        &config.history,
+    )?;
+    if picked || cli.summary {
+        print_summary(&result);
+    }
+
+    Ok(result.exit_code)
+}
+
+fn print_detected(dir: &Path, json: bool, runners: &config::RunnersConfig) -> Result<i32, RtError> {
+    let report = detect::detection_report(dir, runners, &exec::WhichResolver);
+    if json {
+        println!("{}", report.to_json());
+        return Ok(0);
+    }
+
+    for runner in &report.runners {
+        let status = if runner.tool_present {
+            "installed"
+        } else {
+            "missing"
+        };
+        println!(
+            "{}\t{}\t{status}",
+            detect::runner_name(runner.detection.runner),
+            runner.detection.runner_file.display()
+        );
+    }
+    Ok(0)
+}
+
+fn doctor(cwd: &Path, config: &config::Config) -> Result<i32, RtError> {
+    let report = detect::detection_report(cwd, &config.runners, &exec::WhichResolver)
+        .with_task_counts(&|detection| {
+            let detection = configure_detection(detection.clone(), config);
+            tasks::list_tasks(&detection).ok().map(|tasks| tasks.len())
+        });
+
+    if report.runners.is_empty() {
+        println!("No runner found in {}", report.dir.display());
+        return Ok(0);
+    }
+
+    println!("Runners in {}:", report.dir.display());
+    for runner in &report.runners {
+        let file = runner.detection.runner_file.display();
+        let status = match (runner.tool_present, runner.task_count) {
+            (false, _) => "tool missing".to_string(),
+            (true, Some(count)) => format!("tool found, {count} tasks"),
+            (true, None) => "tool found, failed to list tasks".to_string(),
+        };
+        let capabilities = if runner.tool_present {
+            capabilities::capabilities(runner.detection.runner)
+        } else {
+            capabilities::RunnerCapabilities::default()
+        };
+        let tool = match capabilities.version {
+            Some(version) => format!("{} {version}", runner.tool),
+            None => runner.tool.to_string(),
+        };
+        println!("  {file} ({tool}): {status}");
+    }
+
+    for duplicate in detect::duplicate_runner_files(cwd) {
+        let ignored: Vec<String> = duplicate
+            .ignored
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        println!(
+            "Warning: several {} files; rt uses {} and ignores {}",
+            detect::runner_command(duplicate.runner),
+            duplicate.used.display(),
+            ignored.join(", ")
+        );
+    }
+    Ok(0)
+}
+
+/// Prints each required tool version next to the installed one; exits 1 if any requirement fails.
+fn runner_env_check(cwd: &Path, config: &config::Config) -> Result<i32, RtError> {
+    let report = detect::detection_report(cwd, &config.runners, &exec::WhichResolver);
+    let runners: Vec<detect::Runner> = report
+        .runners
+        .iter()
+        .map(|runner| runner.detection.runner)
+        .collect();
+    let checks = capabilities::check_requirements(&runners, &config.require, &|runner| {
+        capabilities::capabilities(runner).version
+    });
+    if checks.is_empty() {
+        println!(
+            "No version requirements for the runners in {}",
+            report.dir.display()
+        );
+        return Ok(0);
+    }
+
+    let mut failed = false;
+    for check in &checks {
+        let name = detect::runner_name(check.runner);
+        let found = check
+            .found
+            .map_or_else(|| "not found".to_string(), |version| version.to_string());
+        if check.is_satisfied() {
+            println!("ok    {name} {found} (requires {})", check.requirement);
+        } else {
+            failed = true;
+            println!(
+                "FAIL  {name}: requires {}, found {found}",
+                check.requirement
+            );
+        }
+    }
+    Ok(i32::from(failed))
+}
+
+fn explain_task(
+    cwd: &Path,
+    task: Option<&str>,
+    passthrough: &[String],
+    config: &config::Config,
+) -> Result<i32, RtError> {
+    let detection = configure_detection(detect::detect_runner(cwd, &config.runners)?, config);
+    let task = match task {
+        Some(task) => task.to_string(),
+        None => match tasks::select_task(&detection, &config.ui, &recent_runs(cwd, false))? {
+            Some(task) => task.name,
+            None => return Ok(0),
+        },
+    };
+
+    println!(
+        "Command: {}",
+        exec::preview_command(&detection, &task, passthrough)?
+    );
+    if let Some(source) = parser::recipe_source(detection.runner, &detection.runner_file, &task) {
+        println!("Recipe: {}:{}", source.file.display(), source.line);
+        if let Some(interpreter) = &source.interpreter {
+            println!("Interpreter: {interpreter}");
+        }
+        println!();
+        for line in &source.lines {
+            println!("{line}");
+        }
+    }
+    Ok(0)
+}
+
+/// Prints the environment `cli.task` would run with, from the runner `--file`/`--runner` pick.
+///
+/// `RT_TASK` and `RT_COMMAND` are filled in as `run` sets them when a task is given.
+fn print_env_dump(cwd: &Path, cli: &Cli, config: &config::Config) -> Result<i32, RtError> {
+    let detection = detect_for(cwd, cli, config).ok();
+    let mut task_env = Vec::new();
+    if let (Some(detection), Some(task)) = (&detection, cli.task.as_deref()) {
+        let task = config.aliases.get(task).map_or(task, String::as_str);
+        let command = exec::preview_command(detection, task, &cli.passthrough)?;
+        task_env.push(("RT_TASK".to_string(), task.to_string()));
+        task_env.push(("RT_COMMAND".to_string(), command));
+    }
+    let base = std::env::vars_os()
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+        .chain(cli.env_vars())
+        .chain(task_env);
+    let env = env::child_environment(base, detection.as_ref());
+    print!("{}", env::format_dump(&env));
+    Ok(0)
+}
+
+fn edit_task(
+    cwd: &Path,
+    task: Option<&str>,
+    runners: &config::RunnersConfig,
+) -> Result<i32, RtError> {
+    let detection = detect::detect_runner(cwd, runners)?;
+    let location =
+        task.and_then(|task| parser::locate_recipe(detection.runner, &detection.runner_file, task));
+    match location {
+        Some((path, line)) => exec::open_in_editor(&path, Some(line), cwd),
+        None => exec::open_in_editor(&detection.runner_file, None, cwd),
+    }
+}
+
+/// Prints the task list as JSON, nested under groups with `--group-by`; an empty runner file
+/// prints `[]` rather than failing.
+fn print_task_json(
+    detection: &detect::Detection,
+    group_by: Option<tasks::GroupBy>,
+) -> Result<i32, RtError> {
+    let items = tasks::list_tasks(detection)?;
+    let json = match group_by {
+        Some(group_by) => {
+            tasks::grouped_tasks_json(detection, &tasks::group_tasks(items, group_by))
+        }
+        None => tasks::tasks_json(detection, &items),
+    };
+    println!("{json}");
+    Ok(0)
+}
+
+/// Prints one line per task rendered through the `--format` template.
+fn print_task_template(detection: &detect::Detection, format: &str) -> Result<i32, RtError> {
+    let template = tasks::parse_list_template(format)?;
+    let wants_params = template.contains(&tasks::TemplatePart::Field(tasks::TemplateField::Params));
+    let items = tasks::require_tasks(detection, tasks::list_tasks(detection)?)?;
+    for item in &items {
+        let params = if wants_params {
+            task_args::params_for_task(detection, &item.name)
+                .map_err(RtError::Io)?
+                .map(|params| format_params(&params))
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        println!("{}", tasks::render_list_template(&template, item, &params));
+    }
+    Ok(0)
+}
+
+/// Formats declared parameters for `{params}`: `name`, `[name]` when optional, `name...` when
+/// variadic.
+fn format_params(params: &[task_args::TaskParam]) -> String {
+    params
+        .iter()
+        .map(|param| {
+            let name = if param.variadic {
+                format!("{}...", param.name)
+            } else {
+                param.name.clone()
+            };
+            if param.required {
+                name
+            } else {
+                format!("[{name}]")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `--list` prints names only.
+///
+/// Piped output is names only so scripts and shell completion can read it line by line;
+/// `--verbose` brings the descriptions back, and `--compact` drops them on a terminal too.
+fn list_is_compact(compact: bool, verbose: bool, stdout_is_terminal: bool) -> bool {
+    compact || (!verbose && !stdout_is_terminal)
+}
+
+/// Detects the runner file given with `--file`, the runner named by `--runner`, or the
+/// highest-priority one.
+fn detect_for(
+    cwd: &Path,
+    cli: &Cli,
+    config: &config::Config,
+) -> Result<detect::Detection, RtError> {
+    let detection = match (cli.file.as_deref(), cli.runner) {
+        (Some(file), runner) => detect::detect_file(file, runner)?,
+        (None, Some(runner)) => detect::detect_named_runner(cwd, runner)?,
+        (None, None) => detect::detect_runner(cwd, &config.runners)?,
+    };
+    Ok(configure_detection(detection, config))
+}
+
+/// Applies the config settings that change how a detected runner's tasks are listed and run.
+fn configure_detection(
+    mut detection: detect::Detection,
+    config: &config::Config,
+) -> detect::Detection {
+    detection.workspaces = config.package_json.workspaces;
+    detection
+}
+
+/// Writes the task snapshot to `path`, or with `check`, compares it and exits 1 on drift.
+fn dump_tasks(detection: &detect::Detection, path: &Path, check: bool) -> Result<i32, RtError> {
+    let snapshot = tasks::task_snapshot(&tasks::list_tasks(detection)?);
+    if !check {
+        std::fs::write(path, snapshot).map_err(RtError::Io)?;
+        return Ok(0);
+    }
+
+    let expected = std::fs::read_to_string(path).map_err(RtError::Io)?;
+    let diff = tasks::snapshot_diff(&expected, &snapshot);
+    if diff.is_empty() {
+        return Ok(0);
+    }
+    println!("Tasks differ from {}:", path.display());
+    for line in diff {
+        println!("{line}");
+    }
+    Ok(1)
+}
+
+fn print_task_list(
+    detection: &detect::Detection,
+    group_by: Option<tasks::GroupBy>,
+    compact: bool,
+) -> Result<i32, RtError> {
+    let items = tasks::require_tasks(detection, tasks::list_tasks(detection)?)?;
+    let color = term::color_enabled(term::stdout_is_terminal());
+    let Some(group_by) = group_by else {
+        print!("{}", tasks::format_task_lines(&items, "", compact, color));
+        return Ok(0);
+    };
+
+    for (idx, (group, members)) in tasks::group_tasks(items, group_by).iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        println!("{}:", group.as_deref().unwrap_or("(ungrouped)"));
+        print!(
+            "{}",
+            tasks::format_task_lines(members, "  ", compact, color)
+        );
+    }
+    Ok(0)
+}
+
+fn print_graph(
+    cwd: &Path,
+    format: graph::GraphFormat,
+    runners: &config::RunnersConfig,
+) -> Result<i32, RtError> {
+    let detection = detect::detect_runner(cwd, runners)?;
+    let nodes = parser::dependencies(detection.runner, &detection.runner_file).ok_or(
+        RtError::GraphUnsupported {
+            tool: detect::runner_command(detection.runner),
+        },
+    )?;
+    print!("{}", graph::render(format, &nodes));
+    Ok(0)
+}
+
 const HISTORY_SELECT_LIMIT: usize = 200;
+/// History records scanned for recently run tasks when opening the task selector.
+const RECENT_RUNS_READ: usize = 1_000;
+
+/// History records from `cwd`, newest first, for ordering the task selector by recency.
+fn recent_runs(cwd: &Path, disabled: bool) -> Vec<history::HistoryRecord> {
+    if disabled {
+        return Vec::new();
+    }
+    let records = history::read_default(Some(RECENT_RUNS_READ)).unwrap_or_default();
+    records
+        .into_iter()
+        .rev()
+        .map(|entry| entry.record)
+        .filter(|record| !record.hashed && Path::new(&record.working_directory) == cwd)
+        .collect()
+}
+/// Default for `--max-history-read`: generous enough for deduplication, bounded for huge files.
+const DEFAULT_MAX_HISTORY_READ: usize = 10_000;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct HistoryChoice {
@@ -123,39 +1187,296 @@ struct HistoryChoice {
     program: String,
     args: Vec<String>,
     display_command: String,
+    /// Only a hash of the command was recorded, so it can't be rerun.
+    hashed: bool,
+    description: Option<String>,
 }
 
 impl fmt::Display for HistoryChoice {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.display_command)
+        match &self.description {
+            Some(description) => write!(f, "{}  -  {description}", self.display_command),
+            None => write!(f, "{}", self.display_command),
+        }
+    }
+}
+
+/// Narrows `--history` to matching records (`--filter`, and `--cwd` when given).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct HistoryFilter {
+    /// Substring of the command line.
+    text: Option<String>,
+    /// Directory the command ran in.
+    cwd: Option<PathBuf>,
+    /// Only runs that exited non-zero.
+    failed_only: bool,
+}
+
+impl HistoryFilter {
+    fn matches(&self, record: &history::HistoryRecord) -> bool {
+        let text_matches = self.text.as_deref().is_none_or(|text| {
+            exec::format_program_args(&record.program, &record.args).contains(text)
+        });
+        let cwd_matches = self
+            .cwd
+            .as_deref()
+            .is_none_or(|cwd| Path::new(&record.working_directory) == cwd);
+        text_matches && cwd_matches && (!self.failed_only || record.exit_code != 0)
+    }
+}
+
+/// Reads up to `max_read` records per history file, oldest first, keeping those `filter` matches.
+///
+/// Filtering happens before the picker's limit, so an old match isn't crowded out by newer runs.
+/// Reads the history records matching `filter`, from at most the newest `max_read` per file.
+fn read_history(
+    max_read: Option<usize>,
+    verbose: bool,
+    filter: &HistoryFilter,
+) -> Result<Vec<history::StoredRecord>, RtError> {
+    let mut records = history::read_default(max_read).map_err(RtError::Io)?;
+    if verbose && let Some(warning) = history::newer_schema_warning(&records) {
+        eprintln!("rt: {warning}");
+    }
+    records.retain(|entry| filter.matches(&entry.record));
+    Ok(records)
+}
+
+/// Reruns the newest command that failed in `cwd`.
+fn rerun_last_failed_here(cwd: &Path, cli: &Cli, config: &config::Config) -> Result<i32, RtError> {
+    let records = read_history(
+        Some(cli.max_history_read),
+        cli.verbose,
+        &HistoryFilter::default(),
+    )?;
+    let Some(failed) = last_failed_in(&records, cwd) else {
+        term::notice(format_args!("rt: no failed command in {}", cwd.display()));
+        return Ok(0);
+    };
+    if failed.record.hashed {
+        return Err(RtError::HashedHistory);
     }
+    term::notice(format_args!("$ {}", failed.record.display_command()));
+    rerun_record(
+        &failed.record.program,
+        &failed.record.args,
+        &failed.record.working_directory,
+        failed.record.desc.as_deref(),
+        cwd,
+        &cli.env_vars(),
+        &config.history,
+    )
+}
+
+/// The newest record that failed in `cwd`, for `--last-failed-here`.
+fn last_failed_in<'a>(
+    records: &'a [history::StoredRecord],
+    cwd: &Path,
+) -> Option<&'a history::StoredRecord> {
+    let filter = HistoryFilter {
+        cwd: Some(cwd.to_path_buf()),
+        failed_only: true,
+        ..HistoryFilter::default()
+    };
+    records
+        .iter()
+        .rev()
+        .find(|entry| filter.matches(&entry.record))
 }
 
-fn rerun_from_history(fallback_cwd: &Path) -> Result<i32, RtError> {
-    let records = history::read_default().map_err(RtError::Io)?;
-    let choices = build_history_choices(&records, HISTORY_SELECT_LIMIT);
+fn rerun_from_history(
+    records: &[history::StoredRecord],
+    fallback_cwd: &Path,
+    env: &[(String, String)],
+    config: &config::Config,
+) -> Result<i32, RtError> {
+    let choices = build_history_choices(records, HISTORY_SELECT_LIMIT);
     if choices.is_empty() {
         return Ok(0);
     }
 
-    let selected = match inquire::Select::new("Select history command", choices).prompt() {
+    let selected = match prompt::select("Select history command", choices, &config.ui).prompt() {
         Ok(item) => item,
         Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => return Ok(0),
-        Err(err) => return Err(RtError::Prompt(err)),
+        Err(err) => return Err(err.into()),
     };
+    if selected.hashed {
+        return Err(RtError::HashedHistory);
+    }
+    rerun_record(
+        &selected.program,
+        &selected.args,
+        &selected.working_directory,
+        selected.description.as_deref(),
+        fallback_cwd,
+        env,
+        &config.history,
+    )
+}
 
-    let execution_cwd = resolve_history_cwd(&selected.working_directory, fallback_cwd);
-    let result = exec::run_program(&selected.program, &selected.args, &execution_cwd)?;
-    let _ = history::append_default(history::RecordInput {
-        program: &result.program,
-        args: &result.args,
-        working_directory: &execution_cwd,
-        exit_code: result.exit_code,
-    });
+/// Reruns the newest history command without a picker.
+fn rerun_last(
+    fallback_cwd: &Path,
+    env: &[(String, String)],
+    config: &config::Config,
+) -> Result<i32, RtError> {
+    let records = history::read_default(Some(1)).map_err(RtError::Io)?;
+    let Some(last) = records.last() else {
+        term::notice("rt: no history to rerun");
+        return Ok(0);
+    };
+    if last.record.hashed {
+        return Err(RtError::HashedHistory);
+    }
+    term::notice(format_args!("$ {}", last.record.display_command()));
+    rerun_record(
+        &last.record.program,
+        &last.record.args,
+        &last.record.working_directory,
+        last.record.desc.as_deref(),
+        fallback_cwd,
+        env,
+        &config.history,
+    )
+}
 
+/// Runs a recorded argv in its recorded directory (or `fallback_cwd`) with the `--env`
+/// assignments, recording it again with its description.
+fn rerun_record(
+    program: &str,
+    args: &[String],
+    working_directory: &str,
+    description: Option<&str>,
+    fallback_cwd: &Path,
+    env: &[(String, String)],
+    history_config: &config::HistoryConfig,
+) -> Result<i32, RtError> {
+    let execution_cwd = resolve_history_cwd(working_directory, fallback_cwd);
+    let result = exec::run_program(program, args, &execution_cwd, env)?;
+    record_history(&result, &execution_cwd, description, history_config);
+    print_summary(&result);
     Ok(result.exit_code)
 }
 
+fn clear_history(yes: bool) -> Result<i32, RtError> {
+    if !yes {
+        let confirmed = match inquire::Confirm::new("Clear all rt history?")
+            .with_default(false)
+            .prompt()
+        {
+            Ok(confirmed) => confirmed,
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => false,
+            Err(err) => return Err(err.into()),
+        };
+        if !confirmed {
+            return Ok(0);
+        }
+    }
+    history::clear_default().map_err(RtError::Io)?;
+    Ok(0)
+}
+
+/// Prefills the newest history command into an editor and runs what the user submits.
+fn amend_last(
+    fallback_cwd: &Path,
+    shell: &str,
+    env: &[(String, String)],
+    history_config: &config::HistoryConfig,
+) -> Result<i32, RtError> {
+    let records = history::read_default(Some(1)).map_err(RtError::Io)?;
+    let Some(last) = records.last() else {
+        term::notice("rt: no history to amend");
+        return Ok(0);
+    };
+    if last.record.hashed {
+        return Err(RtError::HashedHistory);
+    }
+
+    let original = exec::format_program_args(&last.record.program, &last.record.args);
+    let edited = match inquire::Text::new("Edit command")
+        .with_initial_value(&original)
+        .prompt()
+    {
+        Ok(input) => input,
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => return Ok(0),
+        Err(err) => return Err(err.into()),
+    };
+    if edited.trim().is_empty() {
+        return Ok(0);
+    }
+
+    let execution_cwd = resolve_history_cwd(&last.record.working_directory, fallback_cwd);
+    let result = run_shell_and_record(edited.trim(), &execution_cwd, shell, env, history_config)?;
+    print_summary(&result);
+    Ok(result.exit_code)
+}
+
+/// Runs the commands recorded in `path` in order, stopping at the first failure unless `keep_going`.
+fn replay(
+    path: &Path,
+    fallback_cwd: &Path,
+    keep_going: bool,
+    env: &[(String, String)],
+    history_config: &config::HistoryConfig,
+) -> Result<i32, RtError> {
+    if !path.is_file() {
+        return Err(RtError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("replay file not found: {}", path.display()),
+        )));
+    }
+    let records: Vec<history::HistoryRecord> = history::HistoryStore::new(path.to_path_buf())
+        .read_all()
+        .map_err(RtError::Io)?
+        .into_iter()
+        .map(|entry| entry.record)
+        .collect();
+
+    replay_records(&records, fallback_cwd, keep_going, &mut |record, cwd| {
+        if record.hashed {
+            return Err(RtError::HashedHistory);
+        }
+        let result = exec::run_program(&record.program, &record.args, cwd, env)?;
+        record_history(&result, cwd, record.desc.as_deref(), history_config);
+        Ok(result.exit_code)
+    })
+}
+
+fn replay_records(
+    records: &[history::HistoryRecord],
+    fallback_cwd: &Path,
+    keep_going: bool,
+    run: &mut dyn FnMut(&history::HistoryRecord, &Path) -> Result<i32, RtError>,
+) -> Result<i32, RtError> {
+    let mut exit_code = 0;
+    for (idx, record) in records.iter().enumerate() {
+        let cwd = resolve_history_cwd(&record.working_directory, fallback_cwd);
+        term::notice(format_args!(
+            "[{}/{}] {}",
+            idx + 1,
+            records.len(),
+            record.display_command()
+        ));
+
+        let code = match run(record, &cwd) {
+            Ok(code) => code,
+            Err(err) if keep_going => {
+                eprintln!("{err}");
+                classify_error(&err)
+            }
+            Err(err) => return Err(err),
+        };
+        if code != 0 {
+            exit_code = code;
+            if !keep_going {
+                term::notice(format_args!("stopped: command exited with {code}"));
+                return Ok(code);
+            }
+        }
+    }
+    Ok(exit_code)
+}
+
 fn build_history_choices(records: &[history::StoredRecord], limit: usize) -> Vec<HistoryChoice> {
     records
         .iter()
@@ -165,7 +1486,9 @@ fn build_history_choices(records: &[history::StoredRecord], limit: usize) -> Vec
             working_directory: entry.record.working_directory.clone(),
             program: entry.record.program.clone(),
             args: entry.record.args.clone(),
-            display_command: exec::format_program_args(&entry.record.program, &entry.record.args),
+            display_command: entry.record.display_command(),
+            hashed: entry.record.hashed,
+            description: entry.record.desc.clone(),
         })
         .collect()
 }
@@ -181,28 +1504,347 @@ fn resolve_history_cwd(recorded_cwd: &str, fallback_cwd: &Path) -> PathBuf {
 
 fn execute_and_record(
     detection: &detect::Detection,
-    task: &str,
+    task_item: &tasks::TaskItem,
     passthrough: &[String],
     cwd: &Path,
+    cli: &Cli,
+    config: &config::Config,
+    interactive: bool,
 ) -> Result<i32, RtError> {
-    let result = exec::run(detection.runner, task, passthrough, cwd)?;
-    let _ = history::append_default(history::RecordInput {
-        program: &result.program,
-        args: &result.args,
-        working_directory: cwd,
-        exit_code: result.exit_code,
-    });
+    let task = task_item.name.as_str();
+    let options = exec::RunOptions {
+        timeout: cli
+            .timeout
+            .map(Duration::from_secs)
+            .or_else(|| config.timeout.for_task(task)),
+        mise_exec: config.mise.activate && exec::mise_manages(detection),
+        env: cli.env_vars(),
+        log: cli.log.clone(),
+    };
+    if cli.pick {
+        println!("{}", exec::preview_command(detection, task, passthrough)?);
+        return Ok(0);
+    }
+    if cli.dry_run {
+        let invocation = exec::planned_invocation(detection, task, passthrough, &options)?;
+        println!(
+            "{}",
+            exec::format_program_args(&invocation.program, &invocation.args)
+        );
+        return Ok(0);
+    }
+
+    let needs_confirmation = config
+        .confirm
+        .requires_confirmation(task, cli.confirm_destructive);
+    if needs_confirmation && !confirm_run(detection, task, passthrough)? {
+        return Ok(0);
+    }
+
+    // Runs append to the log, so truncate it once up front; repeated runs all land in one file.
+    if let Some(path) = cli.log.as_deref()
+        && !cli.log_append
+    {
+        std::fs::File::create(path).map_err(|source| RtError::LogFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    }
+    let runs = cli.repeat.map_or(1, NonZeroUsize::get);
+    let mut completed = 0;
+    let mut passed = 0;
+    let mut final_code = 0;
+    for _ in 0..runs {
+        let result = exec::run(detection, task, passthrough, cwd, &options)?;
+        if let Some(timeout) = options.timeout
+            && result.timed_out
+        {
+            term::notice(format_args!(
+                "rt: `{task}` timed out after {}s",
+                timeout.as_secs()
+            ));
+        }
+        // The temporary justfile is gone once rt exits, so the command couldn't be rerun.
+        if !cli.file_from_stdin {
+            record_history(
+                &result,
+                cwd,
+                task_item.description.as_deref(),
+                &config.history,
+            );
+        }
+        if interactive || cli.summary {
+            print_summary(&result);
+        }
+        // A hook that can't start is reported, but rt still exits with the task's code.
+        if let Some(hook) = cli.on_fail.as_deref()
+            && let Err(err) = run_on_fail(hook, task, result.exit_code, cwd, &cli.shell())
+        {
+            term::notice(format_args!("rt: --on-fail hook failed: {err}"));
+        }
+
+        completed += 1;
+        if result.exit_code == 0 {
+            passed += 1;
+        } else {
+            final_code = result.exit_code;
+            if !cli.keep_going {
+                break;
+            }
+        }
+    }
+    if cli.repeat.is_some() {
+        term::notice(repeat_summary(task, passed, completed, runs));
+    }
+
+    Ok(final_code)
+}
+
+/// Describes a `--repeat` run, e.g. "rt: `test` passed 8/10 runs".
+fn repeat_summary(task: &str, passed: usize, completed: usize, runs: usize) -> String {
+    if completed < runs {
+        format!(
+            "rt: `{task}` passed {passed}/{completed} runs (stopped at the first failure of {runs})"
+        )
+    } else {
+        format!("rt: `{task}` passed {passed}/{runs} runs")
+    }
+}
+
+/// Runs the `--on-fail` hook when `exit_code` is non-zero; returns the hook's exit code if it ran.
+///
+/// The hook isn't recorded in history, and its exit code doesn't replace the task's.
+fn run_on_fail(
+    hook: &str,
+    task: &str,
+    exit_code: i32,
+    cwd: &Path,
+    shell: &str,
+) -> Result<Option<i32>, RtError> {
+    if exit_code == 0 {
+        return Ok(None);
+    }
+    let env = [
+        ("RT_TASK", task.to_string()),
+        ("RT_EXIT", exit_code.to_string()),
+    ];
+    exec::run_hook(hook, cwd, shell, &env).map(Some)
+}
+
+/// Prints the result line to stderr, closing out a run after the picker UI has cleared.
+///
+/// Only on a terminal, and not with `--quiet` unless `--summary` asks for it.
+fn print_summary(result: &exec::RunResult) {
+    if term::stderr_is_terminal() && term::summary_enabled() {
+        eprintln!("{}", format_summary(result, term::color_enabled(true)));
+    }
+}
+
+fn format_summary(result: &exec::RunResult, color: bool) -> String {
+    let mark = if result.exit_code == 0 {
+        term::paint("✓", "32", color)
+    } else {
+        term::paint("✗", "31", color)
+    };
+    format!(
+        "{mark} {} (exit {}, {:.1}s)",
+        exec::format_program_args(&result.program, &result.args),
+        result.exit_code,
+        result.duration.as_secs_f64()
+    )
+}
+
+/// How much of a task's argument list rt asks for interactively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgPrompt {
+    /// Never prompt; pass CLI passthrough straight to the runner (`--no-args`).
+    Never,
+    /// Prompt only for missing required arguments.
+    Required,
+    /// Also ask for free-form optional arguments (`--args`).
+    Optional,
+    /// Prompt for every declared parameter (`--prompt-all-args`).
+    All,
+}
+
+fn confirm_run(
+    detection: &detect::Detection,
+    task: &str,
+    passthrough: &[String],
+) -> Result<bool, RtError> {
+    let preview = exec::preview_command(detection, task, passthrough)?;
+    match inquire::Confirm::new(&format!("Run `{preview}`?"))
+        .with_default(false)
+        .prompt()
+    {
+        Ok(confirmed) => Ok(confirmed),
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn collect_passthrough(
+    detection: &detect::Detection,
+    task: &str,
+    cli_passthrough: &[String],
+    env: &[(String, String)],
+    arg_prompt: ArgPrompt,
+    ui: &config::UiConfig,
+) -> Result<Option<Vec<String>>, RtError> {
+    if arg_prompt == ArgPrompt::Never {
+        return Ok(Some(cli_passthrough.to_vec()));
+    }
+
+    let (variables, params): (Vec<_>, Vec<_>) = known_task_params(detection, task)?
+        .into_iter()
+        .partition(|param| param.kind == task_args::ParamKind::Variable);
+    let (flags, params): (Vec<_>, Vec<_>) = params
+        .into_iter()
+        .partition(|param| matches!(param.kind, task_args::ParamKind::Flag { .. }));
+    let Some(assignments) = prompt_variables(detection, task, &variables, cli_passthrough, env)?
+    else {
+        return Ok(None);
+    };
+    let Some(mut passthrough) =
+        collect_positional(detection, task, params, cli_passthrough, arg_prompt, ui)?
+    else {
+        return Ok(None);
+    };
+    let Some(flag_args) = prompt_flags(detection, task, &flags, &passthrough, arg_prompt, ui)?
+    else {
+        return Ok(None);
+    };
+    passthrough.extend(flag_args);
+    passthrough.extend(assignments);
+    Ok(Some(passthrough))
+}
+
+/// Collects `--flag [value]` arguments for declared flags not already in `current`.
+///
+/// Required flags are always asked for; with `--args`, the optional ones are ticked first and
+/// only those are asked for.
+fn prompt_flags(
+    detection: &detect::Detection,
+    task: &str,
+    flags: &[task_args::TaskParam],
+    current: &[String],
+    arg_prompt: ArgPrompt,
+    ui: &config::UiConfig,
+) -> Result<Option<Vec<String>>, RtError> {
+    let missing: Vec<&task_args::TaskParam> = flags
+        .iter()
+        .filter(|flag| !flag_is_given(&flag.name, current))
+        .collect();
+    let mut wanted: Vec<&task_args::TaskParam> = missing
+        .iter()
+        .copied()
+        .filter(|flag| flag.required)
+        .collect();
+    let optional: Vec<&task_args::TaskParam> = missing
+        .iter()
+        .copied()
+        .filter(|flag| !flag.required)
+        .collect();
+    if matches!(arg_prompt, ArgPrompt::Optional | ArgPrompt::All) && !optional.is_empty() {
+        let labels: Vec<String> = optional.iter().map(|flag| flag_label(flag)).collect();
+        let message = format!("Options to set for {task}");
+        match prompt::multi_select(&message, labels, ui).raw_prompt() {
+            Ok(picked) => wanted.extend(picked.into_iter().map(|option| optional[option.index])),
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+                return Ok(None);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let mut args = Vec::new();
+    for flag in wanted {
+        let task_args::ParamKind::Flag { takes_value: true } = flag.kind else {
+            args.push(flag.name.clone());
+            continue;
+        };
+        let preview: Vec<String> = current.iter().chain(&args).cloned().collect();
+        let value = if flag.required {
+            prompt_required_argument(detection, task, &flag.name, &preview)?
+        } else {
+            prompt_param_value(detection, task, flag, &preview)?
+        };
+        match value {
+            Some(value) if !value.is_empty() => args.extend([flag.name.clone(), value]),
+            Some(_) => {}
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(args))
+}
 
-    Ok(result.exit_code)
+/// Whether `flag` (e.g. `--port`) already appears in `args`, alone or as `--port=80`.
+fn flag_is_given(flag: &str, args: &[String]) -> bool {
+    args.iter().any(|arg| {
+        arg == flag
+            || arg
+                .strip_prefix(flag)
+                .is_some_and(|rest| rest.starts_with('='))
+    })
 }
 
-fn collect_passthrough(
+fn flag_label(flag: &task_args::TaskParam) -> String {
+    match flag.kind {
+        task_args::ParamKind::Flag { takes_value: true } => format!("{} <value>", flag.name),
+        _ => flag.name.clone(),
+    }
+}
+
+/// Prompts for required `NAME=value` variables not already assigned in `cli_passthrough`, the
+/// environment, or `env` (`--env`), since the runner reads those too.
+fn prompt_variables(
     detection: &detect::Detection,
     task: &str,
+    variables: &[task_args::TaskParam],
     cli_passthrough: &[String],
-    prompt_optional_args: bool,
+    env: &[(String, String)],
+) -> Result<Option<Vec<String>>, RtError> {
+    let mut assignments = Vec::new();
+    for variable in variables.iter().filter(|variable| variable.required) {
+        let prefix = format!("{}=", variable.name);
+        if cli_passthrough.iter().any(|arg| arg.starts_with(&prefix))
+            || env.iter().any(|(key, _)| *key == variable.name)
+            || std::env::var_os(&variable.name).is_some()
+        {
+            continue;
+        }
+        let current: Vec<String> = cli_passthrough
+            .iter()
+            .chain(&assignments)
+            .cloned()
+            .collect();
+        match prompt_required_argument(detection, task, &variable.name, &current)? {
+            Some(value) => assignments.push(format!("{prefix}{value}")),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(assignments))
+}
+
+/// Collects positional arguments, prompting for missing required ones as `arg_prompt` allows.
+fn collect_positional(
+    detection: &detect::Detection,
+    task: &str,
+    params: Vec<task_args::TaskParam>,
+    cli_passthrough: &[String],
+    arg_prompt: ArgPrompt,
+    ui: &config::UiConfig,
 ) -> Result<Option<Vec<String>>, RtError> {
-    let required = task_args::required_args_for_task(detection, task).map_err(RtError::Io)?;
+    if arg_prompt == ArgPrompt::All && !params.is_empty() {
+        return prompt_all_params(detection, task, &params, cli_passthrough);
+    }
+
+    let required: Vec<String> = params
+        .iter()
+        .filter(|param| param.required)
+        .map(|param| param.name.clone())
+        .collect();
+    let prompt_optional_args = matches!(arg_prompt, ArgPrompt::Optional | ArgPrompt::All);
     let plan = build_passthrough_plan(&required, cli_passthrough, prompt_optional_args);
     let mut passthrough = plan.initial_passthrough;
 
@@ -211,7 +1853,7 @@ fn collect_passthrough(
     }
 
     for name in &plan.missing_required {
-        let value = match prompt_required_argument(detection.runner, task, name, &passthrough)? {
+        let value = match prompt_required_argument(detection, task, name, &passthrough)? {
             Some(value) => value,
             None => return Ok(None),
         };
@@ -219,7 +1861,16 @@ fn collect_passthrough(
     }
 
     if plan.prompt_optional_args {
-        let optional = match prompt_optional_passthrough(detection.runner, task, &passthrough)? {
+        let optional: Vec<task_args::TaskParam> = params
+            .into_iter()
+            .skip(passthrough.len())
+            .filter(|param| !param.required)
+            .collect();
+        if !optional.is_empty() {
+            return prompt_selected_optional_params(detection, task, &optional, passthrough, ui);
+        }
+
+        let optional = match prompt_optional_passthrough(detection, task, &passthrough)? {
             Some(args) => args,
             None => return Ok(None),
         };
@@ -229,6 +1880,28 @@ fn collect_passthrough(
     Ok(Some(passthrough))
 }
 
+/// Returns the task's parameters, or none when rt's parser doesn't find the task.
+///
+/// The parser can miss tasks the runner knows, so an unknown task still runs and the runner
+/// reports it; rt only prints the closest declared name as a hint.
+fn known_task_params(
+    detection: &detect::Detection,
+    task: &str,
+) -> Result<Vec<task_args::TaskParam>, RtError> {
+    if let Some(params) = task_args::params_for_task(detection, task).map_err(RtError::Io)? {
+        return Ok(params);
+    }
+    let declared =
+        parser::declared_tasks(detection.runner, &detection.runner_file).unwrap_or_default();
+    if let Some(name) = tasks::suggest_task(task, &declared) {
+        term::notice(format_args!(
+            "rt: `{task}` isn't in {}; did you mean `{name}`?",
+            detection.runner_file.display()
+        ));
+    }
+    Ok(Vec::new())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct PassthroughPlan {
     initial_passthrough: Vec<String>,
@@ -250,14 +1923,14 @@ fn build_passthrough_plan(
 }
 
 fn prompt_required_argument(
-    runner: detect::Runner,
+    detection: &detect::Detection,
     task: &str,
     name: &str,
     current: &[String],
 ) -> Result<Option<String>, RtError> {
     loop {
         let message = format!("Value for required arg {name}");
-        let preview = exec::preview_command(runner, task, current);
+        let preview = exec::preview_command(detection, task, current)?;
         match inquire::Text::new(&message)
             .with_help_message(&format!("Current: $ {preview}"))
             .prompt()
@@ -273,17 +1946,147 @@ fn prompt_required_argument(
             Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
                 return Ok(None);
             }
-            Err(err) => return Err(RtError::Prompt(err)),
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Prompts for each declared parameter not already covered by CLI passthrough, in order.
+fn prompt_all_params(
+    detection: &detect::Detection,
+    task: &str,
+    params: &[task_args::TaskParam],
+    cli_passthrough: &[String],
+) -> Result<Option<Vec<String>>, RtError> {
+    let remaining = params.get(cli_passthrough.len()..).unwrap_or_default();
+    let selected = vec![true; remaining.len()];
+    prompt_params(
+        detection,
+        task,
+        remaining,
+        &selected,
+        cli_passthrough.to_vec(),
+    )
+}
+
+/// Lets the user tick which optional parameters to set, then prompts for just those.
+fn prompt_selected_optional_params(
+    detection: &detect::Detection,
+    task: &str,
+    optional: &[task_args::TaskParam],
+    passthrough: Vec<String>,
+    ui: &config::UiConfig,
+) -> Result<Option<Vec<String>>, RtError> {
+    let names: Vec<String> = optional.iter().map(|param| param.name.clone()).collect();
+    let message = format!("Optional arguments to set for {task}");
+    let picked = match prompt::multi_select(&message, names, ui).raw_prompt() {
+        Ok(picked) => picked,
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+            return Ok(None);
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut selected = vec![false; optional.len()];
+    for option in picked {
+        selected[option.index] = true;
+    }
+    prompt_params(detection, task, optional, &selected, passthrough)
+}
+
+/// Prompts for the selected `params` in order, appending values to `passthrough`.
+///
+/// Parameters are positional, so unselected ones before the last selected one still need a
+/// value: their default is used when known, otherwise they are prompted for as well.
+fn prompt_params(
+    detection: &detect::Detection,
+    task: &str,
+    params: &[task_args::TaskParam],
+    selected: &[bool],
+    mut passthrough: Vec<String>,
+) -> Result<Option<Vec<String>>, RtError> {
+    let Some(last_selected) = selected.iter().rposition(|selected| *selected) else {
+        return Ok(Some(passthrough));
+    };
+
+    for (param, selected) in params.iter().zip(selected).take(last_selected + 1) {
+        if !selected && let Some(default) = &param.default {
+            passthrough.push(default.clone());
+            continue;
+        }
+
+        if param.required && !param.variadic {
+            match prompt_required_argument(detection, task, &param.name, &passthrough)? {
+                Some(value) => passthrough.push(value),
+                None => return Ok(None),
+            }
+            continue;
+        }
+
+        let value = match prompt_param_value(detection, task, param, &passthrough)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        if value.is_empty() {
+            if param.required {
+                eprintln!(
+                    "Argument `{}` is required. Enter a value or cancel.",
+                    param.name
+                );
+                return Ok(None);
+            }
+            // Later parameters are positional, so they can't be passed once one is skipped.
+            break;
         }
+        if param.variadic {
+            passthrough.extend(split_interactive_passthrough(&value));
+        } else {
+            passthrough.push(value);
+        }
+    }
+
+    Ok(Some(passthrough))
+}
+
+fn prompt_param_value(
+    detection: &detect::Detection,
+    task: &str,
+    param: &task_args::TaskParam,
+    current: &[String],
+) -> Result<Option<String>, RtError> {
+    let message = if param.variadic {
+        format!("Values for {} (space-separated)", param.name)
+    } else {
+        format!("Value for {}", param.name)
+    };
+    let preview = exec::preview_command(detection, task, current)?;
+    let help = param_help(&preview, param.default.as_deref());
+    let mut prompt = inquire::Text::new(&message).with_help_message(&help);
+    if let Some(default) = &param.default {
+        // Prefilled so the default can be edited; clearing it still falls back to the default.
+        prompt = prompt.with_initial_value(default).with_default(default);
+    }
+
+    match prompt.prompt() {
+        Ok(input) => Ok(Some(input.trim().to_string())),
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn param_help(preview: &str, default: Option<&str>) -> String {
+    match default {
+        Some(default) => format!("Press enter for default `{default}` · Current: $ {preview}"),
+        None => format!("Current: $ {preview}"),
     }
 }
 
 fn prompt_optional_passthrough(
-    runner: detect::Runner,
+    detection: &detect::Detection,
     task: &str,
     current: &[String],
 ) -> Result<Option<Vec<String>>, RtError> {
-    let preview = exec::preview_command(runner, task, current);
+    let preview = exec::preview_command(detection, task, current)?;
     let message = format!("Additional arguments for {task} (optional, space-separated)");
     match inquire::Text::new(&message)
         .with_help_message(&format!("Current: $ {preview}"))
@@ -291,7 +2094,7 @@ fn prompt_optional_passthrough(
     {
         Ok(input) => Ok(Some(split_interactive_passthrough(&input))),
         Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(None),
-        Err(err) => Err(RtError::Prompt(err)),
+        Err(err) => Err(err.into()),
     }
 }
 
@@ -305,11 +2108,26 @@ fn split_interactive_passthrough(input: &str) -> Vec<String> {
 fn classify_error(err: &RtError) -> i32 {
     match err {
         RtError::NoRunnerFound { .. }
+        | RtError::RunnerNotDetected { .. }
         | RtError::ToolMissing { .. }
         | RtError::ToolMissingCommand { .. }
         | RtError::NoTasks { .. }
-        | RtError::ListFailed { .. } => 3,
-        RtError::Prompt(_) | RtError::Io(_) | RtError::Spawn(_) => 2,
+        | RtError::ListFailed { .. }
+        | RtError::UnknownTask { .. }
+        | RtError::UnnamedYarnWorkspace { .. }
+        | RtError::GraphUnsupported { .. }
+        | RtError::RunnerFileNotFound { .. }
+        | RtError::UnknownBookmark { .. }
+        | RtError::HashedHistory => exit_code::NOTHING_TO_RUN,
+        RtError::InvalidFormat { .. } | RtError::FileUnsupported { .. } | RtError::NoTerminal => {
+            exit_code::USAGE
+        }
+        RtError::InvalidBookmark { .. }
+        | RtError::Prompt(_)
+        | RtError::Io(_)
+        | RtError::Spawn(_)
+        | RtError::LogFile { .. }
+        | RtError::Config { .. } => exit_code::RT_ERROR,
     }
 }
 
@@ -325,25 +2143,26 @@ impl fmt::Display for RunnerItem {
             .file_name()
             .map(|name| name.to_string_lossy().into_owned())
             .unwrap_or_else(|| self.detection.runner_file.to_string_lossy().into_owned());
-        write!(
-            f,
-            "{} ({})",
-            filename,
-            detect::runner_command(self.detection.runner)
-        )
+        match self.detection.package_manager {
+            Some(manager) => write!(f, "{filename} ({} run)", manager.command()),
+            None => write!(f, "{filename} ({})", self.detection.command()),
+        }
     }
 }
 
-fn select_runner(detections: Vec<detect::Detection>) -> Result<Option<detect::Detection>, RtError> {
+fn select_runner(
+    detections: Vec<detect::Detection>,
+    ui: &config::UiConfig,
+) -> Result<Option<detect::Detection>, RtError> {
     let items: Vec<RunnerItem> = detections
         .into_iter()
         .map(|detection| RunnerItem { detection })
         .collect();
 
-    match inquire::Select::new("Select runner", items).prompt() {
+    match prompt::select("Select runner", items, ui).prompt() {
         Ok(item) => Ok(Some(item.detection)),
         Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(None),
-        Err(err) => Err(RtError::Prompt(err)),
+        Err(err) => Err(err.into()),
     }
 }
 
@@ -351,25 +2170,78 @@ fn select_runner(detections: Vec<detect::Detection>) -> Result<Option<detect::De
 pub enum RtError {
     #[error("no runner found in {cwd:?}")]
     NoRunnerFound { cwd: PathBuf },
+    #[error("no {runner} runner file in {cwd:?}")]
+    RunnerNotDetected { runner: &'static str, cwd: PathBuf },
     #[error("required tool not found in PATH: {tool}")]
     ToolMissing { tool: &'static str },
     #[error("required tool not found in PATH: {tool}")]
     ToolMissingCommand { tool: String },
-    #[error("no tasks found using {tool}")]
-    NoTasks { tool: &'static str },
+    #[error(
+        "runner file {path:?} defines no tasks; add one (e.g. with `rt --edit`) or check it is the file {tool} reads"
+    )]
+    NoTasks { tool: &'static str, path: PathBuf },
+    #[error(
+        "task `{task}` not found{}",
+        suggestion.as_ref().map(|name| format!("; did you mean `{name}`?")).unwrap_or_default()
+    )]
+    UnknownTask {
+        task: String,
+        suggestion: Option<String>,
+    },
+    #[error(
+        "yarn can only run scripts of named workspaces; add a \"name\" to {selector}/package.json"
+    )]
+    UnnamedYarnWorkspace { selector: String },
+    #[error("dependency graphs are not supported for {tool}")]
+    GraphUnsupported { tool: &'static str },
+    #[error("--file is not supported for {runner} yet; only just runner files can be given")]
+    FileUnsupported { runner: &'static str },
+    #[error("runner file {path:?} not found")]
+    RunnerFileNotFound { path: PathBuf },
     #[error("failed to list tasks using {tool} (exit code {status})")]
     ListFailed { tool: &'static str, status: i32 },
+    #[error("bookmark `@{name}` not found")]
+    UnknownBookmark { name: String },
+    #[error(
+        "invalid bookmark `{name}`: use `rt --bookmark NAME \"COMMAND\"` with a name without spaces"
+    )]
+    InvalidBookmark { name: String },
+    #[error("invalid --format template: {message}")]
+    InvalidFormat { message: String },
+    #[error(
+        "this history entry only stores a hash of its command (`[history] hash_commands`), so it can't be rerun"
+    )]
+    HashedHistory,
+    #[error("invalid config {path:?}: {message}")]
+    Config { path: PathBuf, message: String },
     #[error("prompt error: {0}")]
-    Prompt(#[from] inquire::error::InquireError),
+    Prompt(inquire::error::InquireError),
+    #[error("cannot prompt: no terminal to read from; pass the task name explicitly")]
+    NoTerminal,
     #[error("io error: {0}")]
     Io(std::io::Error),
     #[error("failed to spawn command: {0}")]
     Spawn(std::io::Error),
+    #[error("cannot write log file {path:?}: {source}")]
+    LogFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl From<InquireError> for RtError {
+    fn from(err: InquireError) -> Self {
+        match err {
+            InquireError::NotTTY => RtError::NoTerminal,
+            err => RtError::Prompt(err),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::detection_at;
 
     #[test]
     fn normalize_passthrough_strips_separator_only_when_first() {
@@ -384,27 +2256,84 @@ mod tests {
         assert!(normalize_passthrough(Vec::new()).is_empty());
     }
 
+    #[test]
+    fn normalize_passthrough_keeps_taskfile_variables_and_positionals() {
+        let passthrough = normalize_passthrough(vec![
+            "--".into(),
+            "ENV=prod".into(),
+            "--".into(),
+            "extra".into(),
+        ]);
+        assert_eq!(
+            passthrough,
+            vec![
+                "ENV=prod".to_string(),
+                "--".to_string(),
+                "extra".to_string()
+            ]
+        );
+        let detection = detection_at(detect::Runner::Taskfile, "Taskfile.yml");
+        let invocation = exec::invocation(&detection, "deploy", &passthrough).unwrap();
+        assert_eq!(invocation.args, vec!["deploy", "ENV=prod", "--", "extra"]);
+    }
+
     #[test]
     fn classify_error_returns_expected_exit_codes() {
         let cwd = PathBuf::from(".");
-        assert_eq!(classify_error(&RtError::NoRunnerFound { cwd }), 3);
-        assert_eq!(classify_error(&RtError::ToolMissing { tool: "just" }), 3);
-        assert_eq!(classify_error(&RtError::NoTasks { tool: "just" }), 3);
+        assert_eq!(
+            classify_error(&RtError::NoRunnerFound { cwd: cwd.clone() }),
+            exit_code::NOTHING_TO_RUN
+        );
+        assert_eq!(
+            classify_error(&RtError::RunnerNotDetected {
+                runner: "task",
+                cwd: cwd.clone()
+            }),
+            exit_code::NOTHING_TO_RUN
+        );
+        assert_eq!(
+            classify_error(&RtError::ToolMissing { tool: "just" }),
+            exit_code::NOTHING_TO_RUN
+        );
+        assert_eq!(
+            classify_error(&RtError::NoTasks {
+                tool: "just",
+                path: PathBuf::from("justfile")
+            }),
+            exit_code::NOTHING_TO_RUN
+        );
         assert_eq!(
             classify_error(&RtError::ListFailed {
                 tool: "just",
                 status: 1
             }),
-            3
+            exit_code::NOTHING_TO_RUN
+        );
+        assert_eq!(
+            classify_error(&RtError::InvalidFormat {
+                message: String::new()
+            }),
+            exit_code::USAGE
         );
         assert_eq!(
             classify_error(&RtError::Io(std::io::Error::from(
                 std::io::ErrorKind::Other
             ))),
-            2
+            exit_code::RT_ERROR
         );
     }
 
+    #[test]
+    fn prompts_without_a_terminal_become_no_terminal_errors() {
+        let err = RtError::from(InquireError::NotTTY);
+        assert!(matches!(err, RtError::NoTerminal));
+        assert_eq!(classify_error(&err), exit_code::USAGE);
+        assert!(matches!(
+            RtError::from(InquireError::InvalidConfiguration("x".to_string())),
+            RtError::Prompt(_)
+        ));
+    }
+
     #[test]
     fn split_interactive_passthrough_handles_whitespace() {
         assert_eq!(
@@ -417,28 +2346,132 @@ mod tests {
 
     #[test]
     fn prompt_passthrough_prefers_cli_passthrough() {
-        let detection = detect::Detection {
-            runner: detect::Runner::Taskfile,
-            runner_file: PathBuf::from("Taskfile.yml"),
-        };
+        let detection = detection_at(detect::Runner::Taskfile, "Taskfile.yml");
         let passthrough = vec!["--flag".to_string(), "value".to_string()];
-        let result = collect_passthrough(&detection, "build", &passthrough, false)
-            .unwrap()
-            .unwrap();
+        let result = collect_passthrough(
+            &detection,
+            "build",
+            &passthrough,
+            &[],
+            ArgPrompt::Required,
+            &config::UiConfig::default(),
+        )
+        .unwrap()
+        .unwrap();
         assert_eq!(result, passthrough);
     }
 
     #[test]
-    fn cli_from_raw_parses_args_flag_and_passthrough() {
-        let raw = Args {
+    fn required_variables_set_with_env_are_not_prompted() {
+        let dir = tempfile::tempdir().unwrap();
+        let taskfile = dir.path().join("Taskfile.yml");
+        std::fs::write(
+            &taskfile,
+            "version: '3'\ntasks:\n  deploy:\n    requires:\n      vars: [RT_TEST_DEPLOY_ENV]\n    cmds:\n      - echo\n",
+        )
+        .unwrap();
+        let detection = detection_at(detect::Runner::Taskfile, taskfile);
+
+        let env = [("RT_TEST_DEPLOY_ENV".to_string(), "prod".to_string())];
+        let result = collect_passthrough(
+            &detection,
+            "deploy",
+            &[],
+            &env,
+            ArgPrompt::Required,
+            &config::UiConfig::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn no_args_skips_prompting_for_missing_required_args() {
+        let dir = tempfile::tempdir().unwrap();
+        let justfile = dir.path().join("justfile");
+        std::fs::write(&justfile, "deploy ENV TARGET:\n  echo {{ENV}} {{TARGET}}\n").unwrap();
+        let detection = detection_at(detect::Runner::Justfile, justfile);
+
+        let passthrough = vec!["prod".to_string()];
+        let result = collect_passthrough(
+            &detection,
+            "deploy",
+            &passthrough,
+            &[],
+            ArgPrompt::Never,
+            &config::UiConfig::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(result, passthrough);
+    }
+
+    #[test]
+    fn no_args_overrides_config_prompt_args() {
+        let cli = Cli {
+            prompt_args: true,
+            no_args: true,
+            ..Cli::from_raw(args().run_inner(&[] as &[&str]).unwrap())
+        };
+        let config = config::Config {
             prompt_args: true,
-            history: true,
-            task: Some("build".to_string()),
-            rest: vec!["--".to_string(), "--env".to_string(), "prod".to_string()],
+            ..config::Config::default()
         };
+        assert_eq!(cli.arg_prompt(&config), ArgPrompt::Never);
+
+        let default_cli = Cli::from_raw(args().run_inner(&[] as &[&str]).unwrap());
+        assert_eq!(default_cli.arg_prompt(&config), ArgPrompt::Optional);
+    }
+
+    #[test]
+    fn pick_prompts_for_optional_args_unless_disabled() {
+        let config = config::Config::default();
+        let pick = Cli::from_raw(args().run_inner(&["--pick"]).unwrap());
+        assert!(pick.pick);
+        assert_eq!(pick.arg_prompt(&config), ArgPrompt::Optional);
+
+        let pick_all = Cli::from_raw(args().run_inner(&["--pick", "--prompt-all-args"]).unwrap());
+        assert_eq!(pick_all.arg_prompt(&config), ArgPrompt::All);
+        let pick_none = Cli::from_raw(args().run_inner(&["--pick", "--no-args"]).unwrap());
+        assert_eq!(pick_none.arg_prompt(&config), ArgPrompt::Never);
+    }
+
+    #[test]
+    fn quiet_overrides_verbose() {
+        let cli = Cli::from_raw(args().run_inner(&["--quiet", "--verbose"]).unwrap());
+        assert!(cli.quiet);
+        assert!(!cli.verbose);
+    }
+
+    #[test]
+    fn repeat_rejects_zero_and_summarizes_runs() {
+        assert!(args().run_inner(&["--repeat", "0", "test"]).is_err());
+        let cli = Cli::from_raw(args().run_inner(&["--repeat", "3", "test"]).unwrap());
+        assert_eq!(cli.repeat.map(NonZeroUsize::get), Some(3));
+
+        assert_eq!(
+            repeat_summary("test", 3, 3, 3),
+            "rt: `test` passed 3/3 runs"
+        );
+        assert_eq!(
+            repeat_summary("test", 1, 2, 5),
+            "rt: `test` passed 1/2 runs (stopped at the first failure of 5)"
+        );
+    }
+
+    #[test]
+    fn cli_from_raw_parses_args_flag_and_passthrough() {
+        let raw = args()
+            .run_inner(&["--args", "--history", "build", "--", "--env", "prod"])
+            .unwrap();
         let cli = Cli::from_raw(raw);
         assert!(cli.prompt_args);
         assert!(cli.history);
+        assert_eq!(
+            cli.arg_prompt(&config::Config::default()),
+            ArgPrompt::Optional
+        );
         assert_eq!(cli.task.as_deref(), Some("build"));
         assert_eq!(
             cli.passthrough,
@@ -446,6 +2479,255 @@ mod tests {
         );
     }
 
+    #[test]
+    fn shell_command_line_joins_quoted_or_split_commands() {
+        assert_eq!(shell_command_line(Some("npm test"), &[]), "npm test");
+        assert_eq!(
+            shell_command_line(Some("npm"), &["run".to_string(), "lint".to_string()]),
+            "npm run lint"
+        );
+        assert_eq!(shell_command_line(None, &[]), "");
+    }
+
+    #[test]
+    fn resolve_cwd_accepts_only_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(resolve_cwd(Some(dir.path())).unwrap(), dir.path());
+        assert_eq!(resolve_cwd(None).unwrap(), std::env::current_dir().unwrap());
+
+        let file = dir.path().join("justfile");
+        std::fs::write(&file, "").unwrap();
+        for bad in [file, dir.path().join("missing")] {
+            match resolve_cwd(Some(&bad)).unwrap_err() {
+                RtError::Io(err) => assert!(err.to_string().contains("not a directory")),
+                other => panic!("unexpected error: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn on_fail_hook_runs_only_after_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let hook = "echo \"$RT_TASK $RT_EXIT\" > hook.out";
+
+        assert_eq!(
+            run_on_fail(hook, "test", 0, dir.path(), "sh").unwrap(),
+            None
+        );
+        assert!(!dir.path().join("hook.out").exists());
+
+        assert_eq!(
+            run_on_fail(hook, "test", 2, dir.path(), "sh").unwrap(),
+            Some(0)
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("hook.out")).unwrap(),
+            "test 2\n"
+        );
+    }
+
+    #[test]
+    fn flag_is_given_matches_bare_and_assigned_flags() {
+        let args = vec!["dir".to_string(), "--port=80".to_string(), "-o".to_string()];
+        assert!(flag_is_given("--port", &args));
+        assert!(flag_is_given("-o", &args));
+        assert!(!flag_is_given("--po", &args));
+        assert!(!flag_is_given("--host", &args));
+    }
+
+    #[test]
+    fn param_help_mentions_the_default() {
+        assert_eq!(
+            param_help("just deploy", Some("prod")),
+            "Press enter for default `prod` · Current: $ just deploy"
+        );
+        assert_eq!(param_help("just deploy", None), "Current: $ just deploy");
+    }
+
+    #[test]
+    fn format_params_marks_optional_and_variadic() {
+        let param = |name: &str, required: bool, variadic: bool| task_args::TaskParam {
+            name: name.to_string(),
+            required,
+            variadic,
+            default: None,
+            kind: task_args::ParamKind::Positional,
+        };
+        assert_eq!(
+            format_params(&[
+                param("target", true, false),
+                param("env", false, false),
+                param("files", false, true)
+            ]),
+            "target [env] [files...]"
+        );
+    }
+
+    #[test]
+    fn list_prints_names_only_when_piped_unless_verbose() {
+        assert!(!list_is_compact(false, false, true));
+        assert!(list_is_compact(false, false, false));
+        assert!(!list_is_compact(false, true, false));
+        assert!(list_is_compact(true, true, true));
+    }
+
+    #[test]
+    fn format_summary_reports_command_exit_code_and_duration() {
+        let result = exec::RunResult {
+            exit_code: 0,
+            timed_out: false,
+            program: "just".to_string(),
+            args: vec!["test".to_string()],
+            duration: std::time::Duration::from_millis(1200),
+        };
+        assert_eq!(format_summary(&result, false), "✓ just test (exit 0, 1.2s)");
+
+        let failed = exec::RunResult {
+            exit_code: 2,
+            timed_out: false,
+            program: "make".to_string(),
+            args: vec!["build".to_string()],
+            duration: std::time::Duration::from_millis(400),
+        };
+        assert_eq!(
+            format_summary(&failed, false),
+            "✗ make build (exit 2, 0.4s)"
+        );
+        assert_eq!(
+            format_summary(&failed, true),
+            "\x1b[31m✗\x1b[0m make build (exit 2, 0.4s)"
+        );
+    }
+
+    fn replay_record(program: &str, working_directory: &str) -> history::HistoryRecord {
+        history::HistoryRecord {
+            schema_version: 2,
+            timestamp: "2026-02-11T00:00:00Z".to_string(),
+            program: program.to_string(),
+            args: vec![],
+            working_directory: working_directory.to_string(),
+            exit_code: 0,
+            duration_ms: None,
+            hashed: false,
+            desc: None,
+        }
+    }
+
+    #[test]
+    fn replay_records_stops_at_first_failure() {
+        let fallback = std::env::temp_dir();
+        let records = vec![
+            replay_record("true", "/path/that/does/not/exist"),
+            replay_record("false", "/path/that/does/not/exist"),
+            replay_record("true", "/path/that/does/not/exist"),
+        ];
+        let mut ran = Vec::new();
+        let code = replay_records(&records, &fallback, false, &mut |record, cwd| {
+            ran.push((record.program.clone(), cwd.to_path_buf()));
+            Ok(if record.program == "false" { 1 } else { 0 })
+        })
+        .unwrap();
+
+        assert_eq!(code, 1);
+        assert_eq!(
+            ran,
+            vec![
+                ("true".to_string(), fallback.clone()),
+                ("false".to_string(), fallback.clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_records_keep_going_runs_everything_and_reports_failure() {
+        let fallback = std::env::temp_dir();
+        let records = vec![
+            replay_record("missing-tool", "."),
+            replay_record("false", "."),
+            replay_record("true", "."),
+        ];
+        let mut ran = 0;
+        let code = replay_records(&records, &fallback, true, &mut |record, _cwd| {
+            ran += 1;
+            match record.program.as_str() {
+                "missing-tool" => Err(RtError::ToolMissingCommand {
+                    tool: record.program.clone(),
+                }),
+                "false" => Ok(1),
+                _ => Ok(0),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(ran, 3);
+        assert_eq!(code, 1);
+    }
+
+    fn stored_record(
+        timestamp: &str,
+        working_directory: &str,
+        args: &[&str],
+        exit_code: i32,
+    ) -> history::StoredRecord {
+        history::StoredRecord {
+            raw: String::new(),
+            record: history::HistoryRecord {
+                schema_version: 2,
+                timestamp: timestamp.to_string(),
+                program: "just".to_string(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                working_directory: working_directory.to_string(),
+                exit_code,
+                duration_ms: None,
+                hashed: false,
+                desc: None,
+            },
+        }
+    }
+
+    #[test]
+    fn history_filter_matches_command_text_and_directory() {
+        let entry = stored_record("2026-02-21T12:00:00+09:00", "/api", &["test", "--lib"], 0);
+        let record = &entry.record;
+
+        assert!(HistoryFilter::default().matches(record));
+        let by_text = |text: &str| HistoryFilter {
+            text: Some(text.to_string()),
+            ..HistoryFilter::default()
+        };
+        assert!(by_text("test --lib").matches(record));
+        assert!(!by_text("deploy").matches(record));
+
+        let by_cwd = |cwd: &str| HistoryFilter {
+            cwd: Some(PathBuf::from(cwd)),
+            ..HistoryFilter::default()
+        };
+        assert!(by_cwd("/api").matches(record));
+        assert!(!by_cwd("/web").matches(record));
+    }
+
+    #[test]
+    fn last_failed_in_picks_the_newest_failure_in_the_directory() {
+        let records = vec![
+            stored_record("2026-02-21T12:00:00+09:00", "/api", &["test", "unit"], 1),
+            stored_record("2026-02-21T12:01:00+09:00", "/api", &["test", "e2e"], 2),
+            stored_record("2026-02-21T12:02:00+09:00", "/api", &["build"], 0),
+            stored_record("2026-02-21T12:03:00+09:00", "/web", &["lint"], 1),
+        ];
+
+        let failed = last_failed_in(&records, Path::new("/api")).unwrap();
+        assert_eq!(failed.record.args, vec!["test", "e2e"]);
+        assert_eq!(
+            last_failed_in(&records, Path::new("/web"))
+                .unwrap()
+                .record
+                .args,
+            vec!["lint"]
+        );
+        assert!(last_failed_in(&records[2..3], Path::new("/api")).is_none());
+        assert!(last_failed_in(&records, Path::new("/docs")).is_none());
+    }
+
     #[test]
     fn build_history_choices_returns_newest_first_with_limit() {
         let records = vec![
@@ -458,6 +2740,9 @@ mod tests {
                     args: vec!["a".to_string()],
                     working_directory: "/repo".to_string(),
                     exit_code: 0,
+                    duration_ms: None,
+                    hashed: false,
+                    desc: None,
                 },
             },
             history::StoredRecord {
@@ -469,6 +2754,9 @@ mod tests {
                     args: vec!["b".to_string()],
                     working_directory: "/repo".to_string(),
                     exit_code: 1,
+                    duration_ms: None,
+                    hashed: false,
+                    desc: None,
                 },
             },
         ];
@@ -493,10 +2781,28 @@ mod tests {
             program: "make".to_string(),
             args: vec!["build".to_string()],
             display_command: "make build".to_string(),
+            hashed: false,
+            description: None,
         };
         assert_eq!(choice.to_string(), "make build".to_string());
     }
 
+    #[test]
+    fn history_choice_display_appends_recorded_description() {
+        let choice = HistoryChoice {
+            working_directory: "/repo".to_string(),
+            program: "make".to_string(),
+            args: vec!["build".to_string()],
+            display_command: "make build".to_string(),
+            hashed: false,
+            description: Some("Build the release binary".to_string()),
+        };
+        assert_eq!(
+            choice.to_string(),
+            "make build  -  Build the release binary".to_string()
+        );
+    }
+
     #[test]
     fn build_passthrough_plan_without_args_flag_and_no_required() {
         let required = Vec::<String>::new();
@@ -1,21 +1,149 @@
+use std::path::{Path, PathBuf};
+
 use crate::detect::Runner;
+use crate::task_args::TaskParam;
 use crate::tasks::TaskItem;
 
 mod cargo_make;
+mod composer;
+mod deno;
+mod earthly;
 mod justfile;
+mod mage;
 mod makefile;
 mod mask;
 mod mise;
+mod package_json;
+mod procfile;
+mod rake;
 mod taskfile;
 
 /// Returns parsed tasks from the output of the given runner's list command.
-pub fn parse_tasks(runner: Runner, output: &str) -> Vec<TaskItem> {
+///
+/// Runners without a list command (e.g. Procfile, package.json, composer.json) are parsed from the runner file's contents.
+/// `path` is the runner file, for parsers that read descriptions from the source as well.
+pub fn parse_tasks(runner: Runner, path: &Path, output: &str) -> Vec<TaskItem> {
     match runner {
-        Runner::Justfile => justfile::parse(output),
+        Runner::Justfile => justfile::parse(output, path),
         Runner::Taskfile => taskfile::parse(output),
         Runner::Maskfile => mask::parse(output),
         Runner::Mise => mise::parse(output),
         Runner::CargoMake => cargo_make::parse(output),
-        Runner::Makefile => makefile::parse(output),
+        Runner::Makefile => makefile::parse(output, path),
+        Runner::Rake => rake::parse(output),
+        Runner::Earthly => earthly::parse(output),
+        Runner::Mage => mage::parse(output),
+        Runner::Procfile => procfile::parse(output),
+        Runner::Deno => deno::parse(output),
+        Runner::Composer => composer::parse(output),
+        Runner::PackageJson => package_json::parse(output),
+    }
+}
+
+/// Returns the file and 1-based line where `task` is defined, following imports and includes.
+pub fn locate_recipe(runner: Runner, path: &Path, task: &str) -> Option<(PathBuf, usize)> {
+    match runner {
+        Runner::Justfile => justfile::locate_recipe(path, task),
+        Runner::Makefile => makefile::locate_recipe(path, task),
+        _ => None,
+    }
+}
+
+/// Returns every name the runner file accepts as a task, or `None` when it can't be read statically.
+///
+/// For justfiles this covers recipes across imports, aliases, and module names.
+pub fn declared_tasks(runner: Runner, path: &Path) -> Option<Vec<String>> {
+    match runner {
+        Runner::Justfile => Some(justfile::declared_tasks(path)),
+        _ => None,
     }
 }
+
+/// Returns the variables a Taskfile task requires, or `None` when the task can't be found.
+pub fn taskfile_required_vars(path: &Path, task: &str) -> Option<Vec<String>> {
+    taskfile::required_vars(path, task)
+}
+
+/// Whether a Taskfile task refers to `CLI_ARGS`, so positional arguments belong after `--`.
+pub fn taskfile_uses_cli_args(path: &Path, task: &str) -> bool {
+    taskfile::uses_cli_args(path, task)
+}
+
+/// Returns the scripts of a root `package.json`'s workspace packages as `<workspace>:<script>`.
+pub fn package_workspace_tasks(path: &Path) -> Vec<TaskItem> {
+    package_json::workspace_tasks(path)
+}
+
+/// Returns the workspace selector and script for a `<workspace>:<script>` task of a
+/// `package.json` with workspaces, or `None` for root scripts.
+pub fn package_workspace_script(path: &Path, task: &str) -> Option<(String, String)> {
+    package_json::workspace_script(path, task)
+}
+
+/// Returns a mask command's arguments and `**OPTIONS**` flags from `mask --introspect` output.
+pub fn mask_params(output: &str, task: &str) -> Option<Vec<TaskParam>> {
+    mask::params(output, task)
+}
+
+/// Returns variables a Makefile target's recipe uses without a fixed value, with `?=` defaults.
+pub fn makefile_recipe_variables(path: &Path, task: &str) -> Vec<(String, Option<String>)> {
+    makefile::recipe_variables(path, task)
+}
+
+/// Returns whether the runner resolves tasks missing from `path` in parent directories itself.
+pub fn falls_back_to_parent(runner: Runner, path: &Path) -> bool {
+    match runner {
+        Runner::Justfile => justfile::fallback_enabled(path),
+        _ => false,
+    }
+}
+
+/// A recipe's source as written, for showing with `--explain`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeSource {
+    pub file: PathBuf,
+    /// 1-based line of the first attribute or header line.
+    pub line: usize,
+    /// Attribute, header and body lines, verbatim.
+    pub lines: Vec<String>,
+    /// Interpreter named by a shebang or `[script(...)]` attribute; `None` for shell recipes.
+    pub interpreter: Option<String>,
+}
+
+/// Returns the source of `task`'s recipe, or `None` when the runner's recipes can't be read.
+pub fn recipe_source(runner: Runner, path: &Path, task: &str) -> Option<RecipeSource> {
+    match runner {
+        Runner::Justfile => justfile::recipe_source(path, task),
+        _ => None,
+    }
+}
+
+/// Returns the dotenv file the runner loads into task environments itself, if it loads one.
+pub fn dotenv_file(runner: Runner, path: &Path) -> Option<PathBuf> {
+    match runner {
+        Runner::Justfile => justfile::dotenv_file(path),
+        _ => None,
+    }
+}
+
+/// Returns variables the runner exports to every task's environment, in file order.
+pub fn exported_variables(runner: Runner, path: &Path) -> Vec<(String, String)> {
+    match runner {
+        Runner::Justfile => justfile::exported_variables(path),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns each task with the tasks it depends on, or `None` when the runner has no dependency data.
+pub fn dependencies(runner: Runner, path: &Path) -> Option<Vec<(String, Vec<String>)>> {
+    match runner {
+        Runner::Justfile => Some(justfile::dependencies(path)),
+        Runner::Makefile => Some(makefile::dependencies(path)),
+        _ => None,
+    }
+}
+
+/// Returns the command line declared for the `task` process in a Procfile.
+pub fn procfile_command(content: &str, task: &str) -> Option<String> {
+    procfile::command(content, task)
+}
@@ -1,13 +1,33 @@
 use crate::tasks::TaskItem;
 
+/// The category cargo-make lists tasks without one under.
+const NO_CATEGORY: &str = "No Category";
+
+/// Parses `cargo make --list-all-steps`, using each category heading as the tasks' group.
+///
+/// cargo-make underlines headings with dashes; a lone `Category:` line counts too. A task line
+/// ending in `:` isn't a heading, since its name and description are separated by ` - ` or by
+/// several spaces.
 pub(super) fn parse(output: &str) -> Vec<TaskItem> {
     let mut items = Vec::new();
-    for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() {
+    let mut category: Option<String> = None;
+    let mut lines = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .peekable();
+    while let Some(line) = lines.next() {
+        if line.starts_with("Available") || line.starts_with("Tasks") || is_underline(line) {
             continue;
         }
-        if line.ends_with(':') || line.starts_with("Available") || line.starts_with("Tasks") {
+        let heading = if lines.next_if(|next| is_underline(next)).is_some() {
+            Some(line)
+        } else {
+            line.strip_suffix(':')
+                .filter(|heading| !has_description_separator(heading))
+        };
+        if let Some(heading) = heading {
+            category = (heading != NO_CATEGORY).then(|| heading.to_string());
             continue;
         }
 
@@ -19,18 +39,27 @@ pub(super) fn parse(output: &str) -> Vec<TaskItem> {
 
         let description = parts
             .next()
-            .map(str::trim)
+            .map(|d| d.trim().trim_start_matches("- ").trim())
             .filter(|d| !d.is_empty())
             .map(|d| d.to_string());
 
         items.push(TaskItem {
             name: name.to_string(),
             description,
+            group: category.clone(),
         });
     }
     items
 }
 
+fn is_underline(line: &str) -> bool {
+    line.len() >= 3 && line.chars().all(|c| c == '-')
+}
+
+fn has_description_separator(line: &str) -> bool {
+    line.contains(" - ") || line.contains("  ") || line.contains('\t')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,5 +75,47 @@ test         Run tests
         assert_eq!(tasks.len(), 2);
         assert_eq!(tasks[0].name, "build");
         assert_eq!(tasks[0].description.as_deref(), Some("Build the project"));
+        assert_eq!(tasks[0].group, None);
+    }
+
+    #[test]
+    fn parse_cargo_make_categories_as_groups() {
+        let output = "\
+Build
+----------
+build - Runs the rust compiler.
+release - Builds, then asks where to upload:
+
+No Category
+----------
+empty - Empty Task
+
+Test:
+test  Runs all tests, in order:
+";
+        let tasks = parse(output);
+        let grouped: Vec<(&str, Option<&str>, Option<&str>)> = tasks
+            .iter()
+            .map(|t| {
+                (
+                    t.name.as_str(),
+                    t.description.as_deref(),
+                    t.group.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            grouped,
+            vec![
+                ("build", Some("Runs the rust compiler."), Some("Build")),
+                (
+                    "release",
+                    Some("Builds, then asks where to upload:"),
+                    Some("Build")
+                ),
+                ("empty", Some("Empty Task"), None),
+                ("test", Some("Runs all tests, in order:"), Some("Test")),
+            ]
+        );
     }
 }
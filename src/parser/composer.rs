@@ -0,0 +1,101 @@
+use crate::tasks::TaskItem;
+
+/// Event scripts composer runs itself around its commands, rather than tasks to pick.
+const EVENT_SCRIPTS: [&str; 22] = [
+    "pre-install-cmd",
+    "post-install-cmd",
+    "pre-update-cmd",
+    "post-update-cmd",
+    "post-status-cmd",
+    "pre-archive-cmd",
+    "post-archive-cmd",
+    "pre-autoload-dump",
+    "post-autoload-dump",
+    "post-root-package-install",
+    "post-create-project-cmd",
+    "pre-operations-exec",
+    "pre-package-install",
+    "post-package-install",
+    "pre-package-update",
+    "post-package-update",
+    "pre-package-uninstall",
+    "post-package-uninstall",
+    "init",
+    "command",
+    "pre-file-download",
+    "pre-command-run",
+];
+
+/// Parses the `scripts` object of a `composer.json`, leaving out composer's event scripts.
+///
+/// A script is a command string or an array of them. The description comes from
+/// `scripts-descriptions`, falling back to the commands.
+pub(super) fn parse(content: &str) -> Vec<TaskItem> {
+    let Ok(config) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(scripts) = config
+        .get("scripts")
+        .and_then(|scripts| scripts.as_object())
+    else {
+        return Vec::new();
+    };
+    let descriptions = config.get("scripts-descriptions");
+
+    scripts
+        .iter()
+        .filter(|(name, _)| !EVENT_SCRIPTS.contains(&name.as_str()))
+        .filter_map(|(name, script)| {
+            let commands = match script {
+                serde_json::Value::String(command) => command.clone(),
+                serde_json::Value::Array(commands) => commands
+                    .iter()
+                    .filter_map(|command| command.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                _ => return None,
+            };
+            let description = descriptions
+                .and_then(|descriptions| descriptions.get(name))
+                .and_then(|description| description.as_str())
+                .map_or(commands, str::to_string);
+            Some(TaskItem {
+                name: name.clone(),
+                description: Some(description),
+                group: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_composer_scripts() {
+        let content = r#"{
+  "scripts": {
+    "test": "phpunit",
+    "lint": ["phpcs src", "phpstan analyse"],
+    "post-install-cmd": "php artisan optimize",
+    "broken": 42
+  },
+  "scripts-descriptions": {
+    "test": "Run the test suite"
+  }
+}"#;
+        let tasks = parse(content);
+        let summary: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.description.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("lint", Some("phpcs src; phpstan analyse")),
+                ("test", Some("Run the test suite")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::tasks::TaskItem;
+
+/// Parses the `tasks` object of a `deno.json` or `deno.jsonc`.
+///
+/// Tasks are either a command string or an object with `command` and an optional `description`;
+/// the description falls back to the command.
+pub(super) fn parse(content: &str) -> Vec<TaskItem> {
+    let Ok(config) = serde_json::from_str::<serde_json::Value>(&strip_jsonc(content)) else {
+        return Vec::new();
+    };
+    let Some(tasks) = config.get("tasks").and_then(|tasks| tasks.as_object()) else {
+        return Vec::new();
+    };
+
+    tasks
+        .iter()
+        .filter_map(|(name, task)| {
+            let description = match task {
+                serde_json::Value::String(command) => Some(command.clone()),
+                serde_json::Value::Object(fields) => fields
+                    .get("description")
+                    .or_else(|| fields.get("command"))
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string),
+                _ => return None,
+            };
+            Some(TaskItem {
+                name: name.clone(),
+                description,
+                group: None,
+            })
+        })
+        .collect()
+}
+
+/// Removes `//` and `/* */` comments and trailing commas, which JSONC allows and JSON doesn't.
+fn strip_jsonc(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(ch) = chars.next() {
+        if in_string {
+            stripped.push(ch);
+            match ch {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (ch, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(ch);
+            }
+            ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+                // Keep tokens on either side of the comment apart.
+                stripped.push(' ');
+            }
+            _ => stripped.push(ch),
+        }
+    }
+    remove_trailing_commas(&stripped)
+}
+
+fn remove_trailing_commas(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut output = String::with_capacity(content.len());
+    let mut in_string = false;
+    let mut idx = 0;
+    while idx < chars.len() {
+        let ch = chars[idx];
+        if in_string {
+            output.push(ch);
+            if ch == '\\' {
+                if let Some(&escaped) = chars.get(idx + 1) {
+                    output.push(escaped);
+                    idx += 1;
+                }
+            } else if ch == '"' {
+                in_string = false;
+            }
+        } else if ch == '"' {
+            in_string = true;
+            output.push(ch);
+        } else if ch == ',' {
+            let next = chars[idx + 1..].iter().find(|next| !next.is_whitespace());
+            if !matches!(next, Some('}' | ']')) {
+                output.push(ch);
+            }
+        } else {
+            output.push(ch);
+        }
+        idx += 1;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(tasks: &[TaskItem]) -> Vec<(&str, Option<&str>)> {
+        tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.description.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_reads_string_and_object_tasks() {
+        let content = r#"{
+  "tasks": {
+    "dev": "deno run --watch main.ts",
+    "check": { "command": "deno check main.ts", "description": "Type-check the app" },
+    "fmt": { "command": "deno fmt" },
+    "broken": 1
+  }
+}"#;
+        assert_eq!(
+            summary(&parse(content)),
+            vec![
+                ("check", Some("Type-check the app")),
+                ("dev", Some("deno run --watch main.ts")),
+                ("fmt", Some("deno fmt")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_accepts_jsonc_comments_and_trailing_commas() {
+        let content = r#"{
+  // Tasks run with `deno task <name>`.
+  "tasks": {
+    /* the server */ "serve": "deno run -A https://example.com/server.ts", // not a comment inside
+    "lint": "deno lint /* kept */",
+  },
+}"#;
+        assert_eq!(
+            summary(&parse(content)),
+            vec![
+                ("lint", Some("deno lint /* kept */")),
+                ("serve", Some("deno run -A https://example.com/server.ts")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_without_tasks_is_empty() {
+        assert!(parse(r#"{"imports": {}}"#).is_empty());
+        assert!(parse("not json").is_empty());
+    }
+}
@@ -0,0 +1,42 @@
+use crate::tasks::TaskItem;
+
+/// Parses `earthly ls` output, one target per line, such as `+build`.
+///
+/// The leading `+` is dropped from names; exec adds it back when running. Lines listing a target's
+/// arguments (`earthly ls -a`) keep only the target.
+pub(super) fn parse(output: &str) -> Vec<TaskItem> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let target = line.split_whitespace().next()?;
+            let name = target.strip_prefix('+').unwrap_or(target);
+            let is_target = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            is_target.then(|| TaskItem {
+                name: name.to_string(),
+                description: None,
+                group: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_earthly_targets_with_or_without_plus() {
+        let output = "\
++base
++build --VERSION
++test
+
+docker
+";
+        let names: Vec<String> = parse(output).into_iter().map(|task| task.name).collect();
+        assert_eq!(names, vec!["base", "build", "test", "docker"]);
+    }
+}
@@ -1,7 +1,31 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::RecipeSource;
+use crate::task_args::{
+    find_top_level_colon, is_valid_identifier, literal_default, split_top_level_whitespace,
+};
 use crate::tasks::TaskItem;
 
-pub(super) fn parse(output: &str) -> Vec<TaskItem> {
+/// Parses `just --list` output, then adds the aliases declared in `path` and its imports.
+///
+/// Private recipes and aliases are left out, as `just --list` does.
+pub(super) fn parse(output: &str, path: &Path) -> Vec<TaskItem> {
+    let private = private_names(path);
+    let items = parse_listing(output)
+        .into_iter()
+        .filter(|item| !private.contains(&item.name))
+        .collect();
+    let aliases: Vec<(String, String)> = aliases(path)
+        .into_iter()
+        .filter(|(alias, target)| !private.contains(alias) && !private.contains(target))
+        .collect();
+    with_aliases(items, &aliases)
+}
+
+fn parse_listing(output: &str) -> Vec<TaskItem> {
     let mut items = Vec::new();
+    let mut group = None;
     for line in output.lines() {
         let line = line.trim();
         if line.is_empty() {
@@ -10,29 +34,487 @@ pub(super) fn parse(output: &str) -> Vec<TaskItem> {
         if line.starts_with("Available") || line.starts_with("Recipes") {
             continue;
         }
+        // `just --list` puts recipes with a `[group]` attribute under `[name]` headings.
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            group = Some(name.to_string());
+            continue;
+        }
 
         let (left, desc) = match line.split_once('#') {
             Some((left, desc)) => (left.trim(), Some(desc.trim())),
             None => (line, None),
         };
 
-        let name = left.split_whitespace().next().unwrap_or("").trim();
+        // The quiet `@` prefix only affects echoing; `just` runs the recipe by its bare name.
+        let name = left
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .trim_start_matches('@');
         if name.is_empty() {
             continue;
         }
 
-        let description = desc.filter(|d| !d.is_empty()).map(|d| d.to_string());
+        let description = desc
+            .map(strip_alias_note)
+            .filter(|d| !d.is_empty())
+            .map(|d| d.to_string());
         items.push(TaskItem {
             name: name.to_string(),
             description,
+            group: group.clone(),
         });
     }
     items
 }
 
+/// Removes the `[alias: b]` / `[aliases: b, bb]` note just appends to a recipe's comment.
+fn strip_alias_note(description: &str) -> &str {
+    match description.rfind('[') {
+        Some(start)
+            if description.ends_with(']')
+                && (description[start..].starts_with("[alias: ")
+                    || description[start..].starts_with("[aliases: ")) =>
+        {
+            description[..start].trim_end()
+        }
+        _ => description,
+    }
+}
+
+/// Inserts an `alias for <target>` task after each aliased recipe, in the recipe's group.
+///
+/// Aliases of recipes missing from the list (e.g. in modules) go last; names already listed are
+/// left alone.
+fn with_aliases(items: Vec<TaskItem>, aliases: &[(String, String)]) -> Vec<TaskItem> {
+    let listed: HashSet<String> = items.iter().map(|item| item.name.clone()).collect();
+    let alias_item = |alias: &str, target: &str, group: Option<String>| TaskItem {
+        name: alias.to_string(),
+        description: Some(format!("alias for {target}")),
+        group,
+    };
+    let mut result = Vec::with_capacity(items.len() + aliases.len());
+    for item in items {
+        let added: Vec<TaskItem> = aliases
+            .iter()
+            .filter(|(alias, target)| *target == item.name && !listed.contains(alias))
+            .map(|(alias, target)| alias_item(alias, target, item.group.clone()))
+            .collect();
+        result.push(item);
+        result.extend(added);
+    }
+    for (alias, target) in aliases {
+        if !listed.contains(target) && !listed.contains(alias) {
+            result.push(alias_item(alias, target, None));
+        }
+    }
+    result
+}
+
+/// Returns recipe and alias names marked `[private]` or starting with `_`, following imports.
+///
+/// `[private]` may share a line with other attributes or sit among stacked attribute lines.
+fn private_names(path: &Path) -> HashSet<String> {
+    let mut private = HashSet::new();
+    for (_, content) in read_with_imports(path) {
+        let mut marked = false;
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                marked |= split_attributes(trimmed)
+                    .iter()
+                    .any(|(name, _)| *name == "private");
+                continue;
+            }
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let name =
+                parse_recipe_line(line).or_else(|| parse_alias_line(line).map(|(alias, _)| alias));
+            if let Some(name) = name
+                && (marked || name.starts_with('_'))
+            {
+                private.insert(name.to_string());
+            }
+            marked = false;
+        }
+    }
+    private
+}
+
+/// Returns `(alias, target)` for each `alias name := recipe` in the justfile and its imports.
+fn aliases(path: &Path) -> Vec<(String, String)> {
+    read_with_imports(path)
+        .iter()
+        .flat_map(|(_, content)| {
+            content
+                .lines()
+                .filter_map(parse_alias_line)
+                .map(|(alias, target)| (alias.to_string(), target.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns the alias and target of an `alias name := recipe` line.
+fn parse_alias_line(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix("alias")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let (name, target) = rest.split_once(":=")?;
+    let (name, target) = (name.trim(), target.trim());
+    (is_valid_identifier(name) && !target.is_empty()).then_some((name, target))
+}
+
+/// Returns the file and 1-based line of the recipe header for `task`, following imports.
+pub(super) fn locate_recipe(path: &Path, task: &str) -> Option<(PathBuf, usize)> {
+    read_with_imports(path)
+        .into_iter()
+        .find_map(|(file, content)| {
+            content
+                .lines()
+                .position(|line| parse_recipe_line(line) == Some(task))
+                .map(|idx| (file, idx + 1))
+        })
+}
+
+/// Returns recipe, alias and module names declared in the justfile and its imports.
+pub(super) fn declared_tasks(path: &Path) -> Vec<String> {
+    read_with_imports(path)
+        .iter()
+        .flat_map(|(_, content)| {
+            content
+                .lines()
+                .filter_map(|line| parse_recipe_line(line).or_else(|| parse_alias_or_module(line)))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns the name declared by `alias name := recipe` or `mod name` / `mod? name 'path'`.
+fn parse_alias_or_module(line: &str) -> Option<&str> {
+    if let Some((alias, _)) = parse_alias_line(line) {
+        return Some(alias);
+    }
+    let mut parts = line.split_whitespace();
+    let name = match parts.next()? {
+        "mod" | "mod?" => parts.next()?,
+        _ => return None,
+    };
+    is_valid_identifier(name).then_some(name)
+}
+
+/// Returns the recipe for `task` with its attributes and body lines untouched, following imports.
+pub(super) fn recipe_source(path: &Path, task: &str) -> Option<RecipeSource> {
+    read_with_imports(path)
+        .into_iter()
+        .find_map(|(file, content)| {
+            let lines: Vec<&str> = content.lines().collect();
+            let header = lines
+                .iter()
+                .position(|line| parse_recipe_line(line) == Some(task))?;
+            let start = lines[..header]
+                .iter()
+                .rposition(|line| !line.trim_start().starts_with('['))
+                .map_or(0, |idx| idx + 1);
+            let body_len = lines[header + 1..]
+                .iter()
+                .take_while(|line| line.trim().is_empty() || line.starts_with([' ', '\t']))
+                .count();
+            let body_end = lines[header + 1..header + 1 + body_len]
+                .iter()
+                .rposition(|line| !line.trim().is_empty())
+                .map_or(header + 1, |idx| header + 2 + idx);
+
+            let attributes: Vec<&str> = lines[start..header].to_vec();
+            let body = &lines[header + 1..body_end];
+            Some(RecipeSource {
+                file,
+                line: start + 1,
+                lines: lines[start..body_end]
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect(),
+                interpreter: interpreter(&attributes, body),
+            })
+        })
+}
+
+/// Returns the interpreter of a non-shell recipe: its shebang, or a `[script]` attribute.
+fn interpreter(attributes: &[&str], body: &[&str]) -> Option<String> {
+    let shebang = body
+        .iter()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .and_then(|line| line.strip_prefix("#!"));
+    if let Some(shebang) = shebang {
+        let command = shebang.trim();
+        let command = command
+            .strip_prefix("/usr/bin/env")
+            .map(|rest| rest.trim_start().trim_start_matches("-S").trim())
+            .unwrap_or(command);
+        return Some(command.to_string());
+    }
+
+    attributes
+        .iter()
+        .flat_map(|line| split_attributes(line))
+        .find_map(|(name, args)| match name {
+            "script" if args.is_empty() => {
+                Some("script-interpreter (default `sh -eu`)".to_string())
+            }
+            "script" => Some(args.join(" ")),
+            _ => None,
+        })
+}
+
+/// Splits an attribute line like `[private, script('python3')]` into names and string arguments.
+fn split_attributes(line: &str) -> Vec<(&str, Vec<String>)> {
+    let Some(inner) = line
+        .trim()
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    else {
+        return Vec::new();
+    };
+
+    let mut attributes = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+    for (idx, ch) in inner.char_indices() {
+        match (quote, ch) {
+            (Some(open), _) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(ch),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                attributes.push(&inner[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    attributes.push(&inner[start..]);
+
+    attributes
+        .into_iter()
+        .map(str::trim)
+        .filter(|attribute| !attribute.is_empty())
+        .map(|attribute| match attribute.split_once('(') {
+            Some((name, rest)) => {
+                let args = rest
+                    .trim_end_matches(')')
+                    .split(',')
+                    .map(|arg| arg.trim().trim_matches(['\'', '"']).to_string())
+                    .filter(|arg| !arg.is_empty())
+                    .collect();
+                (name.trim(), args)
+            }
+            None => (attribute, Vec::new()),
+        })
+        .collect()
+}
+
+/// Returns the `.env` file just loads for recipes, per the `dotenv-*` settings.
+pub(super) fn dotenv_file(path: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let mut load = false;
+    let mut filename = None;
+    let mut dotenv_path = None;
+    for (name, value) in content.lines().filter_map(parse_setting_line) {
+        match name {
+            "dotenv-load" => load = value.is_none_or(|value| value == "true"),
+            "dotenv-filename" => filename = value,
+            "dotenv-path" => dotenv_path = value,
+            _ => {}
+        }
+    }
+
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    match (dotenv_path, filename) {
+        (Some(dotenv_path), _) => Some(dir.join(dotenv_path)),
+        (None, Some(filename)) => Some(dir.join(filename)),
+        (None, None) if load => Some(dir.join(".env")),
+        (None, None) => None,
+    }
+}
+
+/// Returns whether `set fallback` lets just look up unknown recipes in parent directories.
+pub(super) fn fallback_enabled(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| {
+        content
+            .lines()
+            .filter_map(parse_setting_line)
+            .any(|(name, value)| name == "fallback" && value.is_none_or(|value| value == "true"))
+    })
+}
+
+/// Returns variables just exports to recipes: `export NAME := ...` lines, or every top-level
+/// assignment under `set export`, following imports.
+///
+/// Only plain string values are returned; expressions, backticks and interpolation are skipped.
+pub(super) fn exported_variables(path: &Path) -> Vec<(String, String)> {
+    let sources = read_with_imports(path);
+    let lines = || sources.iter().flat_map(|(_, content)| content.lines());
+    let export_all = lines()
+        .filter_map(parse_setting_line)
+        .any(|(name, value)| name == "export" && value.is_none_or(|value| value == "true"));
+
+    lines()
+        .filter_map(parse_assignment_line)
+        .filter(|(exported, _, _)| export_all || *exported)
+        .filter_map(|(_, name, value)| Some((name.to_string(), literal_default(value)?)))
+        .collect()
+}
+
+/// Parses a top-level `[export] NAME := value` line into (exported, name, raw value).
+fn parse_assignment_line(line: &str) -> Option<(bool, &str, &str)> {
+    if line.starts_with([' ', '\t']) {
+        return None;
+    }
+    let (exported, rest) = match line.strip_prefix("export ") {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (name, value) = rest.split_once(":=")?;
+    let name = name.trim();
+    if !is_valid_identifier(name) || matches!(name.split_whitespace().next(), Some("set" | "alias"))
+    {
+        return None;
+    }
+    Some((exported, name, value.trim()))
+}
+
+/// Parses `set name` / `set name := value`, unquoting string values.
+fn parse_setting_line(line: &str) -> Option<(&str, Option<&str>)> {
+    let rest = line.strip_prefix("set")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    match rest.split_once(":=") {
+        Some((name, value)) => {
+            let value = value.trim();
+            let value = value
+                .strip_prefix(['\'', '"'])
+                .and_then(|inner| inner.strip_suffix(['\'', '"']))
+                .unwrap_or(value);
+            Some((name.trim(), Some(value)))
+        }
+        None => Some((rest.trim(), None)),
+    }
+}
+
+/// Returns each recipe with the recipes it depends on, following imports.
+pub(super) fn dependencies(path: &Path) -> Vec<(String, Vec<String>)> {
+    read_with_imports(path)
+        .iter()
+        .flat_map(|(_, content)| content.lines().filter_map(parse_recipe_dependencies))
+        .collect()
+}
+
+fn parse_recipe_dependencies(line: &str) -> Option<(String, Vec<String>)> {
+    let name = parse_recipe_line(line)?;
+    let trimmed = line.trim();
+    let header_end = find_top_level_colon(trimmed)?;
+    let deps = split_top_level_whitespace(&trimmed[header_end + 1..])
+        .into_iter()
+        .filter_map(|token| {
+            let token = token.trim_start_matches('(').trim_end_matches(')');
+            let dep = token.split_whitespace().next()?;
+            is_valid_identifier(dep).then(|| dep.to_string())
+        })
+        .collect();
+    Some((name.to_string(), deps))
+}
+
+/// Reads the justfile and every file it imports, root first, skipping unreadable imports.
+fn read_with_imports(path: &Path) -> Vec<(PathBuf, String)> {
+    let mut visited = HashSet::new();
+    let mut sources = Vec::new();
+    collect_sources(path, &mut visited, &mut sources);
+    sources
+}
+
+fn collect_sources(
+    path: &Path,
+    visited: &mut HashSet<PathBuf>,
+    sources: &mut Vec<(PathBuf, String)>,
+) {
+    let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(key) {
+        return;
+    }
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    let imports: Vec<String> = content
+        .lines()
+        .filter_map(parse_import_line)
+        .map(str::to_string)
+        .collect();
+    sources.push((path.to_path_buf(), content));
+
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+    for import in imports {
+        collect_sources(&base.join(import), visited, sources);
+    }
+}
+
+/// Returns the recipe name declared by a justfile header line, without the quiet `@` prefix.
+pub(super) fn parse_recipe_line(line: &str) -> Option<&str> {
+    if line.starts_with(' ') || line.starts_with('\t') {
+        return None;
+    }
+
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('[') {
+        return None;
+    }
+
+    let header_end = find_top_level_colon(trimmed)?;
+    if trimmed[header_end..].starts_with(":=") {
+        return None;
+    }
+
+    let left = trimmed[..header_end].trim();
+    let name = split_top_level_whitespace(left)
+        .first()?
+        .trim_start_matches('@');
+    if is_valid_identifier(name) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// Returns the path of an `import 'path'` / `import? 'path'` statement.
+fn parse_import_line(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix("import")?;
+    let rest = rest.strip_prefix('?').unwrap_or(rest);
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let rest = rest.trim();
+    let quote = rest.chars().next().filter(|c| matches!(c, '\'' | '"'))?;
+    let inner = &rest[1..];
+    inner.find(quote).map(|end| &inner[..end])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::tempdir;
 
     #[test]
     fn parse_just_list() {
@@ -41,11 +523,382 @@ Available recipes:
     build  # build project
     test
 ";
-        let tasks = parse(output);
+        let tasks = parse(output, Path::new("justfile"));
         assert_eq!(tasks.len(), 2);
         assert_eq!(tasks[0].name, "build");
         assert_eq!(tasks[0].description.as_deref(), Some("build project"));
         assert_eq!(tasks[1].name, "test");
         assert_eq!(tasks[1].description, None);
     }
+
+    #[test]
+    fn parse_assigns_group_headings_to_following_recipes() {
+        let output = "\
+Available recipes:
+    default
+    [lint]
+    clippy # run clippy
+    fmt
+    [test]
+    test
+";
+        let tasks = parse(output, Path::new("justfile"));
+        let summary: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.group.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("default", None),
+                ("clippy", Some("lint")),
+                ("fmt", Some("lint")),
+                ("test", Some("test")),
+            ]
+        );
+        assert_eq!(tasks[1].description.as_deref(), Some("run clippy"));
+    }
+
+    #[test]
+    fn parse_strips_quiet_prefix_from_names() {
+        let tasks = parse(
+            "Available recipes:\n    @build # quiet build\n    test\n",
+            Path::new("justfile"),
+        );
+        assert_eq!(tasks[0].name, "build");
+        assert_eq!(tasks[0].description.as_deref(), Some("quiet build"));
+        assert_eq!(tasks[1].name, "test");
+    }
+
+    #[test]
+    fn quiet_recipes_are_located_and_declared_without_prefix() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(
+            &path,
+            "@build:\n  cargo build\n\n@test FILTER='':\n  cargo test\n",
+        )
+        .unwrap();
+
+        assert_eq!(declared_tasks(&path), vec!["build", "test"]);
+        assert_eq!(locate_recipe(&path, "test"), Some((path.clone(), 4)));
+        assert_eq!(locate_recipe(&path, "@test"), None);
+    }
+
+    #[test]
+    fn parse_recipe_line_extracts_recipe_names() {
+        assert_eq!(parse_recipe_line("build:"), Some("build"));
+        assert_eq!(
+            parse_recipe_line("@test TEST ENV='a:b': build"),
+            Some("test")
+        );
+        assert_eq!(parse_recipe_line("foo := 'bar'"), None);
+        assert_eq!(parse_recipe_line("alias b := build"), None);
+        assert_eq!(parse_recipe_line("  echo: nested"), None);
+        assert_eq!(parse_recipe_line("[private]"), None);
+        assert_eq!(parse_recipe_line("# build:"), None);
+    }
+
+    #[test]
+    fn recipe_source_keeps_python_shebang_body_verbatim() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        let recipe = "[group('data')]\nreport DAYS='7':\n    #!/usr/bin/env python3\n    import sys\n    for day in range({{DAYS}}):\n        print(f\"day {day}\")\n";
+        std::fs::write(
+            &path,
+            format!("build:\n  cargo build\n\n{recipe}\n\ntest:\n  cargo test\n"),
+        )
+        .unwrap();
+
+        let source = recipe_source(&path, "report").unwrap();
+        assert_eq!(source.file, path);
+        assert_eq!(source.line, 4);
+        assert_eq!(source.lines.join("\n") + "\n", recipe);
+        assert_eq!(source.interpreter.as_deref(), Some("python3"));
+    }
+
+    #[test]
+    fn recipe_source_reads_script_attribute_interpreter() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(
+            &path,
+            "[private, script('uv', 'run')]\n[extension('.py')]\nseed:\n  print('seed')\n\nshell:\n  echo hi\n",
+        )
+        .unwrap();
+
+        let seed = recipe_source(&path, "seed").unwrap();
+        assert_eq!(seed.line, 1);
+        assert_eq!(seed.lines.len(), 4);
+        assert_eq!(seed.interpreter.as_deref(), Some("uv run"));
+        assert_eq!(recipe_source(&path, "shell").unwrap().interpreter, None);
+    }
+
+    #[test]
+    fn dotenv_file_follows_dotenv_settings() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+
+        std::fs::write(&path, "set dotenv-load\n\nbuild:\n  echo build\n").unwrap();
+        assert_eq!(dotenv_file(&path), Some(dir.path().join(".env")));
+
+        std::fs::write(&path, "set dotenv-filename := '.env.local'\n").unwrap();
+        assert_eq!(dotenv_file(&path), Some(dir.path().join(".env.local")));
+
+        std::fs::write(
+            &path,
+            "set dotenv-load := false\nset shell := ['bash', '-c']\n",
+        )
+        .unwrap();
+        assert_eq!(dotenv_file(&path), None);
+    }
+
+    #[test]
+    fn parse_lists_aliases_after_their_recipes_across_imports() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("justfile");
+        std::fs::write(
+            &root,
+            "import 'extra.just'\nalias b := build\nalias d := docs::build\n\nbuild:\n  cargo build\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("extra.just"),
+            "alias l := lint\n\n[group('ci')]\nlint:\n  cargo clippy\n",
+        )
+        .unwrap();
+        let output = "\
+Available recipes:
+    build # build project [alias: b]
+    [ci]
+    lint # [alias: l]
+";
+
+        let tasks = parse(output, &root);
+        let summary: Vec<(&str, Option<&str>, Option<&str>)> = tasks
+            .iter()
+            .map(|task| {
+                (
+                    task.name.as_str(),
+                    task.description.as_deref(),
+                    task.group.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("build", Some("build project"), None),
+                ("b", Some("alias for build"), None),
+                ("lint", None, Some("ci")),
+                ("l", Some("alias for lint"), Some("ci")),
+                ("d", Some("alias for docs::build"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_drops_private_recipes_and_their_aliases() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("justfile");
+        std::fs::write(
+            &root,
+            "\
+import 'extra.just'
+alias s := secret
+[private]
+alias hidden := build
+
+build:
+  cargo build
+
+# Not listed.
+[private]
+[no-cd]
+secret:
+  echo secret
+
+[no-cd, private]
+other:
+  true
+
+_helper:
+  true
+",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("extra.just"), "[private]\nlint:\n  true\n").unwrap();
+        // As if `just --list --list-private`-style output reached the parser.
+        let output = "Available recipes:\n    build\n    lint\n    other\n    _helper\n";
+
+        let names: Vec<String> = parse(output, &root)
+            .into_iter()
+            .map(|task| task.name)
+            .collect();
+        assert_eq!(names, vec!["build"]);
+        let private = private_names(&root);
+        for name in ["secret", "other", "_helper", "hidden", "lint"] {
+            assert!(private.contains(name), "{name}");
+        }
+        assert!(!private.contains("build"));
+        assert!(!private.contains("s"));
+    }
+
+    #[test]
+    fn parse_alias_line_reads_name_and_target() {
+        assert_eq!(parse_alias_line("alias b := build"), Some(("b", "build")));
+        assert_eq!(parse_alias_line("alias t:=test"), Some(("t", "test")));
+        assert_eq!(parse_alias_line("aliases := 'x'"), None);
+        assert_eq!(parse_alias_line("alias := build"), None);
+    }
+
+    #[test]
+    fn declared_tasks_includes_imports_aliases_and_modules() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("justfile");
+        std::fs::write(
+            &root,
+            "import 'extra.just'\nmod docs\nalias b := build\nversion := '1'\n\nbuild:\n  cargo build\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("extra.just"), "lint:\n  cargo clippy\n").unwrap();
+
+        assert_eq!(declared_tasks(&root), vec!["docs", "b", "build", "lint"]);
+    }
+
+    #[test]
+    fn exported_variables_follow_set_export() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(
+            &path,
+            "set export\nset shell := ['bash', '-c']\nalias b := build\n\nRUST_LOG := 'debug'\nVERSION := `git describe`\nGREETING := \"hi \" + RUST_LOG\nOUT := 'target' / 'out'\n\nbuild:\n  cargo build\n",
+        )
+        .unwrap();
+        assert_eq!(
+            exported_variables(&path),
+            vec![("RUST_LOG".to_string(), "debug".to_string())]
+        );
+    }
+
+    #[test]
+    fn exported_variables_follow_imports() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(
+            &path,
+            "set export\nimport 'env.just'\n\nRUST_LOG := 'debug'\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("env.just"),
+            "PROFILE := 'dev'\nexport DATABASE_URL := 'postgres://localhost'\n",
+        )
+        .unwrap();
+        assert_eq!(
+            exported_variables(&path),
+            vec![
+                ("RUST_LOG".to_string(), "debug".to_string()),
+                ("PROFILE".to_string(), "dev".to_string()),
+                (
+                    "DATABASE_URL".to_string(),
+                    "postgres://localhost".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn exported_variables_without_setting_only_include_export_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(
+            &path,
+            "export DATABASE_URL := 'postgres://localhost'\nPROFILE := 'dev'\n",
+        )
+        .unwrap();
+        assert_eq!(
+            exported_variables(&path),
+            vec![(
+                "DATABASE_URL".to_string(),
+                "postgres://localhost".to_string()
+            )]
+        );
+
+        std::fs::write(&path, "set export := false\nPROFILE := 'dev'\n").unwrap();
+        assert!(exported_variables(&path).is_empty());
+    }
+
+    #[test]
+    fn locate_recipe_finds_recipe_in_root_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(
+            &path,
+            "set shell := ['bash', '-c']\n\nbuild:\n  echo build\n\ntest:\n  echo test\n",
+        )
+        .unwrap();
+
+        assert_eq!(locate_recipe(&path, "test"), Some((path.clone(), 6)));
+        assert_eq!(locate_recipe(&path, "missing"), None);
+    }
+
+    #[test]
+    fn locate_recipe_follows_imports() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("justfile");
+        let nested = dir.path().join("just").join("release.just");
+        std::fs::create_dir_all(nested.parent().unwrap()).unwrap();
+        std::fs::write(
+            &root,
+            "import 'just/release.just'\nimport? 'missing.just'\n\nbuild:\n  echo build\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &nested,
+            "# publish a release\nrelease VERSION:\n  echo {{VERSION}}\n",
+        )
+        .unwrap();
+
+        assert_eq!(locate_recipe(&root, "release"), Some((nested, 2)));
+        assert_eq!(locate_recipe(&root, "build"), Some((root.clone(), 4)));
+    }
+
+    #[test]
+    fn dependencies_lists_recipe_prerequisites() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(
+            &path,
+            "fmt:\n  cargo fmt\n\nbuild: fmt\n  cargo build\n\ndeploy ENV='prod': build (notify ENV) && cleanup\n  ./deploy\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            dependencies(&path),
+            vec![
+                ("fmt".to_string(), vec![]),
+                ("build".to_string(), vec!["fmt".to_string()]),
+                (
+                    "deploy".to_string(),
+                    vec![
+                        "build".to_string(),
+                        "notify".to_string(),
+                        "cleanup".to_string()
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn locate_recipe_tolerates_import_cycles() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.just");
+        let b = dir.path().join("b.just");
+        std::fs::write(&a, "import 'b.just'\n").unwrap();
+        std::fs::write(&b, "import 'a.just'\n").unwrap();
+
+        assert_eq!(locate_recipe(&a, "build"), None);
+    }
 }
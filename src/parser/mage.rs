@@ -0,0 +1,72 @@
+use crate::tasks::TaskItem;
+
+/// Parses the `Targets:` section of `mage -l`, such as `  build*    builds the binary`.
+///
+/// Like cargo-make's list, each line is a name column and a description column. mage marks the
+/// default target with a trailing `*`, which is dropped from the name.
+pub(super) fn parse(output: &str) -> Vec<TaskItem> {
+    let mut items = Vec::new();
+    let mut in_targets = false;
+    for line in output.lines() {
+        if line.trim() == "Targets:" {
+            in_targets = true;
+            continue;
+        }
+        // The section ends with the footnote for `*` or the end of the indented lines.
+        if !in_targets || !line.starts_with(char::is_whitespace) {
+            in_targets &= line.trim().is_empty();
+            continue;
+        }
+
+        let mut parts = line.trim().splitn(2, char::is_whitespace);
+        let name = parts.next().unwrap_or("").trim_end_matches('*');
+        if name.is_empty() {
+            continue;
+        }
+
+        let description = parts
+            .next()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(|d| d.to_string());
+
+        items.push(TaskItem {
+            name: name.to_string(),
+            description,
+            group: None,
+        });
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mage_targets_section() {
+        let output = "\
+This is a magefile for building the app.
+
+Targets:
+  build*          builds the binary
+  docker:push     pushes the image
+  install
+
+* default target
+";
+        let tasks = parse(output);
+        let summary: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.description.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("build", Some("builds the binary")),
+                ("docker:push", Some("pushes the image")),
+                ("install", None),
+            ]
+        );
+    }
+}
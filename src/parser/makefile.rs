@@ -1,28 +1,70 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use crate::tasks::TaskItem;
 
-pub(super) fn parse(output: &str) -> Vec<TaskItem> {
-    let makefile_source = read_makefile_source_from_disk();
-    parse_with_makefile_source(output, makefile_source.as_deref())
+pub(super) fn parse(output: &str, path: &Path) -> Vec<TaskItem> {
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+    let descriptions = makefile_descriptions(path, base, &mut HashSet::new());
+    parse_with_descriptions(output, &descriptions)
 }
 
-fn parse_with_makefile_source(output: &str, makefile_source: Option<&str>) -> Vec<TaskItem> {
+/// Reads comment descriptions from the Makefile at `path` and the files it includes.
+///
+/// Like make run from there, every include is read relative to `base`, the Makefile's directory.
+/// Missing ones are skipped, whether `include` or `-include`, since only descriptions are wanted
+/// here. When both describe a target, the including file wins.
+fn makefile_descriptions(
+    path: &Path,
+    base: &Path,
+    visited: &mut HashSet<PathBuf>,
+) -> BTreeMap<String, String> {
+    let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(key) {
+        return BTreeMap::new();
+    }
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+
+    let mut descriptions = parse_makefile_descriptions(&content);
+    let includes = content
+        .lines()
+        .filter(|line| !line.starts_with('\t'))
+        .filter_map(parse_include_line)
+        .flatten();
+    for include in includes {
+        for (name, description) in makefile_descriptions(&base.join(include), base, visited) {
+            descriptions.entry(name).or_insert(description);
+        }
+    }
+    descriptions
+}
+
+fn parse_with_descriptions(output: &str, descriptions: &BTreeMap<String, String>) -> Vec<TaskItem> {
     let has_files_section = output
         .lines()
         .any(|line| line.trim_start().starts_with("# Files"));
     let mut in_files = !has_files_section;
     let mut tasks = BTreeMap::new();
     let mut pending_desc: Option<String> = None;
+    // make marks files it only read, such as included makefiles, with `# Not a target:`.
+    let mut not_a_target = false;
 
     for line in output.lines() {
         let line = line.trim_end();
         if line.is_empty() {
             pending_desc = None;
+            not_a_target = false;
             continue;
         }
 
         let trimmed = line.trim_start();
+        if trimmed.starts_with("# Not a target") {
+            not_a_target = true;
+            pending_desc = None;
+            continue;
+        }
         if trimmed.starts_with("# Files") {
             in_files = true;
             pending_desc = None;
@@ -51,20 +93,31 @@ fn parse_with_makefile_source(output: &str, makefile_source: Option<&str>) -> Ve
             continue;
         }
 
-        let (target, _) = match trimmed.split_once(':') {
-            Some(parts) => parts,
-            None => continue,
+        if !trimmed.contains(':') {
+            continue;
+        }
+        if not_a_target {
+            not_a_target = false;
+            pending_desc = None;
+            continue;
+        }
+        // `CC := gcc` and `CC ::= gcc` are assignments, which appear when parsing source directly.
+        let Some((targets, rest)) = split_rule(trimmed) else {
+            pending_desc = None;
+            continue;
         };
 
-        let name = target.trim();
-        if !is_make_target_name(name) {
+        let names: Vec<&str> = targets
+            .split_whitespace()
+            .filter(|name| is_make_target_name(name))
+            .collect();
+        if names.is_empty() {
             pending_desc = None;
             continue;
         }
 
-        let inline_desc = trimmed
-            .split_once(':')
-            .and_then(|(_, rest)| rest.split_once('#'))
+        let inline_desc = rest
+            .split_once('#')
             .map(|(_, comment)| comment.trim())
             .filter(|desc| !desc.is_empty())
             .map(str::to_string);
@@ -72,27 +125,30 @@ fn parse_with_makefile_source(output: &str, makefile_source: Option<&str>) -> Ve
         let description = inline_desc.or_else(|| pending_desc.take());
         pending_desc = None;
 
-        if description.is_some() {
-            tasks.insert(name.to_string(), description);
-        } else {
-            tasks.entry(name.to_string()).or_insert(None);
+        for name in names {
+            if description.is_some() {
+                tasks.insert(name.to_string(), description.clone());
+            } else {
+                tasks.entry(name.to_string()).or_insert(None);
+            }
         }
     }
 
-    if let Some(source) = makefile_source {
-        let descriptions = parse_makefile_descriptions(source);
-        for (name, description) in &mut tasks {
-            if description.is_none()
-                && let Some(source_desc) = descriptions.get(name)
-            {
-                *description = Some(source_desc.clone());
-            }
+    for (name, description) in &mut tasks {
+        if description.is_none()
+            && let Some(source_desc) = descriptions.get(name)
+        {
+            *description = Some(source_desc.clone());
         }
     }
 
     tasks
         .into_iter()
-        .map(|(name, description)| TaskItem { name, description })
+        .map(|(name, description)| TaskItem {
+            name,
+            description,
+            group: None,
+        })
         .collect()
 }
 
@@ -122,17 +178,6 @@ fn parse_comment_line(line: &str) -> Option<String> {
     Some(comment.to_string())
 }
 
-fn read_makefile_source_from_disk() -> Option<String> {
-    let cwd = std::env::current_dir().ok()?;
-    for name in ["Makefile", "makefile", "GNUmakefile"] {
-        let path = cwd.join(name);
-        if path.is_file() {
-            return std::fs::read_to_string(path).ok();
-        }
-    }
-    None
-}
-
 fn parse_makefile_descriptions(source: &str) -> BTreeMap<String, String> {
     let mut descriptions = BTreeMap::new();
     let mut pending_desc: Option<String> = None;
@@ -154,22 +199,13 @@ fn parse_makefile_descriptions(source: &str) -> BTreeMap<String, String> {
             continue;
         }
 
-        let (target, rest) = match trimmed.split_once(':') {
-            Some(parts) => parts,
-            None => {
-                pending_desc = None;
-                continue;
-            }
-        };
-
-        if rest.trim_start().starts_with('=') {
+        let Some((target, rest)) = split_rule(trimmed) else {
             pending_desc = None;
             continue;
-        }
+        };
 
         let target_names: Vec<&str> = target
             .split_whitespace()
-            .map(str::trim)
             .filter(|name| is_make_target_name(name))
             .collect();
 
@@ -178,9 +214,8 @@ fn parse_makefile_descriptions(source: &str) -> BTreeMap<String, String> {
             continue;
         }
 
-        let inline_desc = trimmed
-            .split_once(':')
-            .and_then(|(_, value)| value.split_once('#'))
+        let inline_desc = rest
+            .split_once('#')
             .map(|(_, comment)| comment.trim())
             .filter(|desc| !desc.is_empty())
             .map(str::to_string);
@@ -198,6 +233,362 @@ fn parse_makefile_descriptions(source: &str) -> BTreeMap<String, String> {
     descriptions
 }
 
+/// Returns the file and 1-based line of the rule defining `task`, following `include` directives.
+pub(super) fn locate_recipe(path: &Path, task: &str) -> Option<(PathBuf, usize)> {
+    let mut visited = HashSet::new();
+    locate_recipe_in(path, task, &mut visited)
+}
+
+fn locate_recipe_in(
+    path: &Path,
+    task: &str,
+    visited: &mut HashSet<PathBuf>,
+) -> Option<(PathBuf, usize)> {
+    let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(key) {
+        return None;
+    }
+
+    let content = std::fs::read_to_string(path).ok()?;
+    let mut includes = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        if let Some(paths) = parse_include_line(line) {
+            includes.extend(paths);
+            continue;
+        }
+        if rule_targets(line).any(|name| name == task) {
+            return Some((path.to_path_buf(), idx + 1));
+        }
+    }
+
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+    includes
+        .into_iter()
+        .find_map(|include| locate_recipe_in(&base.join(include), task, visited))
+}
+
+/// Returns each rule target with the prerequisites that are themselves targets, following includes.
+pub(super) fn dependencies(path: &Path) -> Vec<(String, Vec<String>)> {
+    let mut visited = HashSet::new();
+    let mut rules: Vec<(String, Vec<String>)> = Vec::new();
+    collect_rules(path, &mut visited, &mut rules);
+
+    let targets: HashSet<String> = rules.iter().map(|(name, _)| name.clone()).collect();
+    for (_, deps) in &mut rules {
+        deps.retain(|dep| targets.contains(dep));
+    }
+    rules
+}
+
+fn collect_rules(
+    path: &Path,
+    visited: &mut HashSet<PathBuf>,
+    rules: &mut Vec<(String, Vec<String>)>,
+) {
+    let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(key) {
+        return;
+    }
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    let base = path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf();
+    for line in content.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        if let Some(includes) = parse_include_line(line) {
+            for include in includes {
+                collect_rules(&base.join(include), visited, rules);
+            }
+            continue;
+        }
+
+        let prerequisites = rule_prerequisites(line);
+        for name in rule_targets(line) {
+            match rules.iter_mut().find(|(existing, _)| existing == name) {
+                Some((_, deps)) => {
+                    for dep in &prerequisites {
+                        if !deps.contains(dep) {
+                            deps.push(dep.clone());
+                        }
+                    }
+                }
+                None => rules.push((name.to_string(), prerequisites.clone())),
+            }
+        }
+    }
+}
+
+/// Variables make sets itself or that commonly come from the environment; never prompted for.
+const IMPLICIT_VARIABLES: [&str; 28] = [
+    "MAKE",
+    "MAKEFLAGS",
+    "MAKEFILE_LIST",
+    "MAKECMDGOALS",
+    "MAKELEVEL",
+    "CURDIR",
+    "SHELL",
+    "AR",
+    "ARFLAGS",
+    "AS",
+    "CC",
+    "CXX",
+    "CPP",
+    "FC",
+    "LD",
+    "RM",
+    "YACC",
+    "LEX",
+    "INSTALL",
+    "DESTDIR",
+    "CFLAGS",
+    "CXXFLAGS",
+    "CPPFLAGS",
+    "LDFLAGS",
+    "LDLIBS",
+    "HOME",
+    "PATH",
+    "PWD",
+];
+
+/// Returns the variables `task`'s recipe references that may need a value on the command line.
+///
+/// A variable that is never assigned, or only assigned with an empty `?=`, has no default
+/// (`None`); one with `VAR ?= value` defaults to `value`. Variables assigned any other way,
+/// including in included files, are left out, as are make's own and environment variables.
+pub(super) fn recipe_variables(path: &Path, task: &str) -> Vec<(String, Option<String>)> {
+    let mut visited = HashSet::new();
+    let mut scan = VariableScan::default();
+    scan_variables(path, task, &mut visited, &mut scan);
+
+    let mut variables = Vec::new();
+    for name in scan.referenced {
+        if IMPLICIT_VARIABLES.contains(&name.as_str())
+            || std::env::var_os(&name).is_some()
+            || variables.iter().any(|(existing, _)| *existing == name)
+        {
+            continue;
+        }
+        match scan.assigned.get(&name) {
+            None => variables.push((name, None)),
+            Some(Assignment::Conditional(value)) => {
+                let default = Some(value.clone()).filter(|value| !value.is_empty());
+                variables.push((name, default));
+            }
+            Some(Assignment::Fixed) => {}
+        }
+    }
+    variables
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Assignment {
+    /// `VAR ?= value`, which the command line or environment may still set.
+    Conditional(String),
+    /// `=`, `:=`, `::=`, `+=`, `!=` or `define`.
+    Fixed,
+}
+
+#[derive(Debug, Default)]
+struct VariableScan {
+    assigned: BTreeMap<String, Assignment>,
+    referenced: Vec<String>,
+}
+
+impl VariableScan {
+    fn assign(&mut self, name: String, assignment: Assignment) {
+        // A later `?=` doesn't override an earlier definition.
+        let keep_existing =
+            matches!(assignment, Assignment::Conditional(_)) && self.assigned.contains_key(&name);
+        if !keep_existing {
+            self.assigned.insert(name, assignment);
+        }
+    }
+}
+
+fn scan_variables(
+    path: &Path,
+    task: &str,
+    visited: &mut HashSet<PathBuf>,
+    scan: &mut VariableScan,
+) {
+    let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(key) {
+        return;
+    }
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    let base = path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf();
+    let mut in_recipe = false;
+    for line in content.lines() {
+        if line.starts_with('\t') {
+            if in_recipe {
+                scan.referenced.extend(variable_references(line));
+            }
+            continue;
+        }
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        in_recipe = false;
+
+        if let Some(includes) = parse_include_line(line) {
+            for include in includes {
+                scan_variables(&base.join(include), task, visited, scan);
+            }
+            continue;
+        }
+        if let Some((name, assignment)) = parse_assignment(line) {
+            scan.assign(name, assignment);
+            continue;
+        }
+        // `task: VAR = value` defines `VAR` for that target only.
+        if let Some((targets, (name, assignment))) = target_assignment(line) {
+            if targets.split_whitespace().any(|target| target == task) {
+                scan.assign(name, assignment);
+            }
+            continue;
+        }
+        if rule_targets(line).any(|name| name == task) {
+            in_recipe = true;
+            // `target: ; command` puts the first recipe line on the rule line.
+            if let Some((_, command)) = line.split_once(';') {
+                scan.referenced.extend(variable_references(command));
+            }
+        }
+    }
+}
+
+/// Parses `VAR = value` and its variants, including `export`/`override` prefixes and `define`.
+fn parse_assignment(line: &str) -> Option<(String, Assignment)> {
+    let mut line = line.trim();
+    for prefix in ["export ", "override "] {
+        line = line.strip_prefix(prefix).unwrap_or(line).trim_start();
+    }
+    if let Some(name) = line.strip_prefix("define ") {
+        let name = name.split_whitespace().next()?;
+        return Some((name.to_string(), Assignment::Fixed));
+    }
+
+    let eq = line.find('=')?;
+    let (left, value) = (&line[..eq], line[eq + 1..].trim());
+    let (name, assignment) = if let Some(name) = left.strip_suffix('?') {
+        (name, Assignment::Conditional(value.to_string()))
+    } else {
+        let name = left.trim_end_matches([':', '+', '!']);
+        (name, Assignment::Fixed)
+    };
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-');
+    valid.then(|| (name.to_string(), assignment))
+}
+
+/// Parses a target-specific assignment, `targets: VAR = value`, into its targets and assignment.
+fn target_assignment(line: &str) -> Option<(&str, (String, Assignment))> {
+    let (targets, rest) = line.split_once(':')?;
+    if targets.contains('=') || rest.starts_with([':', '=']) {
+        return None;
+    }
+    Some((targets, parse_assignment(rest)?))
+}
+
+/// Returns names referenced as `$(VAR)` or `${VAR}`, skipping function calls like `$(shell ...)`.
+fn variable_references(line: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = line;
+    while let Some(idx) = rest.find('$') {
+        rest = &rest[idx + 1..];
+        let close = match rest.chars().next() {
+            Some('(') => ')',
+            Some('{') => '}',
+            // `$$` escapes a shell `$`; skip both so `$${HOME}` isn't read as a reference.
+            Some('$') => {
+                rest = &rest[1..];
+                continue;
+            }
+            _ => continue,
+        };
+        let Some(end) = rest.find(close) else {
+            break;
+        };
+        let name = &rest[1..end];
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            names.push(name.to_string());
+        }
+        rest = &rest[end..];
+    }
+    names
+}
+
+fn rule_prerequisites(line: &str) -> Vec<String> {
+    let Some((_, rest)) = split_rule(line) else {
+        return Vec::new();
+    };
+    let rest = rest.split(['#', ';']).next().unwrap_or("");
+    rest.split_whitespace()
+        .filter(|name| *name != "|" && is_make_target_name(name))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns the file names listed by an `include`, `-include`, or `sinclude` directive.
+fn parse_include_line(line: &str) -> Option<Vec<String>> {
+    let trimmed = line.trim_start();
+    let rest = ["include", "-include", "sinclude"]
+        .iter()
+        .find_map(|keyword| trimmed.strip_prefix(keyword))?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let rest = rest.split_once('#').map_or(rest, |(before, _)| before);
+    Some(
+        rest.split_whitespace()
+            .filter(|name| !name.contains('$'))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Returns the target names declared by a rule line, or nothing for non-rule lines.
+fn rule_targets(line: &str) -> impl Iterator<Item = &str> {
+    let targets = split_rule(line).map_or("", |(targets, _)| targets);
+    targets
+        .split_whitespace()
+        .filter(|name| is_make_target_name(name))
+}
+
+/// Splits a rule line into its target list and what follows the colon.
+///
+/// `all:: deps` (double-colon) and `a b &: deps` (grouped targets) declare their targets like
+/// `all: deps`; `CC := gcc`, `CC ::= gcc` and target-specific `install: PREFIX = /opt` are
+/// assignments and give `None`.
+fn split_rule(line: &str) -> Option<(&str, &str)> {
+    let (targets, rest) = line.split_once(':')?;
+    let rest = rest.trim_start_matches(':');
+    if targets.contains('=') || rest.starts_with('=') || target_assignment(line).is_some() {
+        return None;
+    }
+    let targets = targets.trim_end();
+    Some((targets.strip_suffix('&').unwrap_or(targets), rest))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,11 +602,42 @@ install:
 \t@echo install
 %.o: %.c
 ";
-        let tasks = parse(output);
+        let tasks = parse_with_descriptions(output, &BTreeMap::new());
         let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
         assert_eq!(names, vec!["all", "install"]);
     }
 
+    #[test]
+    fn parse_make_double_colon_and_multiple_target_rules() {
+        let output = "\
+# Run every check
+all:: x
+all:: y
+lint test check: deps
+docs html &: sources # Build the docs
+CC ::= gcc
+";
+        let tasks = parse_with_descriptions(output, &BTreeMap::new());
+        let described: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|t| (t.name.as_str(), t.description.as_deref()))
+            .collect();
+        assert_eq!(
+            described,
+            vec![
+                ("all", Some("Run every check")),
+                ("check", None),
+                ("docs", Some("Build the docs")),
+                ("html", Some("Build the docs")),
+                ("lint", None),
+                ("test", None),
+            ]
+        );
+        assert_eq!(rule_targets("all:: x").collect::<Vec<_>>(), vec!["all"]);
+        assert_eq!(rule_prerequisites("all:: x"), vec!["x".to_string()]);
+        assert_eq!(rule_targets("CC ::= gcc").count(), 0);
+    }
+
     #[test]
     fn parse_make_files_section() {
         let output = "\
@@ -224,9 +646,13 @@ all: deps build
 install:
 \t@echo install
 
+# Not a target:
+mk/release.mk:
+#  Implicit rule search has been done.
+
 # Finished Make data base
 ";
-        let tasks = parse(output);
+        let tasks = parse_with_descriptions(output, &BTreeMap::new());
         let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
         assert_eq!(names, vec!["all", "install"]);
     }
@@ -238,7 +664,7 @@ install:
 build:
 \tcc *.c -o main
 ";
-        let tasks = parse(output);
+        let tasks = parse_with_descriptions(output, &BTreeMap::new());
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].name, "build");
         assert_eq!(tasks[0].description.as_deref(), Some("build main"));
@@ -250,12 +676,50 @@ build:
 build: # build main
 \tcc *.c -o main
 ";
-        let tasks = parse(output);
+        let tasks = parse_with_descriptions(output, &BTreeMap::new());
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].name, "build");
         assert_eq!(tasks[0].description.as_deref(), Some("build main"));
     }
 
+    #[test]
+    fn parse_reads_descriptions_from_the_detected_makefile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("GNUmakefile");
+        std::fs::write(&path, "# build main\nbuild:\n\tcc *.c -o main\n").unwrap();
+        let output = "\
+# Files
+build:
+\tcc *.c -o main
+
+# Finished Make data base
+";
+        let tasks = parse(output, &path);
+        assert_eq!(tasks[0].description.as_deref(), Some("build main"));
+    }
+
+    #[test]
+    fn parse_automake_source_skips_assignments() {
+        let source = "\
+bin_PROGRAMS = hello
+AM_CFLAGS := -Wall
+.PHONY: docs
+
+# Run the linter
+lint-local:
+\tcppcheck src
+";
+        let tasks = parse_with_descriptions(source, &parse_makefile_descriptions(source));
+        let summary: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.description.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("docs", None), ("lint-local", Some("Run the linter"))]
+        );
+    }
+
     #[test]
     fn parse_make_uses_makefile_comments_as_description() {
         let output = "\
@@ -276,7 +740,7 @@ build:
 test-all: build
 \t./test --all
 ";
-        let tasks = parse_with_makefile_source(output, Some(makefile_source));
+        let tasks = parse_with_descriptions(output, &parse_makefile_descriptions(makefile_source));
         assert_eq!(tasks.len(), 2);
         assert_eq!(tasks[0].name, "build");
         assert_eq!(tasks[0].description.as_deref(), Some("build main"));
@@ -284,6 +748,32 @@ test-all: build
         assert_eq!(tasks[1].description.as_deref(), Some("test everything"));
     }
 
+    #[test]
+    fn makefile_descriptions_follow_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let makefile = dir.path().join("Makefile");
+        std::fs::create_dir(dir.path().join("mk")).unwrap();
+        std::fs::write(
+            &makefile,
+            "include mk/release.mk\n-include local.mk\ninclude missing.mk\n\n# Build it\nbuild:\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("mk/release.mk"),
+            "include Makefile\n\n# Cut a release\nrelease:\n\n# Shadowed\nbuild:\n",
+        )
+        .unwrap();
+
+        let descriptions = makefile_descriptions(&makefile, dir.path(), &mut HashSet::new());
+        assert_eq!(
+            descriptions.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("build".to_string(), "Build it".to_string()),
+                ("release".to_string(), "Cut a release".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn parse_makefile_descriptions_ignores_variable_assignment() {
         let source = "\
@@ -298,4 +788,118 @@ build:
         assert_eq!(descriptions.get("FOO"), None);
         assert_eq!(descriptions.get("build"), Some(&"build main".to_string()));
     }
+
+    #[test]
+    fn locate_recipe_finds_rule_in_root_and_included_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("Makefile");
+        let nested = dir.path().join("mk").join("release.mk");
+        std::fs::create_dir_all(nested.parent().unwrap()).unwrap();
+        std::fs::write(
+            &root,
+            "CC := cc\ninclude mk/release.mk\n-include missing.mk\n\nbuild:\n\tcc *.c -o main\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &nested,
+            "# ship it\nrelease publish: build\n\t./release.sh\n",
+        )
+        .unwrap();
+
+        assert_eq!(locate_recipe(&root, "build"), Some((root.clone(), 5)));
+        assert_eq!(locate_recipe(&root, "publish"), Some((nested, 2)));
+        assert_eq!(locate_recipe(&root, "CC"), None);
+    }
+
+    #[test]
+    fn recipe_variables_keep_only_undefined_or_conditional_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Makefile");
+        std::fs::write(
+            &path,
+            "include vars.mk\n\
+RT_TEST_PREFIX ?= /usr/local\n\
+RT_TEST_DEST ?=\n\
+RT_TEST_NAME := app\n\
+\n\
+install: build\n\
+\tinstall -d $(RT_TEST_DEST)$(RT_TEST_PREFIX)/bin\n\
+\tcp $(RT_TEST_NAME) ${RT_TEST_ENV} $(RT_TEST_REGION) $(CC) $$HOME $(shell pwd)\n\
+\n\
+build:\n\
+\techo $(RT_TEST_UNUSED)\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("vars.mk"), "RT_TEST_REGION = eu\n").unwrap();
+
+        assert_eq!(
+            recipe_variables(&path, "install"),
+            vec![
+                ("RT_TEST_DEST".to_string(), None),
+                ("RT_TEST_PREFIX".to_string(), Some("/usr/local".to_string())),
+                ("RT_TEST_ENV".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn recipe_variables_read_target_specific_assignments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Makefile");
+        std::fs::write(
+            &path,
+            "install: RT_TEST_PREFIX ?= /opt\n\
+install: export RT_TEST_MODE = release\n\
+deploy: RT_TEST_ENV = prod\n\
+\n\
+install: build\n\
+\t$(INSTALL) -d $(DESTDIR)$(RT_TEST_PREFIX) $(RT_TEST_MODE) $(RT_TEST_ENV)\n\
+\techo $(MAKECMDGOALS) $(MAKELEVEL) $(ARFLAGS) $(YACC) $(LEX) $(PWD)\n\
+\n\
+build:\n\
+\ttrue\n",
+        )
+        .unwrap();
+
+        // Only `install`'s own assignments count; `deploy`'s don't reach it.
+        assert_eq!(
+            recipe_variables(&path, "install"),
+            vec![
+                ("RT_TEST_PREFIX".to_string(), Some("/opt".to_string())),
+                ("RT_TEST_ENV".to_string(), None),
+            ]
+        );
+        // Target-specific assignments aren't rules, so they add no prerequisites.
+        assert_eq!(
+            dependencies(&path),
+            vec![
+                ("install".to_string(), vec!["build".to_string()]),
+                ("build".to_string(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn dependencies_keep_only_target_prerequisites() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Makefile");
+        std::fs::write(
+            &path,
+            "all: build test\nbuild: main.c | dirs\n\tcc main.c\ntest: build ; ./test\ndirs:\n\tmkdir -p out\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            dependencies(&path),
+            vec![
+                (
+                    "all".to_string(),
+                    vec!["build".to_string(), "test".to_string()]
+                ),
+                ("build".to_string(), vec!["dirs".to_string()]),
+                ("test".to_string(), vec!["build".to_string()]),
+                ("dirs".to_string(), vec![]),
+            ]
+        );
+    }
 }
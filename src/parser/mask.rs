@@ -1,3 +1,4 @@
+use crate::task_args::{ParamKind, TaskParam};
 use crate::tasks::TaskItem;
 
 #[derive(Debug, serde::Deserialize)]
@@ -15,6 +16,30 @@ struct Command {
     script: Option<serde_json::Value>,
     #[serde(default)]
     subcommands: Vec<Command>,
+    #[serde(default)]
+    required_args: Vec<Arg>,
+    #[serde(default)]
+    optional_args: Vec<Arg>,
+    #[serde(default)]
+    named_flags: Vec<NamedFlag>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Arg {
+    name: String,
+}
+
+/// An entry of a command's `**OPTIONS**` list.
+#[derive(Debug, serde::Deserialize)]
+struct NamedFlag {
+    #[serde(default)]
+    short: String,
+    #[serde(default)]
+    long: String,
+    #[serde(default)]
+    takes_value: bool,
+    #[serde(default)]
+    required: bool,
 }
 
 pub(super) fn parse(output: &str) -> Vec<TaskItem> {
@@ -40,6 +65,7 @@ fn collect_tasks(items: &mut Vec<TaskItem>, command: Command, prefix: &str) {
         items.push(TaskItem {
             name: name.clone(),
             description: clean_description(command.description),
+            group: None,
         });
     }
 
@@ -48,6 +74,65 @@ fn collect_tasks(items: &mut Vec<TaskItem>, command: Command, prefix: &str) {
     }
 }
 
+/// Returns the arguments and options of `task` (`"gen types"` for subcommands) from `--introspect`.
+///
+/// Output rt can't read yields no parameters; `None` means the task isn't in it.
+pub(super) fn params(output: &str, task: &str) -> Option<Vec<TaskParam>> {
+    let Ok(maskfile) = serde_json::from_str::<Maskfile>(output) else {
+        return Some(Vec::new());
+    };
+    let mut path = task.split_whitespace();
+    let first = path.next()?;
+    let mut command = maskfile
+        .commands
+        .iter()
+        .find(|command| command.name == first)?;
+    for name in path {
+        command = command
+            .subcommands
+            .iter()
+            .find(|command| command.name == name)?;
+    }
+
+    let positional = |arg: &Arg, required: bool| TaskParam {
+        name: arg.name.clone(),
+        required,
+        variadic: false,
+        default: None,
+        kind: ParamKind::Positional,
+    };
+    let mut params: Vec<TaskParam> = command
+        .required_args
+        .iter()
+        .map(|arg| positional(arg, true))
+        .chain(
+            command
+                .optional_args
+                .iter()
+                .map(|arg| positional(arg, false)),
+        )
+        .collect();
+    params.extend(command.named_flags.iter().filter_map(|flag| {
+        let name = if !flag.long.is_empty() {
+            format!("--{}", flag.long)
+        } else if !flag.short.is_empty() {
+            format!("-{}", flag.short)
+        } else {
+            return None;
+        };
+        Some(TaskParam {
+            name,
+            required: flag.required,
+            variadic: false,
+            default: None,
+            kind: ParamKind::Flag {
+                takes_value: flag.takes_value,
+            },
+        })
+    }));
+    Some(params)
+}
+
 fn clean_description(desc: Option<String>) -> Option<String> {
     desc.and_then(|desc| {
         let trimmed = desc.trim();
@@ -97,6 +182,44 @@ mod tests {
         assert_eq!(tasks[1].description.as_deref(), Some("Generate types"));
     }
 
+    #[test]
+    fn params_reads_args_and_options() {
+        let output = r#"
+{
+  "commands": [
+    {
+      "name": "serve",
+      "description": "Serve the site",
+      "script": {"executor": "sh", "source": "serve $dir"},
+      "subcommands": [],
+      "required_args": [{"name": "dir"}],
+      "optional_args": [{"name": "host"}],
+      "named_flags": [
+        {"name": "port", "description": "Port", "short": "p", "long": "port", "multiple": false, "takes_value": true, "choices": [], "required": false, "validate_as_number": true},
+        {"name": "open", "description": "Open", "short": "o", "long": "", "multiple": false, "takes_value": false, "choices": [], "required": true, "validate_as_number": false}
+      ]
+    }
+  ]
+}
+"#;
+        let params = params(output, "serve").unwrap();
+        let summary: Vec<(&str, bool, ParamKind)> = params
+            .iter()
+            .map(|param| (param.name.as_str(), param.required, param.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("dir", true, ParamKind::Positional),
+                ("host", false, ParamKind::Positional),
+                ("--port", false, ParamKind::Flag { takes_value: true }),
+                ("-o", true, ParamKind::Flag { takes_value: false }),
+            ]
+        );
+        assert!(super::params(output, "deploy").is_none());
+        assert_eq!(super::params("not json", "serve"), Some(Vec::new()));
+    }
+
     #[test]
     fn parse_mask_invalid_json() {
         let output = "not json";
@@ -7,27 +7,72 @@ struct MiseTask {
     description: Option<String>,
 }
 
+/// The JSON shapes mise versions print: a bare array, or an object wrapping it in `tasks`.
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum MiseTaskList {
+    Array(Vec<MiseTask>),
+    Wrapped { tasks: Vec<MiseTask> },
+}
+
+/// Parses `mise tasks ls --json` output, or the plain `mise tasks` table without `--json`.
+///
+/// Anything else, such as an error message, yields no tasks.
 pub(super) fn parse(output: &str) -> Vec<TaskItem> {
-    let Ok(tasks) = serde_json::from_str::<Vec<MiseTask>>(output) else {
-        return Vec::new();
+    if is_plain_table(output) {
+        return parse_plain(output);
+    }
+    let tasks = match serde_json::from_str::<MiseTaskList>(output) {
+        Ok(MiseTaskList::Array(tasks) | MiseTaskList::Wrapped { tasks }) => tasks,
+        Err(_) => return Vec::new(),
     };
 
     tasks
         .into_iter()
         .map(|task| TaskItem {
             name: task.name,
-            description: task.description.and_then(|desc| {
-                let desc = desc.trim();
-                if desc.is_empty() {
-                    None
-                } else {
-                    Some(desc.to_string())
-                }
-            }),
+            description: non_empty(task.description.as_deref().unwrap_or_default()),
+            group: None,
+        })
+        .collect()
+}
+
+/// Whether `output` starts with the `Name  Description` header of the plain `mise tasks` table.
+fn is_plain_table(output: &str) -> bool {
+    let Some(header) = output.lines().map(str::trim).find(|line| !line.is_empty()) else {
+        return false;
+    };
+    let mut columns = header.split_whitespace();
+    columns.next() == Some("Name") && columns.any(|column| column == "Description")
+}
+
+/// Parses the `Name  Description` table, one task per line after the header.
+fn parse_plain(output: &str) -> Vec<TaskItem> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .skip(1)
+        .map(|line| {
+            let (name, description) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            TaskItem {
+                name: name.to_string(),
+                description: non_empty(description),
+                group: None,
+            }
         })
         .collect()
 }
 
+fn non_empty(desc: &str) -> Option<String> {
+    let desc = desc.trim();
+    if desc.is_empty() {
+        None
+    } else {
+        Some(desc.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,10 +93,50 @@ mod tests {
         assert_eq!(tasks[1].description, None);
     }
 
+    #[test]
+    fn parse_mise_tasks_wrapped_in_an_object() {
+        let output =
+            r#"{"tasks": [{"name": "lint", "description": "Run clippy"}, {"name": "fmt"}]}"#;
+        let tasks = parse(output);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].name, "lint");
+        assert_eq!(tasks[0].description.as_deref(), Some("Run clippy"));
+        assert_eq!(tasks[1].name, "fmt");
+        assert_eq!(tasks[1].description, None);
+    }
+
+    #[test]
+    fn parse_mise_plain_table() {
+        let output = "\
+Name          Description
+build         Build the project
+db:migrate    Apply migrations
+clean
+";
+        let tasks = parse(output);
+        let described: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|t| (t.name.as_str(), t.description.as_deref()))
+            .collect();
+        assert_eq!(
+            described,
+            vec![
+                ("build", Some("Build the project")),
+                ("db:migrate", Some("Apply migrations")),
+                ("clean", None),
+            ]
+        );
+    }
+
     #[test]
     fn parse_mise_invalid_json() {
         let output = "not json";
         let tasks = parse(output);
         assert!(tasks.is_empty());
     }
+
+    #[test]
+    fn parse_mise_truncated_json() {
+        assert!(parse("[not json").is_empty());
+    }
 }
@@ -0,0 +1,322 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::glob_match;
+use crate::tasks::TaskItem;
+
+/// Parses the `scripts` table of a `package.json`, describing each script by its command.
+pub(super) fn parse(content: &str) -> Vec<TaskItem> {
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(manifest) => scripts(&manifest, None),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Returns the scripts of the workspace packages under the root `package.json` at `path`, named
+/// `<workspace>:<script>` and grouped by workspace.
+pub(super) fn workspace_tasks(path: &Path) -> Vec<TaskItem> {
+    let Some(manifest) = read_manifest(path) else {
+        return Vec::new();
+    };
+    let Some(root) = path.parent() else {
+        return Vec::new();
+    };
+    workspaces(root, &manifest)
+        .iter()
+        .flat_map(|workspace| scripts(&workspace.manifest, Some(&workspace.name)))
+        .collect()
+}
+
+/// Returns the workspace selector and script a `<workspace>:<script>` task runs, or `None` for a
+/// root script (root scripts win when a name could be either).
+pub(super) fn workspace_script(path: &Path, task: &str) -> Option<(String, String)> {
+    let manifest = read_manifest(path)?;
+    if script_names(&manifest).any(|name| name == task) {
+        return None;
+    }
+    // Package names can't contain `:`, so the first one ends the workspace name.
+    let (name, script) = task.split_once(':')?;
+    workspaces(path.parent()?, &manifest)
+        .into_iter()
+        .find(|workspace| workspace.name == name)
+        .filter(|workspace| script_names(&workspace.manifest).any(|known| known == script))
+        .map(|workspace| (workspace.selector, script.to_string()))
+}
+
+fn read_manifest(path: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn scripts(manifest: &serde_json::Value, workspace: Option<&str>) -> Vec<TaskItem> {
+    let Some(scripts) = manifest
+        .get("scripts")
+        .and_then(|scripts| scripts.as_object())
+    else {
+        return Vec::new();
+    };
+
+    scripts
+        .iter()
+        .filter_map(|(name, command)| {
+            Some(TaskItem {
+                name: match workspace {
+                    Some(workspace) => format!("{workspace}:{name}"),
+                    None => name.clone(),
+                },
+                description: Some(command.as_str()?.to_string()),
+                group: workspace.map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+fn script_names(manifest: &serde_json::Value) -> impl Iterator<Item = &str> {
+    manifest
+        .get("scripts")
+        .and_then(|scripts| scripts.as_object())
+        .into_iter()
+        .flat_map(|scripts| scripts.keys().map(String::as_str))
+}
+
+struct Workspace {
+    /// The package's `name`, or its directory name when it has none.
+    name: String,
+    /// What the package manager is given to pick the package: its `name`, or `./<path>` from the
+    /// root when it has none, since a bare directory name matches no package.
+    selector: String,
+    manifest: serde_json::Value,
+}
+
+/// Returns the packages matched by the root's `workspaces` field or `pnpm-workspace.yaml`,
+/// in path order.
+fn workspaces(root: &Path, manifest: &serde_json::Value) -> Vec<Workspace> {
+    let patterns = workspace_patterns(root, manifest);
+    let (excluded, included): (Vec<&String>, Vec<&String>) = patterns
+        .iter()
+        .partition(|pattern| pattern.starts_with('!'));
+    let excluded: Vec<PathBuf> = excluded
+        .iter()
+        .flat_map(|pattern| expand_pattern(root, &pattern[1..]))
+        .collect();
+    let mut dirs: Vec<PathBuf> = included
+        .iter()
+        .flat_map(|pattern| expand_pattern(root, pattern))
+        .filter(|dir| dir != root && !excluded.contains(dir))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+
+    dirs.into_iter()
+        .filter_map(|dir| {
+            let manifest = read_manifest(&dir.join("package.json"))?;
+            let (name, selector) = match manifest.get("name").and_then(|name| name.as_str()) {
+                Some(name) => (name.to_string(), name.to_string()),
+                None => {
+                    let relative = dir.strip_prefix(root).ok()?.to_string_lossy();
+                    (
+                        dir.file_name()?.to_string_lossy().into_owned(),
+                        format!("./{}", relative.replace('\\', "/")),
+                    )
+                }
+            };
+            Some(Workspace {
+                name,
+                selector,
+                manifest,
+            })
+        })
+        .collect()
+}
+
+/// Reads `workspaces` (an array, or yarn's `{ "packages": [...] }`), falling back to
+/// `pnpm-workspace.yaml`.
+fn workspace_patterns(root: &Path, manifest: &serde_json::Value) -> Vec<String> {
+    let declared = manifest.get("workspaces").map(|workspaces| {
+        workspaces
+            .get("packages")
+            .unwrap_or(workspaces)
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|pattern| pattern.as_str().map(str::to_string))
+            .collect()
+    });
+    declared.unwrap_or_else(|| {
+        let Ok(content) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) else {
+            return Vec::new();
+        };
+        let Ok(config) = serde_yaml::from_str::<serde_yaml::Value>(&content) else {
+            return Vec::new();
+        };
+        config
+            .get("packages")
+            .and_then(|packages| packages.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|pattern| pattern.as_str().map(str::to_string))
+            .collect()
+    })
+}
+
+/// Expands a workspace glob (`packages/*`, `apps/**`) to directories holding a `package.json`.
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let mut dirs = vec![root.to_path_buf()];
+    for component in pattern.split('/').filter(|component| !component.is_empty()) {
+        dirs = dirs
+            .iter()
+            .flat_map(|dir| match component {
+                "**" => descendants(dir),
+                _ if component.contains(['*', '?']) => subdirectories(dir)
+                    .into_iter()
+                    .filter(|child| {
+                        child
+                            .file_name()
+                            .is_some_and(|name| glob_match(component, &name.to_string_lossy()))
+                    })
+                    .collect(),
+                _ => vec![dir.join(component)],
+            })
+            .collect();
+    }
+    dirs.retain(|dir| dir.join("package.json").is_file());
+    dirs
+}
+
+/// Returns `dir` and every directory below it, skipping `node_modules` and hidden directories.
+fn descendants(dir: &Path) -> Vec<PathBuf> {
+    let mut found = vec![dir.to_path_buf()];
+    for child in subdirectories(dir) {
+        found.extend(descendants(&child));
+    }
+    found
+}
+
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter(|entry| {
+            let name = entry.file_name();
+            name != "node_modules" && !name.to_string_lossy().starts_with('.')
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn summary(tasks: &[TaskItem]) -> Vec<(&str, Option<&str>)> {
+        tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.group.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_reads_scripts_with_commands() {
+        let content = r#"{
+  "name": "web",
+  "scripts": {
+    "build": "vite build",
+    "test": "vitest run",
+    "broken": 1
+  }
+}"#;
+        let tasks = parse(content);
+        let summary: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.description.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("build", Some("vite build")), ("test", Some("vitest run"))]
+        );
+    }
+
+    #[test]
+    fn parse_without_scripts_is_empty() {
+        assert!(parse(r#"{"name": "web"}"#).is_empty());
+        assert!(parse("not json").is_empty());
+    }
+
+    #[test]
+    fn parse_prefixes_workspace_scripts_with_the_package_name() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("package.json");
+        let content =
+            r#"{"scripts": {"lint": "eslint ."}, "workspaces": ["packages/*", "!packages/old"]}"#;
+        write(&root, content);
+        write(
+            &dir.path().join("packages/web/package.json"),
+            r#"{"name": "web", "scripts": {"build": "vite build"}}"#,
+        );
+        write(
+            &dir.path().join("packages/api/package.json"),
+            r#"{"name": "@acme/api", "scripts": {"test": "vitest"}}"#,
+        );
+        write(
+            &dir.path().join("packages/old/package.json"),
+            r#"{"name": "old", "scripts": {"build": "true"}}"#,
+        );
+        write(&dir.path().join("packages/docs/README.md"), "not a package");
+
+        assert_eq!(summary(&parse(content)), vec![("lint", None)]);
+        assert_eq!(
+            summary(&workspace_tasks(&root)),
+            vec![
+                ("@acme/api:test", Some("@acme/api")),
+                ("web:build", Some("web")),
+            ]
+        );
+        assert_eq!(
+            workspace_script(&root, "@acme/api:test"),
+            Some(("@acme/api".to_string(), "test".to_string()))
+        );
+        assert_eq!(workspace_script(&root, "lint"), None);
+        assert_eq!(workspace_script(&root, "web:missing"), None);
+    }
+
+    #[test]
+    fn parse_reads_pnpm_workspace_yaml() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("package.json");
+        let content = r#"{"scripts": {"build:css": "postcss"}}"#;
+        write(&root, content);
+        write(
+            &dir.path().join("pnpm-workspace.yaml"),
+            "packages:\n  - 'apps/**'\n",
+        );
+        write(
+            &dir.path().join("apps/site/package.json"),
+            r#"{"scripts": {"dev": "astro dev"}}"#,
+        );
+        write(
+            &dir.path().join("apps/site/node_modules/dep/package.json"),
+            r#"{"name": "dep", "scripts": {"postinstall": "true"}}"#,
+        );
+
+        assert_eq!(
+            summary(&workspace_tasks(&root)),
+            vec![("site:dev", Some("site"))]
+        );
+        // Without a `name`, the package is picked by its path.
+        assert_eq!(
+            workspace_script(&root, "site:dev"),
+            Some(("./apps/site".to_string(), "dev".to_string()))
+        );
+        // A root script named like `<workspace>:<script>` stays a root script.
+        assert_eq!(workspace_script(&root, "build:css"), None);
+    }
+}
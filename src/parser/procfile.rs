@@ -0,0 +1,75 @@
+use crate::tasks::TaskItem;
+
+pub(super) fn parse(content: &str) -> Vec<TaskItem> {
+    content
+        .lines()
+        .filter_map(parse_line)
+        .map(|(name, command)| TaskItem {
+            name: name.to_string(),
+            description: Some(command.to_string()),
+            group: None,
+        })
+        .collect()
+}
+
+pub(super) fn command(content: &str, task: &str) -> Option<String> {
+    content
+        .lines()
+        .filter_map(parse_line)
+        .find(|(name, _)| *name == task)
+        .map(|(_, command)| command.to_string())
+}
+
+fn parse_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (name, command) = line.split_once(':')?;
+    let name = name.trim();
+    let command = command.trim();
+    let valid_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid_name || command.is_empty() {
+        return None;
+    }
+    Some((name, command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_procfile() {
+        let content = "\
+# processes
+web: bundle exec puma -C config/puma.rb
+
+worker:   bundle exec sidekiq
+release: ./bin/release --url=http://example.com
+";
+        let tasks = parse(content);
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].name, "web");
+        assert_eq!(
+            tasks[0].description.as_deref(),
+            Some("bundle exec puma -C config/puma.rb")
+        );
+        assert_eq!(tasks[1].name, "worker");
+        assert_eq!(
+            tasks[2].description.as_deref(),
+            Some("./bin/release --url=http://example.com")
+        );
+    }
+
+    #[test]
+    fn command_returns_entry_for_name() {
+        let content = "web: puma\nworker: sidekiq\n";
+        assert_eq!(command(content, "worker").as_deref(), Some("sidekiq"));
+        assert_eq!(command(content, "missing"), None);
+    }
+}
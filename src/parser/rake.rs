@@ -0,0 +1,60 @@
+use crate::tasks::TaskItem;
+
+/// Parses `rake -T` lines such as `rake db:migrate[version]  # Migrate the database`.
+///
+/// Namespaced names are kept as written; an argument list in brackets is dropped from the name
+/// since rake takes it as part of the task argument (`db:migrate[42]`).
+pub(super) fn parse(output: &str) -> Vec<TaskItem> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("rake ")?;
+            let (name, description) = match rest.split_once('#') {
+                Some((name, description)) => (name, Some(description.trim())),
+                None => (rest, None),
+            };
+            let name = name.trim();
+            let name = name.split_once('[').map_or(name, |(name, _)| name);
+            if name.is_empty() {
+                return None;
+            }
+            Some(TaskItem {
+                name: name.to_string(),
+                description: description
+                    .filter(|description| !description.is_empty())
+                    .map(str::to_string),
+                group: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rake_task_list() {
+        let output = "\
+rake build                 # Build the gem
+rake db:migrate[version]   # Migrate the database
+rake db:seed               # Seed the database
+rake release
+(in /home/user/app)
+";
+        let tasks = parse(output);
+        let summary: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.description.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("build", Some("Build the gem")),
+                ("db:migrate", Some("Migrate the database")),
+                ("db:seed", Some("Seed the database")),
+                ("release", None),
+            ]
+        );
+    }
+}
@@ -1,26 +1,38 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde_yaml::{Mapping, Value};
+
 use crate::tasks::TaskItem;
 
+/// File names `task` looks for in an included directory, in its lookup order.
+const TASKFILE_NAMES: [&str; 8] = [
+    "Taskfile.yml",
+    "taskfile.yml",
+    "Taskfile.yaml",
+    "taskfile.yaml",
+    "Taskfile.dist.yml",
+    "taskfile.dist.yml",
+    "Taskfile.dist.yaml",
+    "taskfile.dist.yaml",
+];
+
 pub(super) fn parse(output: &str) -> Vec<TaskItem> {
     let mut items = Vec::new();
     for line in output.lines() {
-        let mut line = line.trim_start();
-        if line.is_empty() {
-            continue;
-        }
-        if line.starts_with("task:") || line.starts_with("Available") {
+        // Tasks are bulleted; anything else is a header or a message, not a task name.
+        let line = line.trim_start();
+        let Some(line) = line.strip_prefix("* ").or_else(|| line.strip_prefix("- ")) else {
             continue;
-        }
-
-        if let Some(stripped) = line.strip_prefix("* ") {
-            line = stripped;
-        } else if let Some(stripped) = line.strip_prefix("- ") {
-            line = stripped;
-        }
+        };
 
-        let (name, desc) = match line.split_once(':') {
-            Some((name, desc)) => (name.trim(), Some(desc.trim())),
+        // Included tasks are namespaced (`docker:build`), so the name ends at the first colon
+        // followed by whitespace or the end of the line, not at the first colon.
+        let (name, desc) = match line.split_once(char::is_whitespace) {
+            Some((name, desc)) => (name, Some(desc.trim())),
             None => (line.trim(), None),
         };
+        let name = name.strip_suffix(':').unwrap_or(name);
 
         if name.is_empty() {
             continue;
@@ -30,11 +42,168 @@ pub(super) fn parse(output: &str) -> Vec<TaskItem> {
         items.push(TaskItem {
             name: name.to_string(),
             description,
+            group: None,
         });
     }
     items
 }
 
+/// Returns the variables `task` lists under `requires.vars`, plus those of the tasks it runs
+/// through `deps` or `cmds` that the call doesn't set itself.
+///
+/// `task` may be namespaced (`docker:build`) to reach a task in an included Taskfile. Returns
+/// `None` when the Taskfile can't be read or declares no such task.
+pub(super) fn required_vars(path: &Path, task: &str) -> Option<Vec<String>> {
+    let mut required = Vec::new();
+    let mut visited = HashSet::new();
+    collect_required_vars(path, task, &mut visited, &mut required)?;
+    Some(required)
+}
+
+/// Whether `task`'s own definition refers to `CLI_ARGS`, following include namespaces.
+pub(super) fn uses_cli_args(path: &Path, task: &str) -> bool {
+    let Some(taskfile) = read_yaml(path) else {
+        return false;
+    };
+    let task = task.strip_prefix(':').unwrap_or(task);
+    if let Some((namespace, rest)) = task.split_once(':')
+        && let Some(included) = include_path(path, &taskfile, namespace)
+    {
+        return uses_cli_args(&included, rest);
+    }
+    find_task(&taskfile, task)
+        .and_then(|definition| serde_yaml::to_string(definition).ok())
+        .is_some_and(|definition| definition.contains("CLI_ARGS"))
+}
+
+fn collect_required_vars(
+    path: &Path,
+    task: &str,
+    visited: &mut HashSet<(PathBuf, String)>,
+    required: &mut Vec<String>,
+) -> Option<()> {
+    if !visited.insert((path.to_path_buf(), task.to_string())) {
+        return Some(());
+    }
+
+    let taskfile = read_yaml(path)?;
+    let task = task.strip_prefix(':').unwrap_or(task);
+    if let Some((namespace, rest)) = task.split_once(':')
+        && let Some(included) = include_path(path, &taskfile, namespace)
+    {
+        return collect_required_vars(&included, rest, visited, required);
+    }
+
+    let definition = find_task(&taskfile, task)?;
+    for name in requires_vars(definition) {
+        if !required.contains(&name) {
+            required.push(name);
+        }
+    }
+
+    for (called, provided) in called_tasks(definition) {
+        let mut nested = Vec::new();
+        // A called task that can't be resolved is left for `task` to report.
+        let _ = collect_required_vars(path, &called, visited, &mut nested);
+        for name in nested {
+            if !provided.contains(&name) && !required.contains(&name) {
+                required.push(name);
+            }
+        }
+    }
+    Some(())
+}
+
+fn read_yaml(path: &Path) -> Option<Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_yaml::from_str(&content).ok()
+}
+
+fn find_task<'a>(taskfile: &'a Value, task: &str) -> Option<&'a Value> {
+    let tasks = taskfile.get("tasks")?.as_mapping()?;
+    if let Some(definition) = tasks.get(task) {
+        return Some(definition);
+    }
+    tasks.values().find(|definition| {
+        definition
+            .get("aliases")
+            .and_then(Value::as_sequence)
+            .is_some_and(|aliases| aliases.iter().any(|alias| alias.as_str() == Some(task)))
+    })
+}
+
+/// Resolves the Taskfile included under `namespace`, relative to the including file.
+fn include_path(path: &Path, taskfile: &Value, namespace: &str) -> Option<PathBuf> {
+    let include = taskfile.get("includes")?.get(namespace)?;
+    let target = match include {
+        Value::String(target) => target.as_str(),
+        Value::Mapping(map) => map.get("taskfile")?.as_str()?,
+        _ => return None,
+    };
+    let target = path.parent()?.join(target);
+    if target.is_dir() {
+        TASKFILE_NAMES
+            .iter()
+            .map(|name| target.join(name))
+            .find(|candidate| candidate.is_file())
+    } else {
+        Some(target)
+    }
+}
+
+fn requires_vars(definition: &Value) -> Vec<String> {
+    let Some(vars) = definition
+        .get("requires")
+        .and_then(|requires| requires.get("vars"))
+        .and_then(Value::as_sequence)
+    else {
+        return Vec::new();
+    };
+    vars.iter()
+        .filter_map(|var| match var {
+            Value::String(name) => Some(name.clone()),
+            // `{ name: ENV, enum: [dev, prod] }`
+            Value::Mapping(map) => map.get("name")?.as_str().map(str::to_string),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the tasks run from `deps` and `cmds`, with the variables each call sets.
+fn called_tasks(definition: &Value) -> Vec<(String, Vec<String>)> {
+    let entries = |key| {
+        definition
+            .get(key)
+            .and_then(Value::as_sequence)
+            .into_iter()
+            .flatten()
+    };
+    // Plain strings name tasks under `deps`, but are shell commands under `cmds`.
+    let deps = entries("deps").filter_map(|entry| match entry {
+        Value::String(name) => Some((name.clone(), Vec::new())),
+        _ => task_call(entry),
+    });
+    let cmds = entries("cmds").filter_map(task_call);
+    deps.chain(cmds).collect()
+}
+
+/// Parses a `{ task: name, vars: {...} }` call.
+fn task_call(entry: &Value) -> Option<(String, Vec<String>)> {
+    let task = entry.get("task")?.as_str()?.to_string();
+    let provided = entry
+        .get("vars")
+        .and_then(Value::as_mapping)
+        .map(mapping_keys)
+        .unwrap_or_default();
+    Some((task, provided))
+}
+
+fn mapping_keys(map: &Mapping) -> Vec<String> {
+    map.keys()
+        .filter_map(|key| key.as_str().map(str::to_string))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,4 +220,149 @@ task: Available tasks for this project:
         assert_eq!(tasks[0].name, "build");
         assert_eq!(tasks[0].description.as_deref(), Some("Build the project"));
     }
+
+    #[test]
+    fn uses_cli_args_checks_the_task_definition() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Taskfile.yml");
+        std::fs::write(
+            &path,
+            "version: '3'\ntasks:\n  test:\n    cmds:\n      - go test {{.CLI_ARGS}}\n  build:\n    cmds:\n      - go build\n",
+        )
+        .unwrap();
+        assert!(uses_cli_args(&path, "test"));
+        assert!(!uses_cli_args(&path, "build"));
+        assert!(!uses_cli_args(&path, "missing"));
+    }
+
+    #[test]
+    fn required_vars_reads_requires_and_called_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Taskfile.yml");
+        std::fs::write(
+            &path,
+            r#"
+version: '3'
+tasks:
+  migrate:
+    requires:
+      vars: [DB_URL, ENV]
+  deploy:
+    aliases: [ship]
+    requires:
+      vars:
+        - ENV
+        - name: REGION
+          enum: [us, eu]
+    deps: [migrate]
+    cmds:
+      - task: notify
+        vars: { CHANNEL: ops }
+      - echo deployed
+  notify:
+    requires:
+      vars: [CHANNEL, WEBHOOK]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            required_vars(&path, "deploy"),
+            Some(vec![
+                "ENV".to_string(),
+                "REGION".to_string(),
+                "DB_URL".to_string(),
+                "WEBHOOK".to_string(),
+            ])
+        );
+        assert_eq!(required_vars(&path, "ship"), required_vars(&path, "deploy"));
+        assert_eq!(required_vars(&path, "missing"), None);
+    }
+
+    #[test]
+    fn required_vars_follows_include_namespaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Taskfile.yml");
+        std::fs::write(
+            &path,
+            "version: '3'\nincludes:\n  docker: ./docker\n  db:\n    taskfile: ./db.yml\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("docker")).unwrap();
+        std::fs::write(
+            dir.path().join("docker").join("Taskfile.yml"),
+            "version: '3'\ntasks:\n  push:\n    requires:\n      vars: [REGISTRY]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("db.yml"),
+            "version: '3'\ntasks:\n  seed:\n    requires:\n      vars: [DB_URL]\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            required_vars(&path, "docker:push"),
+            Some(vec!["REGISTRY".to_string()])
+        );
+        assert_eq!(
+            required_vars(&path, "db:seed"),
+            Some(vec!["DB_URL".to_string()])
+        );
+    }
+
+    #[test]
+    fn parse_keeps_included_task_namespaces() {
+        let output = "\
+task: Available tasks for this project:
+* build:                 Build the project
+* docker:build:          Build the image
+* docker:push:
+* docker:db:migrate:     Run migrations
+";
+        let tasks = parse(output);
+        let summary: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.description.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("build", Some("Build the project")),
+                ("docker:build", Some("Build the image")),
+                ("docker:push", None),
+                ("docker:db:migrate", Some("Run migrations")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_keeps_colons_in_namespaced_task_descriptions() {
+        let output = "\
+- docker:release:        Release: tag the image, then push
+- docker:build
+";
+        let tasks = parse(output);
+        let summary: Vec<(&str, Option<&str>)> = tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.description.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("docker:release", Some("Release: tag the image, then push")),
+                ("docker:build", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_skips_lines_without_a_bullet() {
+        let output = "\
+--list-all
+task: No tasks with description available. Try --list-all to list all tasks
+* build:
+";
+        let names: Vec<String> = parse(output).into_iter().map(|task| task.name).collect();
+        assert_eq!(names, vec!["build".to_string()]);
+    }
 }
@@ -0,0 +1,77 @@
+use std::fmt::Display;
+
+use inquire::{MultiSelect, Select};
+use terminal_size::{Height, terminal_size};
+
+use crate::config::UiConfig;
+
+/// Page size used when the terminal height can't be read.
+const DEFAULT_PAGE_SIZE: usize = 10;
+/// Lines taken by the prompt message, filter input and help line around the options.
+const PROMPT_CHROME_LINES: usize = 4;
+const MIN_PAGE_SIZE: usize = 5;
+const MAX_PAGE_SIZE: usize = 30;
+
+/// Builds a `Select` with the `[ui]` picker settings applied.
+pub fn select<'a, T: Display>(message: &'a str, options: Vec<T>, ui: &UiConfig) -> Select<'a, T> {
+    Select::new(message, options)
+        .with_page_size(page_size(ui))
+        .with_vim_mode(ui.vim_keys)
+}
+
+/// Builds a `MultiSelect` with the `[ui]` picker settings applied.
+pub fn multi_select<'a, T: Display>(
+    message: &'a str,
+    options: Vec<T>,
+    ui: &UiConfig,
+) -> MultiSelect<'a, T> {
+    MultiSelect::new(message, options)
+        .with_page_size(page_size(ui))
+        .with_vim_mode(ui.vim_keys)
+}
+
+fn page_size(ui: &UiConfig) -> usize {
+    ui.page_size
+        .unwrap_or_else(|| {
+            terminal_size()
+                .map(|(_, Height(height))| page_size_for_height(height as usize))
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+        })
+        .max(1)
+}
+
+/// Fits the picker to the terminal, leaving room for the surrounding prompt lines.
+fn page_size_for_height(height: usize) -> usize {
+    height
+        .saturating_sub(PROMPT_CHROME_LINES)
+        .clamp(MIN_PAGE_SIZE, MAX_PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_size_for_height_scales_within_bounds() {
+        assert_eq!(page_size_for_height(24), 20);
+        assert_eq!(page_size_for_height(6), MIN_PAGE_SIZE);
+        assert_eq!(page_size_for_height(200), MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn configured_page_size_wins_over_terminal_height() {
+        let ui = UiConfig {
+            page_size: Some(20),
+            vim_keys: true,
+            ..UiConfig::default()
+        };
+        assert_eq!(page_size(&ui), 20);
+        assert_eq!(
+            page_size(&UiConfig {
+                page_size: Some(0),
+                ..UiConfig::default()
+            }),
+            1
+        );
+    }
+}
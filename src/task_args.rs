@@ -1,30 +1,102 @@
 use std::path::Path;
 
 use crate::detect::{Detection, Runner};
+use crate::parser;
+use crate::tasks;
+
+/// A parameter declared by a task, in declaration order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskParam {
+    pub name: String,
+    pub required: bool,
+    pub variadic: bool,
+    /// Literal default value, when the declared default is a plain string.
+    pub default: Option<String>,
+    pub kind: ParamKind,
+}
 
-pub fn required_args_for_task(
+/// How a parameter's value reaches the runner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    /// A positional argument after the task name (just recipe parameters).
+    Positional,
+    /// A `NAME=value` assignment (Taskfile `requires.vars`, Makefile variables).
+    Variable,
+    /// A named flag such as `--port 80`, or a bare `--verbose` when it takes no value (mask
+    /// `**OPTIONS**`). The parameter's name is the flag itself.
+    Flag { takes_value: bool },
+}
+
+/// Returns every parameter declared by the task, required and optional.
+///
+/// Returns `None` when the runner file was parsed and declares no such task, as opposed to a
+/// task that exists but takes no parameters (or a runner rt can't inspect).
+pub fn params_for_task(
     detection: &Detection,
     task: &str,
-) -> Result<Vec<String>, std::io::Error> {
+) -> Result<Option<Vec<TaskParam>>, std::io::Error> {
     match detection.runner {
-        Runner::Justfile => parse_justfile_required_args(&detection.runner_file, task),
-        _ => Ok(Vec::new()),
+        Runner::Justfile => {
+            if let Some(params) = parse_justfile_params(&detection.runner_file, task)? {
+                return Ok(Some(params));
+            }
+            // Module paths (`docs::build`, `docs build`) are resolved by just itself.
+            let is_module_path = task.contains("::") || task.contains(char::is_whitespace);
+            let declared = parser::declared_tasks(detection.runner, &detection.runner_file)
+                .is_some_and(|names| names.iter().any(|name| name == task));
+            // With `set fallback`, just itself looks for the recipe in parent justfiles.
+            let falls_back = parser::falls_back_to_parent(detection.runner, &detection.runner_file);
+            Ok((is_module_path || declared || falls_back).then(Vec::new))
+        }
+        Runner::Taskfile => {
+            // Namespaces, wildcards and includes `task` resolves itself aren't rejected here.
+            let required =
+                parser::taskfile_required_vars(&detection.runner_file, task).unwrap_or_default();
+            Ok(Some(required.into_iter().map(required_variable).collect()))
+        }
+        Runner::Makefile => Ok(Some(
+            parser::makefile_recipe_variables(&detection.runner_file, task)
+                .into_iter()
+                .map(|(name, default)| TaskParam {
+                    name,
+                    required: default.is_none(),
+                    variadic: false,
+                    default,
+                    kind: ParamKind::Variable,
+                })
+                .collect(),
+        )),
+        Runner::Maskfile => match tasks::raw_listing(detection) {
+            Ok(listing) => Ok(parser::mask_params(&listing.output, task)),
+            // Without an introspection listing, mask reports unknown tasks and arguments itself.
+            Err(_) => Ok(Some(Vec::new())),
+        },
+        _ => Ok(Some(Vec::new())),
     }
 }
 
-fn parse_justfile_required_args(path: &Path, task: &str) -> Result<Vec<String>, std::io::Error> {
-    let content = std::fs::read_to_string(path)?;
-
-    for line in content.lines() {
-        if let Some(required) = parse_required_from_just_header(line, task) {
-            return Ok(required);
-        }
+fn required_variable(name: String) -> TaskParam {
+    TaskParam {
+        name,
+        required: true,
+        variadic: false,
+        default: None,
+        kind: ParamKind::Variable,
     }
+}
 
-    Ok(Vec::new())
+fn parse_justfile_params(
+    path: &Path,
+    task: &str,
+) -> Result<Option<Vec<TaskParam>>, std::io::Error> {
+    let content = std::fs::read_to_string(path)?;
+
+    Ok(content
+        .lines()
+        .find_map(|line| parse_params_from_just_header(line, task)))
 }
 
-fn parse_required_from_just_header(line: &str, task: &str) -> Option<Vec<String>> {
+fn parse_params_from_just_header(line: &str, task: &str) -> Option<Vec<TaskParam>> {
     if line.starts_with(' ') || line.starts_with('\t') {
         return None;
     }
@@ -51,22 +123,74 @@ fn parse_required_from_just_header(line: &str, task: &str) -> Option<Vec<String>
         return None;
     }
 
-    let mut required = Vec::new();
+    let mut params = Vec::new();
     for raw in parts.into_iter().skip(1) {
         let token = raw.trim_end_matches(',');
-        if token.is_empty() || token.starts_with('*') || has_top_level_char(token, '=') {
+        if token.is_empty() {
             continue;
         }
 
-        let clean = token.trim_start_matches(['$', '+', '*']);
+        let (declaration, default) = match split_default(token) {
+            Some((declaration, default)) => (declaration, Some(default)),
+            None => (token, None),
+        };
+        let star = declaration.starts_with('*');
+        let variadic = star || declaration.starts_with('+');
+        let clean = declaration.trim_start_matches(['$', '+', '*']);
         if !is_valid_identifier(clean) {
             continue;
         }
 
-        required.push(clean.to_string());
+        params.push(TaskParam {
+            name: clean.to_string(),
+            required: default.is_none() && !star,
+            variadic,
+            default: default.and_then(literal_default),
+            kind: ParamKind::Positional,
+        });
+    }
+
+    Some(params)
+}
+
+fn split_default(token: &str) -> Option<(&str, &str)> {
+    let mut state = ParseState {
+        quote: None,
+        escaped: false,
+        paren: 0,
+        bracket: 0,
+        brace: 0,
+    };
+
+    for (idx, ch) in token.char_indices() {
+        if state.top_level() && ch == '=' {
+            return Some((&token[..idx], &token[idx + 1..]));
+        }
+        advance_state(&mut state, ch);
+    }
+
+    None
+}
+
+/// Returns the value of a plain quoted or bare-word default, skipping expressions.
+pub(crate) fn literal_default(raw: &str) -> Option<String> {
+    for quote in ['\'', '"'] {
+        if let Some(inner) = raw
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return if inner.contains(quote) || (quote == '"' && inner.contains('\\')) {
+                None
+            } else {
+                Some(inner.to_string())
+            };
+        }
     }
 
-    Some(required)
+    if !raw.is_empty() && raw.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Some(raw.to_string());
+    }
+    None
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -91,7 +215,7 @@ impl ParseState {
     }
 }
 
-fn find_top_level_colon(input: &str) -> Option<usize> {
+pub(crate) fn find_top_level_colon(input: &str) -> Option<usize> {
     let mut state = ParseState {
         quote: None,
         escaped: false,
@@ -110,7 +234,7 @@ fn find_top_level_colon(input: &str) -> Option<usize> {
     None
 }
 
-fn split_top_level_whitespace(input: &str) -> Vec<&str> {
+pub(crate) fn split_top_level_whitespace(input: &str) -> Vec<&str> {
     let mut state = ParseState {
         quote: None,
         escaped: false,
@@ -142,25 +266,6 @@ fn split_top_level_whitespace(input: &str) -> Vec<&str> {
     parts
 }
 
-fn has_top_level_char(input: &str, target: char) -> bool {
-    let mut state = ParseState {
-        quote: None,
-        escaped: false,
-        paren: 0,
-        bracket: 0,
-        brace: 0,
-    };
-
-    for ch in input.chars() {
-        if state.top_level() && ch == target {
-            return true;
-        }
-        advance_state(&mut state, ch);
-    }
-
-    false
-}
-
 fn advance_state(state: &mut ParseState, ch: char) {
     if let Some(quote) = state.quote {
         if state.escaped {
@@ -195,7 +300,7 @@ fn advance_state(state: &mut ParseState, ch: char) {
     }
 }
 
-fn is_valid_identifier(value: &str) -> bool {
+pub(crate) fn is_valid_identifier(value: &str) -> bool {
     if value.is_empty() {
         return false;
     }
@@ -212,45 +317,81 @@ fn is_valid_identifier(value: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::detection_at;
     use tempfile::tempdir;
 
+    fn required_names(params: Vec<TaskParam>) -> Vec<String> {
+        params
+            .into_iter()
+            .filter(|param| param.required)
+            .map(|param| param.name)
+            .collect()
+    }
+
     #[test]
-    fn parse_required_from_just_header_extracts_only_required_args() {
+    fn parse_params_from_just_header_marks_only_bare_and_plus_params_required() {
         let header = "test TEST ENV='prod' +FILES *REST: build";
-        let required = parse_required_from_just_header(header, "test").unwrap();
+        let required = required_names(parse_params_from_just_header(header, "test").unwrap());
         assert_eq!(required, vec!["TEST".to_string(), "FILES".to_string()]);
     }
 
     #[test]
-    fn parse_required_from_just_header_ignores_non_recipe_lines() {
-        assert!(parse_required_from_just_header("foo := 'bar'", "foo").is_none());
-        assert!(parse_required_from_just_header("  test TEST:", "test").is_none());
-        assert!(parse_required_from_just_header("# test TEST:", "test").is_none());
+    fn parse_params_from_just_header_ignores_non_recipe_lines() {
+        assert!(parse_params_from_just_header("foo := 'bar'", "foo").is_none());
+        assert!(parse_params_from_just_header("  test TEST:", "test").is_none());
+        assert!(parse_params_from_just_header("# test TEST:", "test").is_none());
     }
 
     #[test]
-    fn parse_required_from_just_header_handles_colons_in_default_values() {
+    fn parse_params_from_just_header_required_handles_colons_in_default_values() {
         let header = "deploy ENV='prod:blue' TARGET: build";
-        let required = parse_required_from_just_header(header, "deploy").unwrap();
+        let required = required_names(parse_params_from_just_header(header, "deploy").unwrap());
         assert_eq!(required, vec!["TARGET".to_string()]);
     }
 
     #[test]
-    fn parse_required_from_just_header_handles_spaces_in_default_values() {
+    fn parse_params_from_just_header_required_handles_spaces_in_default_values() {
         let header = "test MSG='hello world' TARGET: run";
-        let required = parse_required_from_just_header(header, "test").unwrap();
+        let required = required_names(parse_params_from_just_header(header, "test").unwrap());
         assert_eq!(required, vec!["TARGET".to_string()]);
     }
 
     #[test]
-    fn parse_required_from_just_header_ignores_star_and_includes_plus() {
+    fn parse_params_from_just_header_required_ignores_star_and_includes_plus() {
         let header = "build +FILES *REST TARGET: run";
-        let required = parse_required_from_just_header(header, "build").unwrap();
+        let required = required_names(parse_params_from_just_header(header, "build").unwrap());
         assert_eq!(required, vec!["FILES".to_string(), "TARGET".to_string()]);
     }
 
     #[test]
-    fn parse_justfile_required_args_reads_matching_recipe() {
+    fn parse_params_from_just_header_lists_all_params_in_order() {
+        let header = "deploy TARGET ENV='prod' RETRIES=3 LABEL=(a + b) *FLAGS: build";
+        let params = parse_params_from_just_header(header, "deploy").unwrap();
+        let summary: Vec<(&str, bool, bool, Option<&str>)> = params
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.required,
+                    p.variadic,
+                    p.default.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("TARGET", true, false, None),
+                ("ENV", false, false, Some("prod")),
+                ("RETRIES", false, false, Some("3")),
+                ("LABEL", false, false, None),
+                ("FLAGS", false, true, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_justfile_params_reads_matching_recipe() {
         let dir = tempdir().unwrap();
         let path = dir.path().join("justfile");
         std::fs::write(
@@ -265,12 +406,12 @@ test TEST ENV='prod':
         )
         .unwrap();
 
-        let args = parse_justfile_required_args(&path, "test").unwrap();
+        let args = required_names(parse_justfile_params(&path, "test").unwrap().unwrap());
         assert_eq!(args, vec!["TEST".to_string()]);
     }
 
     #[test]
-    fn parse_justfile_required_args_with_colon_in_default() {
+    fn parse_justfile_params_required_with_colon_in_default() {
         let dir = tempdir().unwrap();
         let path = dir.path().join("justfile");
         std::fs::write(
@@ -282,7 +423,66 @@ deploy ENV='prod:blue' TARGET:
         )
         .unwrap();
 
-        let args = parse_justfile_required_args(&path, "deploy").unwrap();
+        let args = required_names(parse_justfile_params(&path, "deploy").unwrap().unwrap());
         assert_eq!(args, vec!["TARGET".to_string()]);
     }
+
+    #[test]
+    fn params_for_taskfile_task_returns_required_variables() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Taskfile.yml");
+        std::fs::write(
+            &path,
+            "version: '3'\ntasks:\n  deploy:\n    requires:\n      vars: [ENV]\n",
+        )
+        .unwrap();
+        let detection = detection_at(Runner::Taskfile, path);
+
+        let params = params_for_task(&detection, "deploy").unwrap().unwrap();
+        assert_eq!(params, vec![required_variable("ENV".to_string())]);
+        assert_eq!(params[0].kind, ParamKind::Variable);
+        assert_eq!(
+            params_for_task(&detection, "remote:deploy").unwrap(),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn params_for_task_leaves_unknown_recipes_to_just_fallback() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("justfile"), "deploy:\n  echo parent\n").unwrap();
+        let child = dir.path().join("service");
+        std::fs::create_dir(&child).unwrap();
+        let path = child.join("justfile");
+        std::fs::write(&path, "set fallback := true\n\nbuild:\n  cargo build\n").unwrap();
+        let mut detection = detection_at(Runner::Justfile, path.clone());
+
+        assert_eq!(params_for_task(&detection, "deploy").unwrap(), Some(vec![]));
+
+        std::fs::write(&path, "build:\n  cargo build\n").unwrap();
+        detection.runner_file = path;
+        assert_eq!(params_for_task(&detection, "deploy").unwrap(), None);
+    }
+
+    #[test]
+    fn params_for_task_distinguishes_missing_recipe_from_no_params() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(
+            &path,
+            "import 'extra.just'\nalias b := build\n\nbuild:\n  cargo build\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("extra.just"), "lint:\n  cargo clippy\n").unwrap();
+        let detection = detection_at(Runner::Justfile, path);
+
+        assert_eq!(params_for_task(&detection, "build").unwrap(), Some(vec![]));
+        assert_eq!(params_for_task(&detection, "b").unwrap(), Some(vec![]));
+        assert_eq!(params_for_task(&detection, "lint").unwrap(), Some(vec![]));
+        assert_eq!(
+            params_for_task(&detection, "docs::build").unwrap(),
+            Some(vec![])
+        );
+        assert_eq!(params_for_task(&detection, "biuld").unwrap(), None);
+    }
 }
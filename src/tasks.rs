@@ -1,16 +1,26 @@
 use inquire::error::InquireError;
+use std::collections::HashMap;
 use std::fmt;
 use terminal_size::{Width, terminal_size};
 
 use crate::RtError;
-use crate::detect::{Runner, runner_command};
-use crate::exec::base_command;
+use crate::config::UiConfig;
+use crate::detect::{Detection, Runner, runner_command, runner_name};
+use crate::exec::{
+    Invocation, ToolResolver, WhichResolver, base_command, format_program_args, invocation,
+    runner_file_args,
+};
+use crate::history::HistoryRecord;
 use crate::parser;
+use crate::prompt;
+use crate::term::paint;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
 pub struct TaskItem {
     pub name: String,
     pub description: Option<String>,
+    /// The group the runner files the task under, if any.
+    pub group: Option<String>,
 }
 
 impl fmt::Display for TaskItem {
@@ -22,14 +32,63 @@ impl fmt::Display for TaskItem {
     }
 }
 
-/// Prompts the user to select a task from the given runner's task list.
-pub fn select_task(runner: Runner) -> Result<Option<String>, RtError> {
-    let tasks = list_tasks(runner)?;
+/// Fails with `NoTasks` when the runner listed successfully but its file defines no tasks.
+pub fn require_tasks(
+    detection: &Detection,
+    tasks: Vec<TaskItem>,
+) -> Result<Vec<TaskItem>, RtError> {
     if tasks.is_empty() {
         return Err(RtError::NoTasks {
-            tool: runner_command(runner),
+            tool: detection.command(),
+            path: detection.runner_file.clone(),
         });
     }
+    Ok(tasks)
+}
+
+/// Serializes `tasks` as `[{"name", "description", "group", "runner"}]` for `--list --json`.
+pub fn tasks_json(detection: &Detection, tasks: &[TaskItem]) -> serde_json::Value {
+    #[derive(serde::Serialize)]
+    struct TaskJson<'a> {
+        #[serde(flatten)]
+        task: &'a TaskItem,
+        runner: &'static str,
+    }
+
+    let runner = runner_name(detection.runner);
+    let entries: Vec<TaskJson<'_>> = tasks.iter().map(|task| TaskJson { task, runner }).collect();
+    serde_json::to_value(entries).unwrap_or_default()
+}
+
+/// Serializes `--group-by` groups as `[{"group", "tasks": [...]}]` in heading order, with
+/// `"group": null` for ungrouped tasks.
+pub fn grouped_tasks_json(
+    detection: &Detection,
+    groups: &[(Option<String>, Vec<TaskItem>)],
+) -> serde_json::Value {
+    groups
+        .iter()
+        .map(|(group, members)| {
+            serde_json::json!({
+                "group": group,
+                "tasks": tasks_json(detection, members),
+            })
+        })
+        .collect()
+}
+
+/// Prompts the user to select a task from the detected runner's task list.
+///
+/// Tasks found in `recent_runs` (history records, newest first) are listed first. When the
+/// runner groups tasks (just's `[group]`), each one shows its group, and typing a group name
+/// also finds its tasks.
+pub fn select_task(
+    detection: &Detection,
+    ui: &UiConfig,
+    recent_runs: &[HistoryRecord],
+) -> Result<Option<TaskItem>, RtError> {
+    let tasks = require_tasks(detection, list_tasks(detection)?)?;
+    let tasks = order_by_recency(detection, tasks, recent_runs);
 
     let max_name_len = tasks
         .iter()
@@ -40,40 +99,110 @@ pub fn select_task(runner: Runner) -> Result<Option<String>, RtError> {
     let term_width = terminal_size()
         .map(|(Width(w), _)| w as usize)
         .unwrap_or(80);
-    let show_description = term_width >= 60;
+    let show_description = !ui.compact && term_width >= 60;
+    let group_width = tasks
+        .iter()
+        .filter_map(|t| t.group.as_ref())
+        .map(|group| group_label(group).chars().count())
+        .max()
+        .unwrap_or(0);
 
     let items: Vec<TaskChoice> = tasks
         .into_iter()
-        .map(|t| TaskChoice::new(t, max_name_len, show_description, term_width))
+        .map(|t| TaskChoice::new(t, max_name_len, group_width, show_description, term_width))
         .collect();
 
     let items_len = items.len();
 
-    match inquire::Select::new("Select task", items)
-        .with_page_size(10)
+    match prompt::select("Select task", items, ui)
         .with_scorer(&move |input, option, string_value, idx| {
             let _ = string_value;
-            score_task(input, &option.name, idx, items_len)
+            score_task(input, &option.task.name, idx, items_len)
+                .or_else(|| score_group(input, option.task.group.as_deref()?, idx, items_len))
         })
         .prompt()
     {
-        Ok(item) => Ok(Some(item.name)),
+        Ok(item) => Ok(Some(item.task)),
         Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(None),
-        Err(err) => Err(RtError::Prompt(err)),
+        Err(err) => Err(err.into()),
     }
 }
+
+/// Moves tasks that `recent_runs` (newest first) ran to the front, most recent first.
+///
+/// A run belongs to the task whose command line it starts with, so runs with extra arguments
+/// still count. Tasks never run keep their original order after the recent ones.
+fn order_by_recency(
+    detection: &Detection,
+    mut tasks: Vec<TaskItem>,
+    recent_runs: &[HistoryRecord],
+) -> Vec<TaskItem> {
+    if recent_runs.is_empty() {
+        return tasks;
+    }
+    let invocations: Vec<(String, Invocation)> = tasks
+        .iter()
+        .filter_map(|task| {
+            invocation(detection, &task.name, &[])
+                .ok()
+                .map(|invocation| (task.name.clone(), invocation))
+        })
+        .collect();
+
+    let mut recent: Vec<&str> = Vec::new();
+    for run in recent_runs {
+        // When one task's command line extends another's, the longer one ran.
+        let task = invocations
+            .iter()
+            .filter(|(_, invocation)| {
+                invocation.program == run.program && run.args.starts_with(&invocation.args)
+            })
+            .max_by_key(|(_, invocation)| invocation.args.len());
+        if let Some((name, _)) = task
+            && !recent.contains(&name.as_str())
+        {
+            recent.push(name);
+        }
+    }
+
+    let rank: HashMap<String, usize> = recent
+        .iter()
+        .enumerate()
+        .map(|(rank, name)| ((*name).to_string(), rank))
+        .collect();
+    tasks.sort_by_key(|task| rank.get(&task.name).copied().unwrap_or(usize::MAX));
+    tasks
+}
+
 #[derive(Debug, Clone)]
 struct TaskChoice {
-    name: String,
+    task: TaskItem,
     display: String,
 }
 
+fn group_label(group: &str) -> String {
+    format!("[{group}]")
+}
+
 impl TaskChoice {
-    fn new(task: TaskItem, name_width: usize, show_description: bool, term_width: usize) -> Self {
+    /// `group_width` is the widest `[group]` label, or 0 when no task has a group.
+    fn new(
+        task: TaskItem,
+        name_width: usize,
+        group_width: usize,
+        show_description: bool,
+        term_width: usize,
+    ) -> Self {
+        let group_column = if group_width == 0 {
+            String::new()
+        } else {
+            let label = task.group.as_deref().map(group_label).unwrap_or_default();
+            format!("{label:group_width$}  ")
+        };
         let display = match (&task.description, show_description) {
             (Some(desc), true) => {
                 // "  -  " is 5 chars, inquire prompt prefix is ~4 chars
-                let prefix_len = name_width + 5 + 4;
+                let prefix_len = group_column.chars().count() + name_width + 5 + 4;
                 let max_desc_len = term_width.saturating_sub(prefix_len);
                 let truncated_desc = if desc.chars().count() > max_desc_len && max_desc_len > 3 {
                     format!(
@@ -84,18 +213,15 @@ impl TaskChoice {
                     desc.clone()
                 };
                 format!(
-                    "{:width$}  -  {}",
+                    "{group_column}{:width$}  -  {}",
                     task.name,
                     truncated_desc,
                     width = name_width
                 )
             }
-            _ => task.name.clone(),
+            _ => format!("{group_column}{}", task.name),
         };
-        Self {
-            name: task.name,
-            display,
-        }
+        Self { task, display }
     }
 }
 
@@ -105,6 +231,13 @@ impl fmt::Display for TaskChoice {
     }
 }
 
+/// Scores a task whose name doesn't match by its group, below every name match.
+fn score_group(input: &str, group: &str, idx: usize, items_len: usize) -> Option<i64> {
+    let input = input.trim().to_ascii_lowercase();
+    (!input.is_empty() && group.to_ascii_lowercase().contains(&input))
+        .then(|| items_len.saturating_sub(idx) as i64)
+}
+
 fn score_task(input: &str, task_name: &str, idx: usize, items_len: usize) -> Option<i64> {
     let input = input.trim();
     if input.is_empty() {
@@ -129,26 +262,329 @@ fn score_task(input: &str, task_name: &str, idx: usize, items_len: usize) -> Opt
     Some(boost.saturating_add(items_len.saturating_sub(idx) as i64))
 }
 
-/// Lists tasks for the given runner by invoking its list command.
-fn list_tasks(runner: Runner) -> Result<Vec<TaskItem>, RtError> {
+/// How `--list --group-by` splits tasks under headings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The runner's own groups, such as just's `[group]` attribute.
+    Group,
+    /// The part of the name before the first `:` or `-`.
+    Prefix,
+}
+
+impl std::str::FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "group" => Ok(Self::Group),
+            "prefix" => Ok(Self::Prefix),
+            other => Err(format!(
+                "unknown grouping `{other}`, expected `group` or `prefix`"
+            )),
+        }
+    }
+}
+
+/// Splits tasks into headed groups in first-seen order, with ungrouped tasks (`None`) last.
+pub fn group_tasks(
+    tasks: Vec<TaskItem>,
+    group_by: GroupBy,
+) -> Vec<(Option<String>, Vec<TaskItem>)> {
+    let mut groups: Vec<(Option<String>, Vec<TaskItem>)> = Vec::new();
+    let mut ungrouped = Vec::new();
+    for task in tasks {
+        let key = match group_by {
+            GroupBy::Group => task.group.clone(),
+            GroupBy::Prefix => task
+                .name
+                .split_once([':', '-'])
+                .map(|(prefix, _)| prefix.to_string())
+                .filter(|prefix| !prefix.is_empty()),
+        };
+        match key {
+            Some(key) => match groups
+                .iter_mut()
+                .find(|(name, _)| name.as_deref() == Some(key.as_str()))
+            {
+                Some((_, members)) => members.push(task),
+                None => groups.push((Some(key), vec![task])),
+            },
+            None => ungrouped.push(task),
+        }
+    }
+    if !ungrouped.is_empty() {
+        groups.push((None, ungrouped));
+    }
+    groups
+}
+
+/// Formats tasks as plain `name  description` lines, names padded to align descriptions.
+///
+/// `compact` prints names only; `color` dims the descriptions.
+pub fn format_task_lines(tasks: &[TaskItem], indent: &str, compact: bool, color: bool) -> String {
+    let width = tasks
+        .iter()
+        .map(|task| task.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    tasks
+        .iter()
+        .map(
+            |task| match task.description.as_ref().filter(|_| !compact) {
+                Some(desc) => format!(
+                    "{indent}{:<width$}  {}\n",
+                    task.name,
+                    paint(desc, "2", color)
+                ),
+                None => format!("{indent}{}\n", task.name),
+            },
+        )
+        .collect()
+}
+
+/// A `{field}` placeholder in a `--format` template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateField {
+    Name,
+    Description,
+    Group,
+    Params,
+}
+
+/// A piece of a parsed `--format` template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    Literal(String),
+    Field(TemplateField),
+}
+
+/// Splits a `--format` template into literal text and `{field}` placeholders.
+///
+/// `\t`, `\n` and `\\` are unescaped, and `{{`/`}}` produce literal braces.
+pub fn parse_list_template(format: &str) -> Result<Vec<TemplatePart>, RtError> {
+    let invalid = |message: String| RtError::InvalidFormat { message };
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('t') => literal.push('\t'),
+                Some('n') => literal.push('\n'),
+                Some('\\') => literal.push('\\'),
+                Some(other) => {
+                    literal.push('\\');
+                    literal.push(other);
+                }
+                None => literal.push('\\'),
+            },
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => name.push(ch),
+                        None => return Err(invalid(format!("unclosed `{{{name}`"))),
+                    }
+                }
+                let field = match name.as_str() {
+                    "name" => TemplateField::Name,
+                    "description" => TemplateField::Description,
+                    "group" => TemplateField::Group,
+                    "params" => TemplateField::Params,
+                    _ => {
+                        return Err(invalid(format!(
+                            "unknown placeholder `{{{name}}}`; use {{name}}, {{description}}, {{group}} or {{params}}"
+                        )));
+                    }
+                };
+                if !literal.is_empty() {
+                    parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(TemplatePart::Field(field));
+            }
+            '}' => {
+                return Err(invalid(
+                    "unmatched `}`; write `}}` for a literal brace".into(),
+                ));
+            }
+            ch => literal.push(ch),
+        }
+    }
+    if !literal.is_empty() {
+        parts.push(TemplatePart::Literal(literal));
+    }
+    Ok(parts)
+}
+
+/// Renders one task; a missing description or group renders as empty text.
+pub fn render_list_template(template: &[TemplatePart], item: &TaskItem, params: &str) -> String {
+    template
+        .iter()
+        .map(|part| match part {
+            TemplatePart::Literal(text) => text.as_str(),
+            TemplatePart::Field(TemplateField::Name) => item.name.as_str(),
+            TemplatePart::Field(TemplateField::Description) => {
+                item.description.as_deref().unwrap_or_default()
+            }
+            TemplatePart::Field(TemplateField::Group) => item.group.as_deref().unwrap_or_default(),
+            TemplatePart::Field(TemplateField::Params) => params,
+        })
+        .collect()
+}
+
+/// Serializes tasks for `--dump-tasks`: one `name<TAB>description` line each, sorted by name so
+/// a snapshot only changes when tasks do.
+pub fn task_snapshot(tasks: &[TaskItem]) -> String {
+    let mut lines: Vec<String> = tasks
+        .iter()
+        .map(|task| {
+            let description = task
+                .description
+                .as_deref()
+                .unwrap_or("")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{}\t{description}\n", task.name)
+        })
+        .collect();
+    lines.sort();
+    lines.dedup();
+    lines.concat()
+}
+
+/// Compares two snapshots, returning `- line` for lines only in `expected` and `+ line` for lines
+/// only in `actual`, in name order. Empty when they match.
+pub fn snapshot_diff(expected: &str, actual: &str) -> Vec<String> {
+    let mut expected: Vec<&str> = expected.lines().filter(|line| !line.is_empty()).collect();
+    let mut actual: Vec<&str> = actual.lines().filter(|line| !line.is_empty()).collect();
+    expected.sort_unstable();
+    actual.sort_unstable();
+
+    let mut diff = Vec::new();
+    let (mut old, mut new) = (0, 0);
+    loop {
+        match (expected.get(old), actual.get(new)) {
+            (Some(a), Some(b)) if a == b => {
+                old += 1;
+                new += 1;
+            }
+            (Some(a), b) if b.is_none_or(|b| a < b) => {
+                diff.push(format!("- {a}"));
+                old += 1;
+            }
+            (_, Some(b)) => {
+                diff.push(format!("+ {b}"));
+                new += 1;
+            }
+            _ => return diff,
+        }
+    }
+}
+
+/// Returns the candidate closest to a mistyped `task`, when one is plausibly what was meant.
+pub fn suggest_task<'a>(task: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let max_distance = (task.chars().count() / 3).max(2);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(task, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Lists tasks for the detected runner by invoking its list command.
+///
+/// Nothing is cached between calls, so an edit that keeps the runner file's mtime (such as a
+/// `git checkout`) is always seen.
+pub fn list_tasks(detection: &Detection) -> Result<Vec<TaskItem>, RtError> {
+    list_tasks_with(detection, &WhichResolver)
+}
+
+fn list_tasks_with(
+    detection: &Detection,
+    resolver: &dyn ToolResolver,
+) -> Result<Vec<TaskItem>, RtError> {
+    let listing = raw_listing_with(detection, resolver)?;
+    let mut tasks = parser::parse_tasks(detection.runner, &detection.runner_file, &listing.output);
+    if detection.runner == Runner::PackageJson && detection.workspaces {
+        tasks.extend(parser::package_workspace_tasks(&detection.runner_file));
+    }
+    Ok(tasks)
+}
+
+/// The unparsed task listing our parsers consume, and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawListing {
+    /// The list command that produced the output, or the runner file that was read.
+    pub source: String,
+    pub output: String,
+}
+
+/// Returns the output of the first list command variant that succeeds, without parsing it.
+pub fn raw_listing(detection: &Detection) -> Result<RawListing, RtError> {
+    raw_listing_with(detection, &WhichResolver)
+}
+
+fn raw_listing_with(
+    detection: &Detection,
+    resolver: &dyn ToolResolver,
+) -> Result<RawListing, RtError> {
+    let runner = detection.runner;
+    if lists_from_runner_file(detection) {
+        let content = std::fs::read_to_string(&detection.runner_file).map_err(RtError::Io)?;
+        return Ok(RawListing {
+            source: detection.runner_file.display().to_string(),
+            output: content,
+        });
+    }
+
     let mut last_status = 2;
     for args in list_command_variants(runner) {
-        let current_dir = std::env::current_dir().map_err(RtError::Io)?;
-        let mut command = base_command(runner)?;
+        let current_dir = match detection.runner_file.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => std::env::current_dir().map_err(RtError::Io)?,
+        };
+        let mut command = base_command(runner, resolver)?;
+        command.args(runner_file_args(detection)).args(args);
+        let source = format_command(&command);
         let output = command
-            .args(args)
             .current_dir(&current_dir)
             .output()
             .map_err(RtError::Spawn)?;
 
         let status = output.status.code().unwrap_or(2);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if status == 0 {
-            return Ok(parser::parse_tasks(runner, &stdout));
-        }
-
-        if runner == Runner::Makefile && !stdout.trim().is_empty() {
-            return Ok(parser::parse_tasks(runner, &stdout));
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        // make exits non-zero with `-q` when targets are out of date, but the database is complete.
+        if status == 0 || (runner == Runner::Makefile && !stdout.trim().is_empty()) {
+            return Ok(RawListing {
+                source,
+                output: stdout,
+            });
         }
 
         last_status = status;
@@ -160,25 +596,390 @@ fn list_tasks(runner: Runner) -> Result<Vec<TaskItem>, RtError> {
     })
 }
 
+fn format_command(command: &std::process::Command) -> String {
+    let args: Vec<String> = command
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    format_program_args(&command.get_program().to_string_lossy(), &args)
+}
+
+/// Returns whether tasks are read from the runner file itself instead of a list command.
+///
+/// `Makefile.am` has no Makefile for `make -qp` to read until `./configure` runs.
+fn lists_from_runner_file(detection: &Detection) -> bool {
+    matches!(
+        detection.runner,
+        Runner::Procfile | Runner::Deno | Runner::Composer | Runner::PackageJson
+    ) || detection.is_automake_source()
+}
+
 /// Returns possible command variants to list tasks for the given runner.
 fn list_command_variants(runner: Runner) -> Vec<Vec<&'static str>> {
     match runner {
         Runner::Justfile => vec![vec!["--list", "--unsorted"]],
         Runner::Taskfile => vec![vec!["--list-all"]],
         Runner::Maskfile => vec![vec!["--introspect"]],
-        Runner::Mise => vec![vec!["tasks", "ls", "--json"]],
+        // Older mise has no `tasks ls`, and older still no `--json`.
+        Runner::Mise => vec![
+            vec!["tasks", "ls", "--json"],
+            vec!["tasks", "--json"],
+            vec!["tasks"],
+        ],
         Runner::CargoMake => vec![
             vec!["make", "--list-all-steps"],
             vec!["make", "--list-all"],
             vec!["make", "--list"],
         ],
         Runner::Makefile => vec![vec!["-rR", "-qp"], vec!["-qp"]],
+        Runner::Rake => vec![vec!["-T"]],
+        Runner::Earthly => vec![vec!["ls"]],
+        Runner::Mage => vec![vec!["-l"]],
+        Runner::Procfile | Runner::Deno | Runner::Composer | Runner::PackageJson => Vec::new(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{FakeResolver, detection, detection_at};
+
+    fn run(program: &str, args: &[&str]) -> HistoryRecord {
+        HistoryRecord {
+            schema_version: 3,
+            timestamp: "2026-02-21T12:00:00+09:00".to_string(),
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            working_directory: "/repo".to_string(),
+            exit_code: 0,
+            duration_ms: None,
+            hashed: false,
+            desc: None,
+        }
+    }
+
+    #[test]
+    fn order_by_recency_puts_recent_tasks_first() {
+        let mut tasks: Vec<TaskItem> = ["build", "lint", "test", "deploy"]
+            .into_iter()
+            .map(|name| task(name, None))
+            .collect();
+        tasks[2].description = Some("run tests".to_string());
+        let recent_runs = vec![
+            run("just", &["test", "--", "unit"]),
+            run("make", &["lint"]),
+            run("just", &["deploy", "prod"]),
+            run("just", &["test"]),
+        ];
+
+        let ordered = order_by_recency(&detection(Runner::Justfile), tasks, &recent_runs);
+
+        let names: Vec<&str> = ordered.iter().map(|task| task.name.as_str()).collect();
+        assert_eq!(names, vec!["test", "deploy", "build", "lint"]);
+        assert_eq!(ordered[0].description.as_deref(), Some("run tests"));
+    }
+
+    #[test]
+    fn list_tasks_reports_missing_tool() {
+        let err = list_tasks_with(&detection(Runner::Justfile), &FakeResolver(None)).unwrap_err();
+        match err {
+            RtError::ToolMissing { tool } => assert_eq!(tool, "just"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn list_tasks_reports_failed_list_command() {
+        let err = list_tasks_with(&detection(Runner::Justfile), &FakeResolver(Some("false")))
+            .unwrap_err();
+        match err {
+            RtError::ListFailed { tool, status } => {
+                assert_eq!(tool, "just");
+                assert_eq!(status, 1);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn raw_listing_returns_unparsed_output_and_command() {
+        let listing =
+            raw_listing_with(&detection(Runner::Justfile), &FakeResolver(Some("echo"))).unwrap();
+        assert_eq!(listing.source, "echo --list --unsorted");
+        assert_eq!(listing.output, "--list --unsorted\n");
+    }
+
+    #[test]
+    fn tasks_json_lists_name_description_and_runner() {
+        let tasks = vec![
+            TaskItem {
+                name: "build".to_string(),
+                description: Some("Build it".to_string()),
+                group: None,
+            },
+            TaskItem {
+                name: "test".to_string(),
+                description: None,
+                group: Some("ci".to_string()),
+            },
+        ];
+        assert_eq!(
+            tasks_json(&detection(Runner::Justfile), &tasks),
+            serde_json::json!([
+                {"name": "build", "description": "Build it", "group": null, "runner": "just"},
+                {"name": "test", "description": null, "group": "ci", "runner": "just"},
+            ])
+        );
+        assert_eq!(
+            tasks_json(&detection(Runner::Makefile), &[]),
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn grouped_tasks_json_nests_tasks_under_their_group() {
+        let task = |name: &str, group: Option<&str>| TaskItem {
+            name: name.to_string(),
+            description: None,
+            group: group.map(str::to_string),
+        };
+        let groups = group_tasks(
+            vec![task("lint", Some("ci")), task("build", None)],
+            GroupBy::Group,
+        );
+        assert_eq!(
+            grouped_tasks_json(&detection(Runner::Justfile), &groups),
+            serde_json::json!([
+                {"group": "ci", "tasks": [
+                    {"name": "lint", "description": null, "group": "ci", "runner": "just"},
+                ]},
+                {"group": null, "tasks": [
+                    {"name": "build", "description": null, "group": null, "runner": "just"},
+                ]},
+            ])
+        );
+    }
+
+    #[test]
+    fn empty_justfile_reports_file_without_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("justfile");
+        std::fs::write(&path, "").unwrap();
+        let detection = detection_at(Runner::Justfile, path.clone());
+
+        let tasks = list_tasks_with(&detection, &FakeResolver(Some("true"))).unwrap();
+        match require_tasks(&detection, tasks).unwrap_err() {
+            RtError::NoTasks {
+                tool,
+                path: err_path,
+            } => {
+                assert_eq!(tool, "just");
+                assert_eq!(err_path, path);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn empty_makefile_reports_file_without_tasks() {
+        if which::which("make").is_err() {
+            eprintln!("skipping: `make` is not installed");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Makefile");
+        std::fs::write(&path, "").unwrap();
+        let detection =
+            crate::detect::detect_runner(dir.path(), &crate::config::RunnersConfig::default())
+                .unwrap();
+
+        let tasks = list_tasks(&detection).unwrap();
+        let err = require_tasks(&detection, tasks).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "runner file {path:?} defines no tasks; add one (e.g. with `rt --edit`) or check it is the file make reads"
+            )
+        );
+    }
+
+    #[test]
+    fn list_tasks_ignores_echoed_arguments_from_resolved_program() {
+        let tasks =
+            list_tasks_with(&detection(Runner::Taskfile), &FakeResolver(Some("echo"))).unwrap();
+        assert!(tasks.is_empty());
+    }
+
+    #[test]
+    fn list_tasks_sees_edits_that_keep_an_older_mtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.json");
+        let write = |content: &str| {
+            std::fs::write(&path, content).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(std::time::UNIX_EPOCH)
+                .unwrap();
+        };
+        let detection = detection_at(Runner::PackageJson, path.clone());
+        let names = || -> Vec<String> {
+            list_tasks_with(&detection, &FakeResolver(None))
+                .unwrap()
+                .into_iter()
+                .map(|task| task.name)
+                .collect()
+        };
+
+        write(r#"{"scripts": {"build": "tsc"}}"#);
+        assert_eq!(names(), vec!["build".to_string()]);
+        write(r#"{"scripts": {"test": "vitest"}}"#);
+        assert_eq!(names(), vec!["test".to_string()]);
+    }
+
+    fn task(name: &str, group: Option<&str>) -> TaskItem {
+        TaskItem {
+            name: name.to_string(),
+            description: None,
+            group: group.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn task_snapshot_is_sorted_and_diffable() {
+        let mut deploy = task("deploy", None);
+        deploy.description = Some("Ship it\n  to prod".to_string());
+        let snapshot = task_snapshot(&[task("test", None), deploy, task("build", None)]);
+        assert_eq!(snapshot, "build\t\ndeploy\tShip it to prod\ntest\t\n");
+        assert!(snapshot_diff(&snapshot, &snapshot).is_empty());
+
+        let renamed = task_snapshot(&[task("check", None), task("build", None)]);
+        assert_eq!(
+            snapshot_diff(&snapshot, &renamed),
+            vec!["+ check\t", "- deploy\tShip it to prod", "- test\t",]
+        );
+    }
+
+    #[test]
+    fn group_tasks_by_group_puts_ungrouped_last() {
+        let tasks = vec![
+            task("default", None),
+            task("clippy", Some("lint")),
+            task("test", Some("test")),
+            task("fmt", Some("lint")),
+        ];
+        let grouped = group_tasks(tasks, GroupBy::Group);
+        let groups: Vec<(Option<&str>, Vec<&str>)> = grouped
+            .iter()
+            .map(|(name, members)| {
+                (
+                    name.as_deref(),
+                    members.iter().map(|task| task.name.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (Some("lint"), vec!["clippy", "fmt"]),
+                (Some("test"), vec!["test"]),
+                (None, vec!["default"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn group_tasks_by_prefix_splits_on_colon_or_dash() {
+        let tasks = vec![
+            task("db:migrate", None),
+            task("test-unit", None),
+            task("db:seed", None),
+            task("build", None),
+        ];
+        let groups: Vec<Option<String>> = group_tasks(tasks, GroupBy::Prefix)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            groups,
+            vec![Some("db".to_string()), Some("test".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn format_task_lines_aligns_descriptions() {
+        let tasks = vec![
+            TaskItem {
+                name: "build".to_string(),
+                description: Some("build it".to_string()),
+                group: None,
+            },
+            task("test-all", None),
+        ];
+        assert_eq!(
+            format_task_lines(&tasks, "  ", false, false),
+            "  build     build it\n  test-all\n"
+        );
+        assert_eq!(
+            format_task_lines(&tasks, "", true, false),
+            "build\ntest-all\n"
+        );
+        assert_eq!(
+            format_task_lines(&tasks, "", false, true),
+            "build     \x1b[2mbuild it\x1b[0m\ntest-all\n"
+        );
+    }
+
+    #[test]
+    fn taskfile_includes_list_and_run_namespaced_tasks() {
+        if which::which("task").is_err() {
+            eprintln!("skipping: `task` is not installed");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("docker")).unwrap();
+        std::fs::write(
+            dir.path().join("Taskfile.yml"),
+            "version: '3'\n\nincludes:\n  docker: ./docker/Taskfile.yml\n\ntasks:\n  build:\n    desc: Build the project\n    cmds:\n      - echo build\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("docker").join("Taskfile.yml"),
+            "version: '3'\n\ntasks:\n  build:\n    desc: Build the image\n    cmds:\n      - touch {{.ROOT_DIR}}/image-built\n",
+        )
+        .unwrap();
+        let detection =
+            crate::detect::detect_runner(dir.path(), &crate::config::RunnersConfig::default())
+                .unwrap();
+
+        let names: Vec<String> = list_tasks(&detection)
+            .unwrap()
+            .into_iter()
+            .map(|task| task.name)
+            .collect();
+        assert_eq!(names, vec!["build", "docker:build"]);
+
+        let result = crate::exec::run(
+            &detection,
+            "docker:build",
+            &[],
+            dir.path(),
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(result.exit_code, 0);
+        assert_eq!(result.args, vec!["docker:build".to_string()]);
+        assert!(dir.path().join("image-built").exists());
+    }
+
+    #[test]
+    fn suggest_task_picks_closest_plausible_name() {
+        let candidates = vec!["build".to_string(), "bench".to_string(), "test".to_string()];
+        assert_eq!(suggest_task("biuld", &candidates), Some("build"));
+        assert_eq!(suggest_task("tset", &candidates), Some("test"));
+        assert_eq!(suggest_task("deploy", &candidates), None);
+    }
 
     #[test]
     fn score_task_prefers_exact_over_prefix() {
@@ -189,6 +990,35 @@ mod tests {
         assert!(exact > prefix);
     }
 
+    #[test]
+    fn task_choice_shows_group_column_only_when_groups_exist() {
+        let mut clippy = task("clippy", Some("lint"));
+        clippy.description = Some("run clippy".to_string());
+        let width = group_label("lint").chars().count();
+
+        assert_eq!(
+            TaskChoice::new(clippy.clone(), 6, width, true, 80).display,
+            "[lint]  clippy  -  run clippy"
+        );
+        assert_eq!(
+            TaskChoice::new(task("build", None), 6, width, true, 80).display,
+            "        build"
+        );
+        assert_eq!(
+            TaskChoice::new(clippy, 6, 0, true, 80).display,
+            "clippy  -  run clippy"
+        );
+    }
+
+    #[test]
+    fn score_group_ranks_below_name_matches() {
+        let by_group = score_group("lint", "lint", 0, 2).unwrap();
+        let by_name = score_task("lint", "lint-all", 1, 2).unwrap();
+        assert!(by_name > by_group);
+        assert_eq!(score_group("", "lint", 0, 2), None);
+        assert_eq!(score_group("test", "lint", 0, 2), None);
+    }
+
     #[test]
     fn score_task_filters_non_matches() {
         let items_len = 1;
@@ -214,4 +1044,46 @@ mod tests {
         assert!(exact.is_some());
         assert!(fuzzy.is_none());
     }
+
+    fn render(format: &str, item: &TaskItem) -> String {
+        render_list_template(&parse_list_template(format).unwrap(), item, "target [jobs]")
+    }
+
+    #[test]
+    fn list_template_substitutes_fields_and_escapes() {
+        let item = TaskItem {
+            name: "build".to_string(),
+            description: Some("compile".to_string()),
+            group: Some("dev".to_string()),
+        };
+        assert_eq!(render(r"{name}\t{description}", &item), "build\tcompile");
+        assert_eq!(
+            render("{group}/{name}: {params}", &item),
+            "dev/build: target [jobs]"
+        );
+        assert_eq!(render(r"{{{name}}}\n\\", &item), "{build}\n\\");
+    }
+
+    #[test]
+    fn list_template_renders_missing_fields_as_empty() {
+        let item = TaskItem {
+            name: "lint".to_string(),
+            description: None,
+            group: None,
+        };
+        assert_eq!(render(r"{name}\t{description}|{group}", &item), "lint\t|");
+    }
+
+    #[test]
+    fn list_template_rejects_unknown_and_unclosed_placeholders() {
+        for format in ["{name} {desc}", "{name", "name}"] {
+            assert!(
+                matches!(
+                    parse_list_template(format),
+                    Err(RtError::InvalidFormat { .. })
+                ),
+                "{format}"
+            );
+        }
+    }
 }
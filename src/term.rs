@@ -0,0 +1,97 @@
+use std::fmt;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static COLOR_DISABLED: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+static QUIET_SUMMARY: AtomicBool = AtomicBool::new(false);
+
+/// Whether stderr is attached to a terminal, so decorations meant for humans are worth printing.
+pub fn stderr_is_terminal() -> bool {
+    std::io::stderr().is_terminal()
+}
+
+/// Whether stdout is attached to a terminal rather than a pipe or file.
+pub fn stdout_is_terminal() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+/// Whether rt can show interactive prompts without consuming input meant for the task.
+///
+/// When stdin is piped, inquire's crossterm backend reads keys from `/dev/tty` instead, so the
+/// piped data is left for the task; prompting only fails when there's no controlling terminal.
+pub fn can_prompt() -> bool {
+    std::io::stdin().is_terminal() || controlling_terminal_available()
+}
+
+#[cfg(unix)]
+fn controlling_terminal_available() -> bool {
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .is_ok()
+}
+
+#[cfg(not(unix))]
+fn controlling_terminal_available() -> bool {
+    false
+}
+
+/// Turns colors off for the rest of the process, for `--no-color`.
+pub fn disable_color() {
+    COLOR_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Silences rt's own notices for the rest of the process, for `--quiet`.
+///
+/// `keep_summary` leaves result lines on, for an explicit `--summary`.
+pub fn enable_quiet(keep_summary: bool) {
+    QUIET.store(true, Ordering::Relaxed);
+    QUIET_SUMMARY.store(!keep_summary, Ordering::Relaxed);
+}
+
+/// Whether a finished run's result line may be printed; `--quiet` hides it.
+pub fn summary_enabled() -> bool {
+    !QUIET_SUMMARY.load(Ordering::Relaxed)
+}
+
+/// Prints one of rt's own non-error messages to stderr, unless `--quiet` is on.
+pub fn notice(message: impl fmt::Display) {
+    if !QUIET.load(Ordering::Relaxed) {
+        eprintln!("{message}");
+    }
+}
+
+/// Whether ANSI colors should be used on a terminal stream; `NO_COLOR` and `--no-color` turn
+/// them off.
+pub fn color_enabled(is_terminal: bool) -> bool {
+    is_terminal
+        && !COLOR_DISABLED.load(Ordering::Relaxed)
+        && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// Wraps `text` in the SGR `code` when `color` is on.
+pub fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_wraps_only_when_color_is_enabled() {
+        assert_eq!(paint("ok", "32", false), "ok");
+        assert_eq!(paint("ok", "32", true), "\x1b[32mok\x1b[0m");
+    }
+
+    #[test]
+    fn color_is_disabled_off_terminal() {
+        assert!(!color_enabled(false));
+    }
+}
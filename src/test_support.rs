@@ -0,0 +1,43 @@
+//! Fixtures shared by the unit tests.
+
+use std::path::PathBuf;
+
+use crate::detect::{Detection, Runner};
+use crate::exec::ToolResolver;
+
+/// Resolves every tool to the given program, or to nothing.
+pub struct FakeResolver(pub Option<&'static str>);
+
+impl ToolResolver for FakeResolver {
+    fn resolve(&self, _tool: &str) -> Option<PathBuf> {
+        self.0.map(PathBuf::from)
+    }
+}
+
+/// Resolves only the named tool, as if it were the one installed.
+pub struct OnlyResolver(pub &'static str);
+
+impl ToolResolver for OnlyResolver {
+    fn resolve(&self, tool: &str) -> Option<PathBuf> {
+        (tool == self.0).then(|| PathBuf::from(tool))
+    }
+}
+
+/// A detection of `runner` whose file isn't read.
+pub fn detection(runner: Runner) -> Detection {
+    Detection {
+        runner,
+        runner_file: PathBuf::from("runner-file"),
+        package_manager: None,
+        explicit_file: false,
+        workspaces: false,
+    }
+}
+
+/// A detection of `runner` from the runner file at `path`.
+pub fn detection_at(runner: Runner, path: impl Into<PathBuf>) -> Detection {
+    Detection {
+        runner_file: path.into(),
+        ..detection(runner)
+    }
+}
@@ -0,0 +1,14 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// The `rt` binary run in `dir`, keeping its history and config under `state` rather than the
+/// user's, with stdin closed.
+pub fn rt(dir: &Path, state: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rt"));
+    command
+        .current_dir(dir)
+        .env("XDG_STATE_HOME", state)
+        .env("XDG_CONFIG_HOME", state)
+        .stdin(Stdio::null());
+    command
+}
@@ -0,0 +1,52 @@
+use std::io::Write;
+use std::process::Stdio;
+
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn justfile_from_stdin_runs_in_the_current_directory() {
+    if which::which("just").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let mut child = common::rt(dir.path(), state.path())
+        .args(["--file", "-", "where"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"where:\n\t@pwd\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    let printed = std::path::PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    assert_eq!(
+        printed.canonicalize().unwrap(),
+        dir.path().canonicalize().unwrap()
+    );
+    // The temporary justfile is gone afterwards, so the run isn't recorded for `--last`.
+    assert!(!state.path().join("rt/history.jsonl").exists());
+}
+
+#[test]
+fn file_rejects_runners_other_than_just() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("Makefile"), "build:\n\t@true\n").unwrap();
+
+    let output = common::rt(dir.path(), dir.path())
+        .args(["--file", "Makefile", "build"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(122));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not supported for make"));
+}
@@ -0,0 +1,40 @@
+use std::process::Stdio;
+
+use tempfile::tempdir;
+
+mod common;
+
+/// Runs `rt` in `dir` with stdout piped and returns its stdout.
+fn run_listing(dir: &std::path::Path, args: &[&str]) -> String {
+    let state = tempdir().unwrap();
+    let output = common::rt(dir, state.path())
+        .args(args)
+        .env_remove("NO_COLOR")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn piped_list_is_plain_with_one_task_per_line() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Makefile"),
+        "build: ## Build everything\n\t@true\ntest:\n\t@true\n",
+    )
+    .unwrap();
+
+    let names = run_listing(dir.path(), &["--list"]);
+    assert_eq!(names, "build\ntest\n");
+
+    let verbose = run_listing(dir.path(), &["--list", "--verbose"]);
+    assert!(!verbose.contains('\x1b'), "{verbose:?}");
+    assert_eq!(verbose.lines().count(), 2);
+    assert!(verbose.starts_with("build"));
+}
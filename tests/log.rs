@@ -0,0 +1,53 @@
+use std::process::Output;
+
+use tempfile::{TempDir, tempdir};
+
+mod common;
+
+fn rt(dir: &TempDir, args: &[&str]) -> Output {
+    let state = tempdir().unwrap();
+    common::rt(dir.path(), state.path())
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// The lines of `build.log`, sorted, since stdout and stderr are read on separate threads.
+fn log_lines(dir: &TempDir) -> Vec<String> {
+    let mut lines: Vec<String> = std::fs::read_to_string(dir.path().join("build.log"))
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn log_shows_output_and_writes_it_to_the_file() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Makefile"),
+        "build:\n\t@echo compiling\n\t@echo warning >&2\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("build.log"), "stale\n").unwrap();
+
+    let output = rt(&dir, &["--log", "build.log", "build"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "compiling\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning\n"));
+    assert_eq!(log_lines(&dir), ["compiling", "warning"]);
+
+    let output = rt(&dir, &["--log", "build.log", "--log-append", "build"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        log_lines(&dir),
+        ["compiling", "compiling", "warning", "warning"]
+    );
+}
@@ -0,0 +1,29 @@
+use std::process::Output;
+
+use tempfile::tempdir;
+
+mod common;
+
+fn rt_last(extra: &[&str]) -> Output {
+    let dir = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    common::rt(dir.path(), state.path())
+        .arg("--last")
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn quiet_silences_the_empty_history_notice() {
+    let loud = rt_last(&[]);
+    assert_eq!(loud.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&loud.stderr),
+        "rt: no history to rerun\n"
+    );
+
+    let quiet = rt_last(&["--quiet", "--verbose"]);
+    assert_eq!(quiet.status.code(), Some(0));
+    assert!(quiet.stderr.is_empty());
+}
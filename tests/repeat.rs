@@ -0,0 +1,62 @@
+use std::process::Output;
+
+use tempfile::{TempDir, tempdir};
+
+mod common;
+
+/// A Makefile whose `flaky` target fails on its second run.
+fn flaky_project() -> TempDir {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Makefile"),
+        "flaky:\n\t@echo run >> runs.txt\n\t@test $$(wc -l < runs.txt) -ne 2\n",
+    )
+    .unwrap();
+    dir
+}
+
+fn rt(dir: &TempDir, args: &[&str]) -> Output {
+    let state = tempdir().unwrap();
+    common::rt(dir.path(), state.path())
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn runs(dir: &TempDir) -> usize {
+    std::fs::read_to_string(dir.path().join("runs.txt"))
+        .unwrap()
+        .lines()
+        .count()
+}
+
+#[test]
+fn repeat_stops_at_the_first_failure() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = flaky_project();
+
+    let output = rt(&dir, &["--repeat", "4", "flaky"]);
+
+    assert!(!output.status.success());
+    assert_eq!(runs(&dir), 2);
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("rt: `flaky` passed 1/2 runs (stopped at the first failure of 4)")
+    );
+}
+
+#[test]
+fn repeat_with_keep_going_runs_every_time() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = flaky_project();
+
+    let output = rt(&dir, &["--repeat", "4", "--keep-going", "flaky"]);
+
+    assert!(!output.status.success());
+    assert_eq!(runs(&dir), 4);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("rt: `flaky` passed 3/4 runs"));
+}
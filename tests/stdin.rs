@@ -0,0 +1,61 @@
+use std::io::Write;
+use std::process::Stdio;
+
+use tempfile::tempdir;
+
+mod common;
+
+/// Runs `rt` in `dir` with `input` piped to its stdin and returns its stdout.
+fn run_piped(dir: &std::path::Path, args: &[&str], input: &str) -> (i32, String) {
+    let state = tempdir().unwrap();
+    let mut child = common::rt(dir, state.path())
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn piped_stdin_reaches_the_task() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("Makefile"), "upper:\n\t@tr a-z A-Z\n").unwrap();
+
+    let (code, stdout) = run_piped(dir.path(), &["upper"], "piped data\n");
+
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "PIPED DATA\n");
+}
+
+#[test]
+fn piped_stdin_is_echoed_back_with_passthrough() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Makefile"),
+        "echo:\n\t@cat\n\t@echo \"done $(SUFFIX)\"\n",
+    )
+    .unwrap();
+
+    let (code, stdout) = run_piped(dir.path(), &["echo", "SUFFIX=ok"], "one\ntwo\n");
+
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "one\ntwo\ndone ok\n");
+}
@@ -0,0 +1,32 @@
+use std::process::Stdio;
+
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn task_sees_rt_task_and_rt_command_over_inherited_values() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("Makefile"),
+        "show:\n\t@echo \"$$RT_TASK|$$RT_COMMAND\"\n",
+    )
+    .unwrap();
+
+    let output = common::rt(dir.path(), state.path())
+        .args(["--env", "RT_TASK=from-flag", "show", "V=1"])
+        .env("RT_COMMAND", "stale")
+        .stderr(Stdio::null())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "show|make show V=1\n"
+    );
+}
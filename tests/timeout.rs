@@ -0,0 +1,129 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn timeout_kills_the_task_and_records_elapsed_time() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    // The recipe's shell records the pid of the `sleep` it starts, so the test can check it died.
+    std::fs::write(
+        dir.path().join("Makefile"),
+        "hang:\n\t@sleep 30 & echo $$! > sleep.pid; wait\n",
+    )
+    .unwrap();
+
+    let started = Instant::now();
+    let status = common::rt(dir.path(), state.path())
+        .args(["--timeout", "1", "hang"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+
+    assert_eq!(status.code(), Some(124));
+    assert!(started.elapsed() < Duration::from_secs(20));
+
+    let history = std::fs::read_to_string(state.path().join("rt/history.jsonl")).unwrap();
+    let record: serde_json::Value = serde_json::from_str(history.lines().last().unwrap()).unwrap();
+    assert_eq!(record["exit_code"], 124);
+    let duration_ms = record["duration_ms"].as_u64().unwrap();
+    assert!((1000..20_000).contains(&duration_ms), "{duration_ms}");
+
+    let pid = std::fs::read_to_string(dir.path().join("sleep.pid")).unwrap();
+    assert!(
+        process_exits(pid.trim()),
+        "sleep {} outlived the timeout",
+        pid.trim()
+    );
+}
+
+/// Runs `rt --timeout 10 ask` with `input` on stdin and returns its stdout; `ask` echoes a line.
+fn run_timed_ask(command: &mut Command, dir: &std::path::Path, input: &str) -> String {
+    std::fs::write(
+        dir.join("Makefile"),
+        "ask:\n\t@read line; echo \"got $$line\"\n",
+    )
+    .unwrap();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn timed_task_reads_piped_stdin() {
+    if which::which("make").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let mut command = common::rt(dir.path(), state.path());
+    command.args(["--timeout", "10", "ask"]);
+
+    assert_eq!(
+        run_timed_ask(&mut command, dir.path(), "hello\n"),
+        "got hello\n"
+    );
+}
+
+/// A timed task in its own process group must still be able to read the terminal.
+#[cfg(target_os = "linux")]
+#[test]
+fn timed_task_reads_the_terminal() {
+    if which::which("make").is_err() || which::which("script").is_err() {
+        return;
+    }
+    let dir = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    // `script` runs rt on a new terminal and types its own stdin into it.
+    let mut command = Command::new("script");
+    command
+        .args([
+            "-qec",
+            concat!(env!("CARGO_BIN_EXE_rt"), " --timeout 10 ask"),
+            "/dev/null",
+        ])
+        .current_dir(dir.path())
+        .env("XDG_STATE_HOME", state.path())
+        .env("XDG_CONFIG_HOME", state.path());
+
+    let started = Instant::now();
+    let stdout = run_timed_ask(&mut command, dir.path(), "hello\n");
+    assert!(stdout.contains("got hello"), "{stdout:?}");
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+/// Whether the process `pid` is gone, or a zombie waiting to be reaped, within a couple of seconds.
+fn process_exits(pid: &str) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        let output = Command::new("ps")
+            .args(["-o", "stat=", "-p", pid])
+            .output()
+            .unwrap();
+        let state = String::from_utf8_lossy(&output.stdout);
+        if state.trim().is_empty() || state.trim_start().starts_with('Z') {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    false
+}